@@ -0,0 +1,228 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `fee` module provides the types and methods used to price transactions
+//! and to estimate their fees from the recent blocks.
+
+use constants::{MIN_FEE_PER_BYTE, FEE_ESTIMATION_BLOCKS};
+use error::ErrorKind;
+use result::Result;
+use utils::Amount;
+use crypto::Digest;
+use models::transaction::Transaction;
+use models::block::Block;
+
+use std::collections::VecDeque;
+
+/// Returns the number of bytes paid by a `Transaction`, that is its size plus
+/// the size of its data.
+pub fn get_transaction_weight(transaction: &Transaction) -> Result<u32> {
+    Ok(transaction.size()? + transaction.data_size)
+}
+
+/// Returns the fee paid by a `Transaction` for each of its bytes.
+pub fn get_fee_rate(transaction: &Transaction) -> Result<Amount> {
+    let weight = get_transaction_weight(transaction)?;
+
+    Ok(&transaction.fee / &Amount::from(weight))
+}
+
+/// A `MinimumFee` is the minimum fee per byte accepted for a transaction.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MinimumFee {
+    /// The minimum fee per byte.
+    pub fee_per_byte: Amount,
+}
+
+impl MinimumFee {
+    /// Creates a new `MinimumFee`.
+    pub fn new(fee_per_byte: &Amount) -> MinimumFee {
+        MinimumFee {
+            fee_per_byte: fee_per_byte.clone(),
+        }
+    }
+
+    /// Returns the minimum fee of a given number of bytes.
+    pub fn fee(&self, weight: u32) -> Amount {
+        &self.fee_per_byte * &Amount::from(weight)
+    }
+
+    /// Returns the minimum fee of a `Transaction`.
+    pub fn transaction_fee(&self, transaction: &Transaction) -> Result<Amount> {
+        Ok(self.fee(get_transaction_weight(transaction)?))
+    }
+
+    /// Verifies if a `Transaction` pays at least the minimum fee.
+    pub fn verify_transaction(&self, transaction: &Transaction) -> Result<bool> {
+        Ok(transaction.fee >= self.transaction_fee(transaction)?)
+    }
+}
+
+impl Default for MinimumFee {
+    fn default() -> MinimumFee {
+        MinimumFee::new(&Amount::from(MIN_FEE_PER_BYTE))
+    }
+}
+
+/// A `FeeEstimate` is a set of suggested fees per byte.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    /// The fee per byte confirming in a quarter of the recent cases.
+    pub low: Amount,
+    /// The fee per byte confirming in half of the recent cases.
+    pub medium: Amount,
+    /// The fee per byte confirming in nine tenths of the recent cases.
+    pub high: Amount,
+}
+
+/// A `FeeEstimator` suggests fees from the fees paid in the recent blocks.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FeeEstimator {
+    /// The minimum fee, suggested when there are no better estimates.
+    pub minimum_fee: MinimumFee,
+    /// The maximum number of recent blocks kept.
+    pub max_blocks: u32,
+    /// The ids of the recent blocks with the sorted fees per byte of their transactions.
+    blocks: VecDeque<(Digest, Vec<Amount>)>,
+}
+
+impl FeeEstimator {
+    /// Creates a new `FeeEstimator`.
+    pub fn new(minimum_fee: &MinimumFee) -> FeeEstimator {
+        FeeEstimator {
+            minimum_fee: minimum_fee.clone(),
+            max_blocks: FEE_ESTIMATION_BLOCKS,
+            blocks: VecDeque::new(),
+        }
+    }
+
+    /// Returns the number of recent blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Verifies if there are no recent blocks.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Adds a `Block` and its transactions to the recent blocks.
+    pub fn add_block(&mut self, block: &Block, transactions: &[Transaction]) -> Result<()> {
+        if transactions.len() != block.transactions_ids.len() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let mut fee_rates = Vec::new();
+
+        for (transaction, id) in transactions.iter().zip(block.transactions_ids.iter()) {
+            if transaction.id != *id {
+                return Err(ErrorKind::InvalidID.into());
+            }
+
+            if transaction.is_coinbase()? {
+                continue;
+            }
+
+            fee_rates.push(get_fee_rate(transaction)?);
+        }
+
+        fee_rates.sort();
+
+        self.blocks.push_back((block.id, fee_rates));
+
+        while self.blocks.len() > self.max_blocks as usize {
+            self.blocks.pop_front();
+        }
+
+        Ok(())
+    }
+
+    /// Removes the last added `Block` from the recent blocks, as after a reorg.
+    pub fn revert_block(&mut self, block: &Block) -> Result<()> {
+        match self.blocks.back() {
+            Some(&(id, _)) => {
+                if id != block.id {
+                    return Err(ErrorKind::InvalidChain.into());
+                }
+            },
+            None => return Err(ErrorKind::NotFound.into()),
+        }
+
+        self.blocks.pop_back();
+
+        Ok(())
+    }
+
+    /// Estimates the fees per byte needed to confirm a transaction within a
+    /// given number of blocks.
+    ///
+    /// For every run of consecutive recent blocks as long as the depth, the lowest
+    /// fee per byte paid in any of them would have been enough to confirm within the
+    /// run. The estimates are percentiles of these fees per byte.
+    pub fn estimate(&self, depth: u32) -> Result<FeeEstimate> {
+        if depth == 0 {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        let minimum = &self.minimum_fee.fee_per_byte;
+
+        let blocks_length = self.blocks.len();
+        let depth = (depth as usize).min(blocks_length);
+
+        let mut fee_rates = Vec::new();
+
+        if depth != 0 {
+            for start in 0..blocks_length - depth + 1 {
+                let mut fee_rate: Option<&Amount> = None;
+
+                for (_, block_fee_rates) in self.blocks.iter().skip(start).take(depth) {
+                    // a block with no transactions would have accepted any fee
+                    let block_fee_rate = block_fee_rates.first().unwrap_or(minimum);
+
+                    let is_lower = match fee_rate {
+                        Some(fee_rate) => block_fee_rate < fee_rate,
+                        None => true,
+                    };
+
+                    if is_lower {
+                        fee_rate = Some(block_fee_rate);
+                    }
+                }
+
+                fee_rates.push(fee_rate.unwrap().clone());
+            }
+        }
+
+        fee_rates.sort();
+
+        let percentile = |n: usize| -> Amount {
+            if fee_rates.is_empty() {
+                return minimum.clone();
+            }
+
+            let fee_rate = &fee_rates[(fee_rates.len() - 1) * n / 100];
+
+            if fee_rate < minimum {
+                minimum.clone()
+            } else {
+                fee_rate.clone()
+            }
+        };
+
+        Ok(FeeEstimate {
+            low: percentile(25),
+            medium: percentile(50),
+            high: percentile(90),
+        })
+    }
+}
+
+impl Default for FeeEstimator {
+    fn default() -> FeeEstimator {
+        FeeEstimator::new(&MinimumFee::default())
+    }
+}
@@ -0,0 +1,405 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `header_tree` module provides the header tree type and methods, which
+//! keeps track of the competing chains of block headers and selects the best one.
+
+use constants::{MAX_ORPHANS, MEDIAN_TIME_SPAN};
+use error::ErrorKind;
+use result::Result;
+use traits::Validate;
+use utils::{NetworkType, Timestamp};
+use crypto::{Random, Digest, Memory};
+use models::block_header::{BlockHeader, get_pow_work};
+
+use std::collections::BTreeMap;
+
+/// Returns the work done to mine a `BlockHeader`, that is the memory spent
+/// by each hash times the expected number of hashes.
+pub fn get_work(block_header: &BlockHeader) -> Memory {
+    get_pow_work(block_header.pow_difficulty, &block_header.pow_memory)
+}
+
+/// A `BlockHeader` connected to the tree, with the cumulative work of its chain.
+#[derive(Clone, PartialEq, Debug)]
+struct HeaderNode {
+    /// The block header.
+    header: BlockHeader,
+    /// The work of the chain ending with the block header.
+    work: Memory,
+}
+
+/// A `Reorg` is the change of the best chain after adding block headers to a `HeaderTree`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Reorg {
+    /// The block headers removed from the best chain, from the old tip backwards.
+    pub detached: Vec<BlockHeader>,
+    /// The block headers added to the best chain, up to the new tip.
+    pub attached: Vec<BlockHeader>,
+}
+
+impl Reorg {
+    /// Verifies if the `Reorg` only extends the best chain.
+    pub fn is_extension(&self) -> bool {
+        self.detached.is_empty()
+    }
+}
+
+/// A `HeaderTree` is the tree of the known block headers, rooted at the genesis.
+#[derive(Clone, PartialEq, Debug)]
+pub struct HeaderTree {
+    /// The network type of the tree.
+    pub network_type: NetworkType,
+    /// The maximum number of orphan block headers.
+    pub max_orphans: u32,
+    /// The block headers connected to the genesis, by id.
+    nodes: BTreeMap<Digest, HeaderNode>,
+    /// The block headers waiting for their parent, by id.
+    orphans: BTreeMap<Digest, BlockHeader>,
+    /// The ids of the orphan block headers, by previous id.
+    orphans_by_prev_id: BTreeMap<Digest, Vec<Digest>>,
+    /// The id of the genesis, if any.
+    genesis: Option<Digest>,
+    /// The id of the tip of the best chain, if any.
+    tip: Option<Digest>,
+}
+
+impl HeaderTree {
+    /// Creates a new empty `HeaderTree`.
+    pub fn new(network_type: NetworkType) -> HeaderTree {
+        HeaderTree {
+            network_type: network_type,
+            max_orphans: MAX_ORPHANS,
+            nodes: BTreeMap::new(),
+            orphans: BTreeMap::new(),
+            orphans_by_prev_id: BTreeMap::new(),
+            genesis: None,
+            tip: None,
+        }
+    }
+
+    /// Returns the genesis `BlockHeader`, if any.
+    pub fn genesis(&self) -> Option<&BlockHeader> {
+        self.genesis.map(|id| &self.nodes[&id].header)
+    }
+
+    /// Returns the `BlockHeader` at the tip of the best chain, if any.
+    pub fn tip(&self) -> Option<&BlockHeader> {
+        self.tip.map(|id| &self.nodes[&id].header)
+    }
+
+    /// Returns the height of the tip of the best chain, if any.
+    pub fn height(&self) -> Option<u32> {
+        self.tip().map(|header| header.height)
+    }
+
+    /// Returns the cumulative work of the best chain, if any.
+    pub fn tip_work(&self) -> Option<&Memory> {
+        self.tip.map(|id| &self.nodes[&id].work)
+    }
+
+    /// Returns the cumulative work of the chain ending with a `BlockHeader`, if any.
+    pub fn work(&self, id: Digest) -> Option<&Memory> {
+        self.nodes.get(&id).map(|node| &node.work)
+    }
+
+    /// Returns the number of block headers connected to the genesis.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Verifies if there are no block headers connected to the genesis.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the number of orphan block headers.
+    pub fn orphans_len(&self) -> usize {
+        self.orphans.len()
+    }
+
+    /// Returns a `BlockHeader` connected to the genesis, if any.
+    pub fn get(&self, id: Digest) -> Option<&BlockHeader> {
+        self.nodes.get(&id).map(|node| &node.header)
+    }
+
+    /// Verifies if a `BlockHeader` is connected to the genesis.
+    pub fn contains(&self, id: Digest) -> bool {
+        self.nodes.contains_key(&id)
+    }
+
+    /// Verifies if a `BlockHeader` is waiting for its parent.
+    pub fn is_orphan(&self, id: Digest) -> bool {
+        self.orphans.contains_key(&id)
+    }
+
+    /// Returns the ancestor at a given height of a `BlockHeader`, if any.
+    pub fn ancestor(&self, id: Digest, height: u32) -> Option<&BlockHeader> {
+        let mut header = self.get(id)?;
+
+        if height > header.height {
+            return None;
+        }
+
+        while header.height > height {
+            header = self.get(header.prev_id)?;
+        }
+
+        Some(header)
+    }
+
+    /// Returns the ancestors used to verify the timestamp of a `BlockHeader`, that is
+    /// the last `MEDIAN_TIME_SPAN` block headers of its chain, sorted by height.
+    pub fn ancestors(&self, block_header: &BlockHeader) -> Result<Vec<BlockHeader>> {
+        let window_length = block_header.height.min(MEDIAN_TIME_SPAN);
+
+        let mut ancestors = Vec::new();
+        let mut prev_id = block_header.prev_id;
+
+        for _ in 0..window_length {
+            let ancestor = match self.get(prev_id) {
+                Some(ancestor) => ancestor,
+                None => return Err(ErrorKind::NotFound.into()),
+            };
+
+            prev_id = ancestor.prev_id;
+            ancestors.push(ancestor.clone());
+        }
+
+        ancestors.reverse();
+
+        Ok(ancestors)
+    }
+
+    /// Verifies if a `BlockHeader` is in the best chain.
+    pub fn is_best_chain(&self, id: Digest) -> bool {
+        let height = match self.get(id) {
+            Some(header) => header.height,
+            None => return false,
+        };
+
+        match self.tip {
+            Some(tip) => self.ancestor(tip, height).map(|header| header.id) == Some(id),
+            None => false,
+        }
+    }
+
+    /// Adds a `BlockHeader` to the `HeaderTree`, returning the `Reorg` of the best
+    /// chain, if the best chain changed.
+    pub fn add_block_header(&mut self, block_header: &BlockHeader) -> Result<Option<Reorg>> {
+        let id = block_header.id;
+
+        if self.nodes.contains_key(&id) || self.orphans.contains_key(&id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        if block_header.network_type != self.network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        block_header.validate()?;
+
+        if block_header.height == 0 {
+            if self.genesis.is_some() || !block_header.is_genesis()? {
+                return Err(ErrorKind::InvalidGenesis.into());
+            }
+
+            self.genesis = Some(id);
+        } else if !self.nodes.contains_key(&block_header.prev_id) {
+            self.add_orphan(block_header)?;
+
+            return Ok(None);
+        }
+
+        let old_tip = self.tip;
+
+        if let Err(err) = self.connect(block_header.clone()) {
+            self.discard_orphans(id);
+
+            return Err(err);
+        }
+
+        // connect the orphans waiting for the new block header and for its descendants
+        let mut parents = vec![id];
+
+        while let Some(parent_id) = parents.pop() {
+            let children_ids = match self.orphans_by_prev_id.remove(&parent_id) {
+                Some(children_ids) => children_ids,
+                None => continue,
+            };
+
+            for child_id in children_ids {
+                let child = self.orphans.remove(&child_id).unwrap();
+
+                // an invalid orphan is discarded with its descendants, it does not
+                // invalidate its parent
+                if self.connect(child).is_ok() {
+                    parents.push(child_id);
+                } else {
+                    self.discard_orphans(child_id);
+                }
+            }
+        }
+
+        if self.tip == old_tip {
+            return Ok(None);
+        }
+
+        Ok(Some(self.reorg(old_tip, self.tip.unwrap())))
+    }
+
+    /// Adds a `BlockHeader` to the orphans. When there are `max_orphans` orphans, random
+    /// orphans are evicted together with their descendants.
+    fn add_orphan(&mut self, block_header: &BlockHeader) -> Result<()> {
+        if self.max_orphans == 0 {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        while self.orphans.len() >= self.max_orphans as usize {
+            let index = Random::u32_range(0..self.orphans.len() as u32)? as usize;
+            let evicted_id = *self.orphans.keys().nth(index).unwrap();
+
+            self.remove_orphan(evicted_id);
+        }
+
+        self.orphans_by_prev_id.entry(block_header.prev_id)
+            .or_default()
+            .push(block_header.id);
+        self.orphans.insert(block_header.id, block_header.clone());
+
+        Ok(())
+    }
+
+    /// Removes an orphan `BlockHeader` and its descendants.
+    fn remove_orphan(&mut self, id: Digest) {
+        let prev_id = match self.orphans.remove(&id) {
+            Some(block_header) => block_header.prev_id,
+            None => return,
+        };
+
+        let is_empty = match self.orphans_by_prev_id.get_mut(&prev_id) {
+            Some(siblings_ids) => {
+                siblings_ids.retain(|sibling_id| *sibling_id != id);
+                siblings_ids.is_empty()
+            },
+            None => false,
+        };
+
+        if is_empty {
+            self.orphans_by_prev_id.remove(&prev_id);
+        }
+
+        self.discard_orphans(id);
+    }
+
+    /// Discards the orphans waiting for a `BlockHeader`, and their descendants.
+    fn discard_orphans(&mut self, id: Digest) {
+        let mut parents = vec![id];
+
+        while let Some(parent_id) = parents.pop() {
+            let children_ids = match self.orphans_by_prev_id.remove(&parent_id) {
+                Some(children_ids) => children_ids,
+                None => continue,
+            };
+
+            for child_id in children_ids {
+                self.orphans.remove(&child_id);
+                parents.push(child_id);
+            }
+        }
+    }
+
+    /// Connects a `BlockHeader` to its parent, updating the tip if its chain
+    /// has more work than the best chain. The `BlockHeader` PoW, retarget and
+    /// timestamp are verified against its ancestors, as the work of its chain
+    /// is computed from the difficulty and memory it declares.
+    fn connect(&mut self, block_header: BlockHeader) -> Result<()> {
+        let mut work = get_work(&block_header);
+
+        if block_header.height != 0 {
+            let parent = &self.nodes[&block_header.prev_id];
+
+            if !block_header.verify_prev(&parent.header)? {
+                return Err(ErrorKind::InvalidChain.into());
+            }
+
+            if block_header.timestamp < parent.header.timestamp {
+                return Err(ErrorKind::InvalidTimestamp.into());
+            }
+
+            let ancestors = self.ancestors(&block_header)?;
+
+            if !block_header.verify_timestamp(&ancestors, Timestamp::now())? {
+                return Err(ErrorKind::InvalidTimestamp.into());
+            }
+
+            work += &parent.work;
+        }
+
+        // on equal work the first seen chain is kept
+        let is_best = match self.tip_work() {
+            Some(tip_work) => work > *tip_work,
+            None => true,
+        };
+
+        let id = block_header.id;
+
+        self.nodes.insert(id, HeaderNode {
+            header: block_header,
+            work: work,
+        });
+
+        if is_best {
+            self.tip = Some(id);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `Reorg` from an old tip to a new tip.
+    fn reorg(&self, old_tip: Option<Digest>, new_tip: Digest) -> Reorg {
+        let mut reorg = Reorg::default();
+
+        let mut new_header = &self.nodes[&new_tip].header;
+
+        if let Some(old_tip) = old_tip {
+            let mut old_header = &self.nodes[&old_tip].header;
+
+            while old_header.height > new_header.height {
+                reorg.detached.push(old_header.clone());
+                old_header = &self.nodes[&old_header.prev_id].header;
+            }
+
+            while new_header.height > old_header.height {
+                reorg.attached.push(new_header.clone());
+                new_header = &self.nodes[&new_header.prev_id].header;
+            }
+
+            while old_header.id != new_header.id {
+                reorg.detached.push(old_header.clone());
+                old_header = &self.nodes[&old_header.prev_id].header;
+
+                reorg.attached.push(new_header.clone());
+                new_header = &self.nodes[&new_header.prev_id].header;
+            }
+        } else {
+            loop {
+                reorg.attached.push(new_header.clone());
+
+                if new_header.height == 0 {
+                    break;
+                }
+
+                new_header = &self.nodes[&new_header.prev_id].header;
+            }
+        }
+
+        reorg.attached.reverse();
+
+        reorg
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `light_client` module provides the light client type and methods, which keeps
+//! only the chain of block headers and verifies the inclusion of transactions and
+//! outputs in its blocks through Merkle proofs.
+
+use error::ErrorKind;
+use result::Result;
+use traits::Validate;
+use utils::{NetworkType, MerkleProof};
+use crypto::Digest;
+use models::output::Output;
+use models::transaction::Transaction;
+use models::block_header::BlockHeader;
+use chain::header_tree::{HeaderTree, Reorg};
+
+/// A `LightClient` keeps the block headers of the chain, without their blocks.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LightClient {
+    /// The tree of the verified block headers.
+    tree: HeaderTree,
+}
+
+impl LightClient {
+    /// Creates a new `LightClient` from the genesis `BlockHeader`.
+    pub fn new(genesis: &BlockHeader) -> Result<LightClient> {
+        let mut tree = HeaderTree::new(genesis.network_type);
+        tree.add_block_header(genesis)?;
+
+        let light_client = LightClient {
+            tree: tree,
+        };
+
+        Ok(light_client)
+    }
+
+    /// Returns the network type of the `LightClient`.
+    pub fn network_type(&self) -> NetworkType {
+        self.tree.network_type
+    }
+
+    /// Returns the `HeaderTree` of the verified block headers.
+    pub fn tree(&self) -> &HeaderTree {
+        &self.tree
+    }
+
+    /// Returns the `BlockHeader` at the tip of the best chain.
+    pub fn tip(&self) -> &BlockHeader {
+        self.tree.tip().unwrap()
+    }
+
+    /// Returns the height of the tip of the best chain.
+    pub fn height(&self) -> u32 {
+        self.tip().height
+    }
+
+    /// Verifies and adds a `BlockHeader` following a known one, returning the `Reorg`
+    /// of the best chain, if the best chain changed. The `BlockHeader` PoW, retarget
+    /// and timestamp are verified against its ancestors by the `HeaderTree`.
+    pub fn add_block_header(&mut self, block_header: &BlockHeader) -> Result<Option<Reorg>> {
+        block_header.validate()?;
+
+        if self.tree.contains(block_header.id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        if !self.tree.contains(block_header.prev_id) {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        self.tree.add_block_header(block_header)
+    }
+
+    /// Returns the number of confirmations of a `BlockHeader`, that is its depth in the
+    /// best chain counting itself, or 0 if it is not in the best chain.
+    pub fn confirmations(&self, block_header_id: Digest) -> u32 {
+        if !self.tree.is_best_chain(block_header_id) {
+            return 0;
+        }
+
+        self.height() - self.tree.get(block_header_id).unwrap().height + 1
+    }
+
+    /// Verifies that a `Transaction` is included in the block of a known `BlockHeader`,
+    /// returning the number of confirmations of the block.
+    pub fn verify_transaction(&self,
+                              block_header_id: Digest,
+                              transaction: &Transaction,
+                              proof: &MerkleProof) -> Result<u32> {
+        transaction.validate()?;
+
+        if transaction.network_type != self.network_type() {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        let block_header = match self.tree.get(block_header_id) {
+            Some(block_header) => block_header,
+            None => return Err(ErrorKind::NotFound.into()),
+        };
+
+        if proof.leaf != transaction.id {
+            return Err(ErrorKind::InvalidTransaction.into());
+        }
+
+        if !block_header.verify_transaction(proof)? {
+            return Err(ErrorKind::InvalidProof.into());
+        }
+
+        Ok(self.confirmations(block_header_id))
+    }
+
+    /// Verifies that an `Output` is created by a `Transaction` included in the block
+    /// of a known `BlockHeader`, returning the number of confirmations of the block.
+    pub fn verify_output(&self,
+                         block_header_id: Digest,
+                         output: &Output,
+                         transaction: &Transaction,
+                         proof: &MerkleProof) -> Result<u32> {
+        if !transaction.outputs.contains(output) {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        self.verify_transaction(block_header_id, transaction, proof)
+    }
+
+    /// Verifies that an `Output` is the coinbase output of a known `BlockHeader`,
+    /// returning the number of confirmations of the block.
+    pub fn verify_coinbase_output(&self, block_header_id: Digest, output: &Output) -> Result<u32> {
+        let block_header = match self.tree.get(block_header_id) {
+            Some(block_header) => block_header,
+            None => return Err(ErrorKind::NotFound.into()),
+        };
+
+        if block_header.coinbase_output != *output {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        Ok(self.confirmations(block_header_id))
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `mempool` module provides the mempool type and methods, which keeps the
+//! transactions waiting to be included in a block.
+
+use constants::{MAX_MEMPOOL_WEIGHT, MEMPOOL_EXPIRY_TIME};
+use error::ErrorKind;
+use result::Result;
+use utils::{Amount, NetworkType, Timestamp};
+use crypto::Digest;
+use models::transaction::Transaction;
+use chain::state::ChainState;
+use chain::fee::{MinimumFee, get_transaction_weight, get_fee_rate};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A `MempoolEntry` is a `Transaction` kept in the mempool.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MempoolEntry {
+    /// The transaction.
+    pub transaction: Transaction,
+    /// The size of the transaction.
+    pub size: u32,
+    /// The size of the transaction plus the size of its data.
+    pub weight: u32,
+    /// The fee paid for every byte of the transaction and of its data.
+    pub fee_rate: Amount,
+    /// The time the transaction was added to the mempool.
+    pub timestamp: Timestamp,
+}
+
+/// A `Mempool` keeps the valid transactions not yet included in a block.
+/// Only transactions spending outputs of the chain are accepted.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mempool {
+    /// The network type of the mempool.
+    pub network_type: NetworkType,
+    /// The minimum fee accepted.
+    pub minimum_fee: MinimumFee,
+    /// The maximum total weight of the transactions.
+    pub max_weight: u32,
+    /// The seconds after which a transaction is dropped.
+    pub expiry_time: u32,
+    /// The entries, by transaction id.
+    entries: BTreeMap<Digest, MempoolEntry>,
+    /// The transactions ids, by spent output id.
+    spends: BTreeMap<Digest, Digest>,
+    /// The transactions ids, sorted by fee rate.
+    fee_rates: BTreeSet<(Amount, Digest)>,
+    /// The total weight of the transactions.
+    weight: u32,
+}
+
+impl Mempool {
+    /// Creates a new empty `Mempool`.
+    pub fn new(network_type: NetworkType) -> Mempool {
+        Mempool {
+            network_type: network_type,
+            minimum_fee: MinimumFee::default(),
+            max_weight: MAX_MEMPOOL_WEIGHT,
+            expiry_time: MEMPOOL_EXPIRY_TIME,
+            entries: BTreeMap::new(),
+            spends: BTreeMap::new(),
+            fee_rates: BTreeSet::new(),
+            weight: 0,
+        }
+    }
+
+    /// Returns the number of transactions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Verifies if there are no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total weight of the transactions.
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    /// Returns an entry, if any.
+    pub fn get(&self, id: Digest) -> Option<&MempoolEntry> {
+        self.entries.get(&id)
+    }
+
+    /// Verifies if a transaction is in the `Mempool`.
+    pub fn contains(&self, id: Digest) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    /// Returns the id of the transaction spending an output, if any.
+    pub fn spender(&self, output_id: Digest) -> Option<Digest> {
+        self.spends.get(&output_id).cloned()
+    }
+
+    /// Adds a `Transaction`, verifying it against the `ChainState`. When the
+    /// `Mempool` is full the transactions with the lowest fee rate are evicted.
+    /// If the `Transaction` itself would be evicted, the `Mempool` is left unchanged.
+    pub fn add_transaction(&mut self, transaction: &Transaction, state: &ChainState) -> Result<()> {
+        let id = transaction.id;
+
+        if self.entries.contains_key(&id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        if transaction.network_type != self.network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        for input in &transaction.inputs {
+            if self.spends.contains_key(&input.id) {
+                return Err(ErrorKind::DoubleSpend.into());
+            }
+        }
+
+        let height = state.height().map_or(0, |height| height + 1);
+        state.verify_transaction(transaction, height)?;
+
+        if !self.minimum_fee.verify_transaction(transaction)? {
+            return Err(ErrorKind::InvalidFee.into());
+        }
+
+        let entry = MempoolEntry {
+            transaction: transaction.clone(),
+            size: transaction.size()?,
+            weight: get_transaction_weight(transaction)?,
+            fee_rate: get_fee_rate(transaction)?,
+            timestamp: Timestamp::now(),
+        };
+
+        self.insert(entry);
+
+        let mut evicted = Vec::new();
+
+        while self.weight > self.max_weight {
+            let lowest_id = self.fee_rates.iter().next().unwrap().1;
+
+            let lowest = self.remove(lowest_id).unwrap();
+
+            if lowest_id == id {
+                for entry in evicted {
+                    self.insert(entry);
+                }
+
+                return Err(ErrorKind::OutOfBound.into());
+            }
+
+            evicted.push(lowest);
+        }
+
+        Ok(())
+    }
+
+    /// Removes a `Transaction`, if any.
+    pub fn remove_transaction(&mut self, id: Digest) -> Option<Transaction> {
+        self.remove(id).map(|entry| entry.transaction)
+    }
+
+    /// Removes the transactions included in a block and the ones conflicting with
+    /// them. Returns the removed conflicting transactions.
+    pub fn remove_block_transactions(&mut self, transactions: &[Transaction]) -> Vec<Transaction> {
+        let mut conflicts = Vec::new();
+
+        for transaction in transactions {
+            self.remove(transaction.id);
+
+            for input in &transaction.inputs {
+                if let Some(spender_id) = self.spender(input.id) {
+                    if let Some(entry) = self.remove(spender_id) {
+                        conflicts.push(entry.transaction);
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Removes the transactions older than the expiry time. Returns the
+    /// removed transactions.
+    pub fn expire(&mut self, now: Timestamp) -> Vec<Transaction> {
+        let expired_ids: Vec<Digest> = self.entries.values()
+            .filter(|entry| now.diff(entry.timestamp) >= i64::from(self.expiry_time))
+            .map(|entry| entry.transaction.id)
+            .collect();
+
+        expired_ids.into_iter()
+            .filter_map(|id| self.remove(id))
+            .map(|entry| entry.transaction)
+            .collect()
+    }
+
+    /// Returns the transactions with the highest fee rates whose total size fits in
+    /// a given size, ready to be included in a `Block`.
+    pub fn block_template(&self, max_size: u32) -> Vec<Transaction> {
+        let mut transactions = Vec::new();
+        let mut size = 0;
+
+        for &(_, id) in self.fee_rates.iter().rev() {
+            let entry = &self.entries[&id];
+
+            if size + entry.size > max_size {
+                continue;
+            }
+
+            size += entry.size;
+            transactions.push(entry.transaction.clone());
+        }
+
+        transactions
+    }
+
+    /// Adds an entry to the indexes.
+    fn insert(&mut self, entry: MempoolEntry) {
+        let id = entry.transaction.id;
+
+        for input in &entry.transaction.inputs {
+            self.spends.insert(input.id, id);
+        }
+
+        self.fee_rates.insert((entry.fee_rate.clone(), id));
+        self.weight += entry.weight;
+        self.entries.insert(id, entry);
+    }
+
+    /// Removes an entry from the indexes.
+    fn remove(&mut self, id: Digest) -> Option<MempoolEntry> {
+        let entry = self.entries.remove(&id)?;
+
+        for input in &entry.transaction.inputs {
+            self.spends.remove(&input.id);
+        }
+
+        self.fee_rates.remove(&(entry.fee_rate.clone(), id));
+        self.weight -= entry.weight;
+
+        Some(entry)
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `chain` module provides the types and methods used to keep track of the blockchain.
+
+pub mod state;
+pub mod header_tree;
+pub mod fee;
+pub mod mempool;
+pub mod light_client;
+
+pub use self::state::*;
+pub use self::header_tree::*;
+pub use self::fee::*;
+pub use self::mempool::*;
+pub use self::light_client::*;
@@ -0,0 +1,373 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `state` module provides the chain state type and methods, which keeps
+//! track of the unspent outputs of the blockchain.
+
+use constants::MATURITY_TIME;
+use error::ErrorKind;
+use result::{Result, ResultExt};
+use traits::Validate;
+use utils::{Amount, NetworkType};
+use crypto::Digest;
+use models::output::Output;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+
+use std::collections::BTreeMap;
+
+/// An `UnspentOutput` is an `Output` not yet spent by any `Transaction` in the chain.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct UnspentOutput {
+    /// The unspent output.
+    pub output: Output,
+    /// The height of the block that created the output.
+    pub height: u32,
+    /// If the output was created by a coinbase.
+    pub is_coinbase: bool,
+}
+
+impl UnspentOutput {
+    /// Verifies if the `UnspentOutput` can be spent at a given height.
+    pub fn is_mature(&self, height: u32, maturity_time: u32) -> bool {
+        !self.is_coinbase || height >= self.height.saturating_add(maturity_time)
+    }
+}
+
+/// The changes applied by a block, used to revert it.
+#[derive(Clone, PartialEq, Debug)]
+struct BlockUndo {
+    /// The header of the applied block.
+    header: BlockHeader,
+    /// The outputs spent by the block.
+    spent: Vec<UnspentOutput>,
+    /// The ids of the outputs created by the block.
+    created: Vec<Digest>,
+}
+
+/// A `ChainState` is the set of unspent outputs at the tip of the chain.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChainState {
+    /// The network type of the chain.
+    pub network_type: NetworkType,
+    /// The number of heights to pass before a coinbase output can be spent.
+    pub maturity_time: u32,
+    /// The unspent outputs by id, sorted by height. Identical outputs, as the
+    /// coinbase outputs of blocks paying the same amount to the same witness,
+    /// share their id and are spent interchangeably.
+    unspent: BTreeMap<Digest, Vec<UnspentOutput>>,
+    /// The undo data of the applied blocks, from the genesis to the tip.
+    undos: Vec<BlockUndo>,
+}
+
+impl ChainState {
+    /// Creates a new empty `ChainState`.
+    pub fn new(network_type: NetworkType) -> ChainState {
+        ChainState {
+            network_type: network_type,
+            maturity_time: MATURITY_TIME,
+            unspent: BTreeMap::new(),
+            undos: Vec::new(),
+        }
+    }
+
+    /// Returns the `BlockHeader` at the tip of the chain, if any.
+    pub fn tip(&self) -> Option<&BlockHeader> {
+        self.undos.last().map(|undo| &undo.header)
+    }
+
+    /// Returns the height of the tip of the chain, if any.
+    pub fn height(&self) -> Option<u32> {
+        self.tip().map(|header| header.height)
+    }
+
+    /// Returns the number of unspent outputs.
+    pub fn len(&self) -> usize {
+        self.unspent.values().map(|outputs| outputs.len()).sum()
+    }
+
+    /// Verifies if there are no unspent outputs.
+    pub fn is_empty(&self) -> bool {
+        self.unspent.is_empty()
+    }
+
+    /// Returns an unspent output, if any. Of identical outputs, the oldest
+    /// is returned, which is the next to be spent.
+    pub fn get(&self, id: Digest) -> Option<&UnspentOutput> {
+        self.unspent.get(&id).and_then(|outputs| outputs.first())
+    }
+
+    /// Verifies if an output is unspent.
+    pub fn contains(&self, id: Digest) -> bool {
+        self.unspent.contains_key(&id)
+    }
+
+    /// Returns the unspent outputs.
+    pub fn unspent_outputs(&self) -> Vec<UnspentOutput> {
+        self.unspent.values().flat_map(|outputs| outputs.iter().cloned()).collect()
+    }
+
+    /// Returns the total amount of the unspent outputs.
+    pub fn balance(&self) -> Amount {
+        let mut balance = Amount::new();
+
+        for unspent in self.unspent.values().flat_map(|outputs| outputs.iter()) {
+            balance += &unspent.output.amount;
+        }
+
+        balance
+    }
+
+    /// Verifies a `Transaction` against the unspent outputs, given the height
+    /// of the block that would include it.
+    pub fn verify_transaction(&self, transaction: &Transaction, height: u32) -> Result<()> {
+        transaction.validate()?;
+
+        if transaction.network_type != self.network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        if transaction.is_coinbase()? {
+            return Err(ErrorKind::InvalidTransaction.into());
+        }
+
+        let mut inputs_amount = Amount::new();
+
+        for (index, input) in transaction.inputs.iter().enumerate() {
+            let index = index as u32;
+
+            let unspent = match self.get(input.id) {
+                Some(unspent) => unspent,
+                None => return Err(ErrorKind::NotFound.with_index("inputs", index)),
+            };
+
+            if !unspent.is_mature(height, self.maturity_time) {
+                return Err(ErrorKind::ImmatureCoinbase.with_index("inputs", index));
+            }
+
+            if !input.verify(&unspent.output).with_index("inputs", index)? {
+                return Err(ErrorKind::InvalidProof.with_index("inputs", index));
+            }
+
+            inputs_amount += &unspent.output.amount;
+        }
+
+        if inputs_amount != transaction.total_amount() {
+            return Err(ErrorKind::InvalidBalance.with_values(&inputs_amount, transaction.total_amount()));
+        }
+
+        Ok(())
+    }
+
+    /// Applies a `Block` and its transactions to the `ChainState`.
+    pub fn apply_block(&mut self,
+                       block_header: &BlockHeader,
+                       block: &Block,
+                       transactions: &[Transaction]) -> Result<()> {
+        if block_header.network_type != self.network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        if !block_header.verify(block, transactions, self.tip())? {
+            return Err(ErrorKind::InvalidBlock.into());
+        }
+
+        let mut spent = Vec::new();
+        let mut created = Vec::new();
+
+        let res = self.apply_transactions(block_header, transactions, &mut spent, &mut created);
+
+        if res.is_err() {
+            self.undo(spent, created);
+
+            return res;
+        }
+
+        self.undos.push(BlockUndo {
+            header: block_header.clone(),
+            spent: spent,
+            created: created,
+        });
+
+        Ok(())
+    }
+
+    /// Applies the transactions of a block, keeping track of the spent and created
+    /// outputs.
+    fn apply_transactions(&mut self,
+                          block_header: &BlockHeader,
+                          transactions: &[Transaction],
+                          spent: &mut Vec<UnspentOutput>,
+                          created: &mut Vec<Digest>) -> Result<()> {
+        let height = block_header.height;
+        let is_genesis = block_header.is_genesis()?;
+
+        for (index, transaction) in transactions.iter().enumerate() {
+            self.apply_transaction(transaction, height, is_genesis, spent, created)
+                .with_index("transactions", index as u32)?;
+        }
+
+        // the genesis coinbase output is already created by the genesis transaction
+        if !is_genesis {
+            self.create_output(created, &block_header.coinbase_output, height, true)?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies a transaction of a block, keeping track of the spent and created
+    /// outputs.
+    fn apply_transaction(&mut self,
+                         transaction: &Transaction,
+                         height: u32,
+                         is_genesis: bool,
+                         spent: &mut Vec<UnspentOutput>,
+                         created: &mut Vec<Digest>) -> Result<()> {
+        transaction.validate()?;
+
+        if transaction.network_type != self.network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        let is_coinbase = transaction.is_coinbase()?;
+
+        // only the genesis can mint through a transaction, the other blocks
+        // mint through the coinbase output of their header
+        if is_coinbase && !is_genesis {
+            return Err(ErrorKind::InvalidTransaction.into());
+        }
+
+        if !is_coinbase {
+            let mut inputs_amount = Amount::new();
+
+            for (index, input) in transaction.inputs.iter().enumerate() {
+                let index = index as u32;
+
+                let output = match self.remove_output(input.id) {
+                    Some(output) => output,
+                    None => {
+                        if spent.iter().any(|output| output.output.id == input.id) {
+                            return Err(ErrorKind::DoubleSpend.with_index("inputs", index));
+                        }
+
+                        return Err(ErrorKind::NotFound.with_index("inputs", index));
+                    },
+                };
+
+                let is_mature = output.is_mature(height, self.maturity_time);
+                let is_verified = input.verify(&output.output);
+
+                spent.push(output);
+
+                if !is_mature {
+                    return Err(ErrorKind::ImmatureCoinbase.with_index("inputs", index));
+                }
+
+                if !is_verified.with_index("inputs", index)? {
+                    return Err(ErrorKind::InvalidProof.with_index("inputs", index));
+                }
+
+                inputs_amount += &spent[spent.len() - 1].output.amount;
+            }
+
+            if inputs_amount != transaction.total_amount() {
+                return Err(ErrorKind::InvalidBalance.with_values(&inputs_amount, transaction.total_amount()));
+            }
+        }
+
+        for output in &transaction.outputs {
+            self.create_output(created, output, height, is_coinbase)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reverts the `Block` at the tip of the `ChainState`.
+    pub fn revert_block(&mut self, block_header: &BlockHeader) -> Result<()> {
+        match self.tip() {
+            Some(tip) => {
+                if tip.id != block_header.id {
+                    return Err(ErrorKind::InvalidChain.into());
+                }
+            },
+            None => return Err(ErrorKind::NotFound.into()),
+        }
+
+        let undo = self.undos.pop().unwrap();
+
+        self.undo(undo.spent, undo.created);
+
+        Ok(())
+    }
+
+    /// Removes the created outputs and restores the spent ones.
+    fn undo(&mut self, spent: Vec<UnspentOutput>, created: Vec<Digest>) {
+        // the spent outputs go first, as some may have been created by the same block
+        for output in spent {
+            self.insert_output(output);
+        }
+
+        // the created outputs are the newest of their id
+        for id in created {
+            let is_empty = match self.unspent.get_mut(&id) {
+                Some(outputs) => {
+                    outputs.pop();
+                    outputs.is_empty()
+                },
+                None => false,
+            };
+
+            if is_empty {
+                self.unspent.remove(&id);
+            }
+        }
+    }
+
+    /// Inserts an unspent output after the older identical outputs.
+    fn insert_output(&mut self, output: UnspentOutput) {
+        let outputs = self.unspent.entry(output.output.id).or_default();
+        let index = outputs.iter()
+            .position(|other| other.height > output.height)
+            .unwrap_or(outputs.len());
+
+        outputs.insert(index, output);
+    }
+
+    /// Removes the oldest unspent output of an id, if any.
+    fn remove_output(&mut self, id: Digest) -> Option<UnspentOutput> {
+        let (output, is_empty) = match self.unspent.get_mut(&id) {
+            Some(outputs) => (outputs.remove(0), outputs.is_empty()),
+            None => return None,
+        };
+
+        if is_empty {
+            self.unspent.remove(&id);
+        }
+
+        Some(output)
+    }
+
+    /// Adds a new unspent output.
+    fn create_output(&mut self,
+                     created: &mut Vec<Digest>,
+                     output: &Output,
+                     height: u32,
+                     is_coinbase: bool) -> Result<()> {
+        output.validate()?;
+
+        self.insert_output(UnspentOutput {
+            output: output.clone(),
+            height: height,
+            is_coinbase: is_coinbase,
+        });
+
+        created.push(output.id);
+
+        Ok(())
+    }
+}
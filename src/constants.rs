@@ -22,9 +22,26 @@ pub const MIN_DATETIME: &str = "2018-01-18T00:00:00Z";
 /// The maximum accepted error noise of time measures. The internet is messy.
 pub const MAX_TIMENOISE: i64 = 3_600;
 
+/// The number of previous block headers whose median timestamp a block header
+/// timestamp has to be above.
+pub const MEDIAN_TIME_SPAN: u32 = 11;
+
 /// The genesis output amount.
 pub const GENESIS_AMOUNT: u32 = 21;
 
+/// The number of decimal places of the amounts.
+pub const AMOUNT_DECIMALS: u32 = 8;
+
+/// The number of base units of a coin, 10 to the power of `AMOUNT_DECIMALS`.
+pub const AMOUNT_UNIT: u64 = 100_000_000;
+
+/// The symbol of the coin.
+pub const AMOUNT_SYMBOL: &str = "YBC";
+
+/// The maximum supply of coins in base units. It is below 2^53, so amounts are safe
+/// integers in JSON too.
+pub const MAX_SUPPLY: u64 = 21_000_000 * AMOUNT_UNIT;
+
 /// The minimum data duration in hours.
 pub const MIN_DATA_DURATION: u32 = 1;
 
@@ -50,8 +67,12 @@ pub const CONFIRMATION_TIME: u32 = 10;
 /// Retarget time, which is the number of blocks before a retarget.
 pub const RETARGET_TIME: u32 = 10;
 
-/// Mining interest rate.
-pub const INTEREST_RATE: f32 = 1.4;
+/// Maximum factor by which the work can change at a retarget.
+pub const MAX_RETARGET_FACTOR: u32 = 4;
+
+/// Mining interest rate, as a numerator and a denominator, by which the subsidy
+/// grows at each height.
+pub const INTEREST_RATE: (u32, u32) = (7, 5);
 
 /// Minimum difficulty.
 pub const MIN_DIFFICULTY: u32 = 3;
@@ -67,3 +88,57 @@ pub const GENESIS_MEMORY: u32 = 64;
 
 /// Heights to pass before a coinbase is spendable.
 pub const MATURITY_TIME: u32 = 60_480; // 1 week
+
+/// Maximum number of orphan block headers kept while waiting for their parent.
+pub const MAX_ORPHANS: u32 = 1_000;
+
+/// Minimum fee paid for every byte of a transaction and of its data, as a fraction.
+pub const MIN_FEE_PER_BYTE: (u32, u32) = (1, 1_000_000);
+
+/// Number of recent blocks looked at when estimating fees.
+pub const FEE_ESTIMATION_BLOCKS: u32 = 100;
+
+/// Maximum number of bytes of the transactions kept in the mempool.
+pub const MAX_MEMPOOL_WEIGHT: u32 = 300_000_000;
+
+/// Seconds after which a transaction not yet confirmed is dropped from the mempool.
+pub const MEMPOOL_EXPIRY_TIME: u32 = 1_209_600; // 2 weeks
+
+/// Number of bits of the remainders of the Golomb-coded sets of the block filters.
+pub const GCS_P: u8 = 19;
+
+/// Inverse of the false positive rate of the Golomb-coded sets of the block filters.
+pub const GCS_M: u64 = 784_931;
+
+/// Number of bytes of the short ids of the transactions of a compact block.
+pub const SHORT_ID_LENGTH: usize = 6;
+
+/// Version of the canonical binary encoding of the models.
+pub const ENCODING_VERSION: u8 = 1;
+
+/// Version of the JSON representation of the models.
+pub const JSON_VERSION: u32 = 1;
+
+/// Default maximum number of bytes read decoding a model.
+pub const MAX_DECODE_SIZE: u64 = 32_000_000;
+
+/// Default maximum number of items of a list read decoding a model.
+pub const MAX_DECODE_LENGTH: u32 = 1_000_000;
+
+/// Version of the peer-to-peer protocol.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Maximum number of bytes of the payload of a protocol message.
+pub const MAX_MESSAGE_SIZE: u32 = 32_000_000;
+
+/// Maximum number of block headers sent in a protocol message.
+pub const MAX_HEADERS_LENGTH: u32 = 2_000;
+
+/// Maximum number of ids requested or announced in a protocol message.
+pub const MAX_INVENTORY_LENGTH: u32 = 50_000;
+
+/// Maximum number of block headers ids in a block locator.
+pub const MAX_LOCATOR_LENGTH: u32 = 64;
+
+/// Maximum number of unrelated protocol messages received while waiting for a response.
+pub const MAX_IGNORED_MESSAGES: u32 = 1_000;
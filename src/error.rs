@@ -6,6 +6,10 @@
 // terms.
 
 //! The `error` module provides the errors used throughout the library.
+//!
+//! An `Error` has an `ErrorKind`, the source error it was converted from, if any,
+//! and the `ErrorDetails` of the failure: the path of the field which failed and
+//! the expected and actual values.
 
 use failure::{Fail, Context, Backtrace};
 use failure::Error as FailureError;
@@ -29,6 +33,70 @@ use std::io::Error as IOError;
 pub struct Error {
     /// Inner `Context` with the `Fail` implementor.
     inner: Context<ErrorKind>, 
+    /// The details of the failure.
+    details: ErrorDetails,
+}
+
+/// An `ErrorField` is a segment of the path of the field of an `Error`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ErrorField {
+    /// The name of the field.
+    pub name: &'static str,
+    /// The index of the item of the field, if the field is a list.
+    pub index: Option<u32>,
+}
+
+impl Display for ErrorField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{}[{}]", self.name, index),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// The `ErrorDetails` of an `Error` describe where and how a value failed.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ErrorDetails {
+    /// The path of the field which failed, from the outermost.
+    pub fields: Vec<ErrorField>,
+    /// The expected value, if any.
+    pub expected: Option<String>,
+    /// The actual value, if any.
+    pub actual: Option<String>,
+}
+
+impl ErrorDetails {
+    /// Returns the path of the field which failed, as in `transactions[1].inputs`.
+    pub fn path(&self) -> String {
+        self.fields.iter()
+            .map(|field| field.to_string())
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+
+    /// Verifies if there are no details.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.expected.is_none() && self.actual.is_none()
+    }
+}
+
+impl Display for ErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.fields.is_empty() {
+            write!(f, "at {}", self.path())?;
+        }
+
+        if let (Some(ref expected), Some(ref actual)) = (&self.expected, &self.actual) {
+            if !self.fields.is_empty() {
+                write!(f, " ")?;
+            }
+
+            write!(f, "(expected {}, actual {})", expected, actual)?;
+        }
+
+        Ok(())
+    }
 }
 
 /// The different types of errors used in `libyobicash`.
@@ -84,6 +152,18 @@ pub enum ErrorKind {
     InvalidBlock,
     #[fail(display="Invalid Proof-of-Work")]
     InvalidPoW,
+    #[fail(display="Invalid chain")]
+    InvalidChain,
+    #[fail(display="Double spend")]
+    DoubleSpend,
+    #[fail(display="Immature coinbase")]
+    ImmatureCoinbase,
+    #[fail(display="Insufficient funds")]
+    InsufficientFunds,
+    #[fail(display="Invalid fee")]
+    InvalidFee,
+    #[fail(display="Disconnected")]
+    Disconnected,
     #[fail(display="Crypto failure")]
     CryptoFailure,
     #[fail(display="Regex failure")]
@@ -112,101 +192,174 @@ impl Fail for Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Display::fmt(&self.inner, f)
+        Display::fmt(&self.inner, f)?;
+
+        if !self.details.is_empty() {
+            write!(f, " {}", self.details)?;
+        }
+
+        if let Some(cause) = self.inner.cause() {
+            write!(f, ": {}", cause)?;
+        }
+
+        Ok(())
     }
 }
+
 impl Error {
+    /// Creates an `Error` from its kind and its source error.
+    fn from_source<E: Fail>(source: E, kind: ErrorKind) -> Error {
+        Error {
+            inner: source.context(kind),
+            details: ErrorDetails::default(),
+        }
+    }
+
+    /// Returns the `ErrorKind`.
     pub fn kind(&self) -> ErrorKind {
         *self.inner.get_context()
     }
+
+    /// Returns the `ErrorDetails`.
+    pub fn details(&self) -> &ErrorDetails {
+        &self.details
+    }
+
+    /// Prepends a field to the path of the field which failed.
+    pub fn with_field(mut self, name: &'static str) -> Error {
+        self.details.fields.insert(0, ErrorField { name: name, index: None });
+        self
+    }
+
+    /// Prepends the item of a list field to the path of the field which failed.
+    pub fn with_index(mut self, name: &'static str, index: u32) -> Error {
+        self.details.fields.insert(0, ErrorField { name: name, index: Some(index) });
+        self
+    }
+
+    /// Sets the expected and actual values, if not already set by an inner failure.
+    pub fn with_values<E: Display, A: Display>(mut self, expected: E, actual: A) -> Error {
+        if self.details.expected.is_none() && self.details.actual.is_none() {
+            self.details.expected = Some(expected.to_string());
+            self.details.actual = Some(actual.to_string());
+        }
+
+        self
+    }
+}
+
+impl ErrorKind {
+    /// Creates an `Error` failed at a field.
+    pub fn with_field(self, name: &'static str) -> Error {
+        Error::from(self).with_field(name)
+    }
+
+    /// Creates an `Error` failed at the item of a list field.
+    pub fn with_index(self, name: &'static str, index: u32) -> Error {
+        Error::from(self).with_index(name, index)
+    }
+
+    /// Creates an `Error` with the expected and actual values.
+    pub fn with_values<E: Display, A: Display>(self, expected: E, actual: A) -> Error {
+        Error::from(self).with_values(expected, actual)
+    }
 }
 
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Error {
-        Error { inner: Context::new(kind) }
+        Error {
+            inner: Context::new(kind),
+            details: ErrorDetails::default(),
+        }
     }
 }
 
 impl From<Context<ErrorKind>> for Error {
     fn from(inner: Context<ErrorKind>) -> Error {
-        Error { inner: inner }
+        Error {
+            inner: inner,
+            details: ErrorDetails::default(),
+        }
     }
 }
 
 impl From<FailureError> for Error {
     fn from(e: FailureError) -> Error {
-        Error { inner: e.context(ErrorKind::FromFailure) }
+        Error {
+            inner: e.context(ErrorKind::FromFailure),
+            details: ErrorDetails::default(),
+        }
     }
 }
 
 impl From<CryptoError> for Error {
     fn from(e: CryptoError) -> Error {
-        Error { inner: e.context(ErrorKind::CryptoFailure) }
+        Error::from_source(e, ErrorKind::CryptoFailure)
     }
 }
 
 impl From<IOError> for Error {
-    fn from(_: IOError) -> Error {
-        Error { inner: Context::new(ErrorKind::IOFailure) }
+    fn from(e: IOError) -> Error {
+        Error::from_source(e, ErrorKind::IOFailure)
     }
 }
 
 impl From<ParseIntError> for Error {
-    fn from(_: ParseIntError) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: ParseIntError) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
 
 impl From<ParseIntegerError> for Error {
-    fn from(_: ParseIntegerError) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: ParseIntegerError) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
 
 impl From<ParseRationalError> for Error {
-    fn from(_: ParseRationalError) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: ParseRationalError) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
 
 impl From<FromUtf8Error> for Error {
-    fn from(_: FromUtf8Error) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: FromUtf8Error) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
 
 impl From<RegexError> for Error {
-    fn from(_: RegexError) -> Error {
-        Error { inner: Context::new(ErrorKind::RegexFailure) }
+    fn from(e: RegexError) -> Error {
+        Error::from_source(e, ErrorKind::RegexFailure)
     }
 }
 
 impl From<ToMessagePackError> for Error {
-    fn from(_: ToMessagePackError) -> Error {
-        Error { inner: Context::new(ErrorKind::SerializationFailure) }
+    fn from(e: ToMessagePackError) -> Error {
+        Error::from_source(e, ErrorKind::SerializationFailure)
     }
 }
 
 impl From<FromMessagePackError> for Error {
-    fn from(_: FromMessagePackError) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: FromMessagePackError) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
 
 impl From<JsonError> for Error {
-    fn from(_: JsonError) -> Error {
-        Error { inner: Context::new(ErrorKind::JsonFailure) }
+    fn from(e: JsonError) -> Error {
+        Error::from_source(e, ErrorKind::JsonFailure)
     }
 }
 
 impl From<FromHexError> for Error {
-    fn from(_: FromHexError) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: FromHexError) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
 
 impl From<FromTimeError> for Error {
-    fn from(_: FromTimeError) -> Error {
-        Error { inner: Context::new(ErrorKind::DeserializationFailure) }
+    fn from(e: FromTimeError) -> Error {
+        Error::from_source(e, ErrorKind::DeserializationFailure)
     }
 }
@@ -33,6 +33,12 @@ pub mod traits;
 pub mod crypto;
 pub mod utils;
 pub mod models;
+pub mod chain;
+pub mod store;
+pub mod wallet;
+pub mod mining;
+pub mod protocol;
+pub mod node;
 
 pub use self::error::*;
 pub use self::result::*;
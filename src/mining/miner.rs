@@ -0,0 +1,301 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `miner` module provides the miner type and methods, used to mine block
+//! headers on multiple threads.
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use error::ErrorKind;
+use result::Result;
+use traits::Identify;
+use crypto::{Digest, ZKPWitness, BalloonHasher};
+use crypto::BinarySerialize as CryptoBinarySerialize;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The number of nonces taken at once by a mining thread.
+const NONCES_PER_CHUNK: u64 = 16;
+
+/// The default interval between two progress reports, in milliseconds.
+const PROGRESS_INTERVAL: u64 = 1_000;
+
+/// A `MiningJob` is a `BlockHeader` being mined.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MiningJob {
+    /// The block header, without the `PoW` nonce, digest and id.
+    pub block_header: BlockHeader,
+    /// The next nonce to try, used to resume a cancelled job.
+    pub next_nonce: u64,
+}
+
+impl MiningJob {
+    /// Creates a new `MiningJob` for a `Block` and its transactions.
+    pub fn new(block: &Block,
+               transactions: &[Transaction],
+               prev_block_header: &BlockHeader,
+               witness: ZKPWitness) -> Result<MiningJob> {
+        let job = MiningJob {
+            block_header: BlockHeader::new_template(block, transactions, prev_block_header, witness)?,
+            next_nonce: 0,
+        };
+
+        Ok(job)
+    }
+}
+
+/// A `MiningProgress` reports the work done by a `Miner`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MiningProgress {
+    /// The number of hashes computed.
+    pub hashes: u64,
+    /// The time spent mining.
+    pub elapsed: Duration,
+    /// The number of hashes computed per second.
+    pub hashes_per_second: f64,
+}
+
+/// A `MinerHandle` is used to cancel a `Miner` from another thread.
+#[derive(Clone, Debug)]
+pub struct MinerHandle {
+    /// The cancellation flag of the miner.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl MinerHandle {
+    /// Cancels the running mining, as when a competing block arrives. A cancel
+    /// with no running mining cancels the next mining.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Verifies if the mining has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// A `Miner` mines block headers splitting the nonces between its threads.
+#[derive(Debug)]
+pub struct Miner {
+    /// The number of mining threads.
+    pub threads: u32,
+    /// The interval between two progress reports.
+    pub progress_interval: Duration,
+    /// The cancellation flag.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Miner {
+    /// Creates a new `Miner`.
+    pub fn new(threads: u32) -> Result<Miner> {
+        if threads == 0 {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        let miner = Miner {
+            threads: threads,
+            progress_interval: Duration::from_millis(PROGRESS_INTERVAL),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+
+        Ok(miner)
+    }
+
+    /// Returns a `MinerHandle` used to cancel the `Miner`.
+    pub fn handle(&self) -> MinerHandle {
+        MinerHandle {
+            cancelled: self.cancelled.clone(),
+        }
+    }
+
+    /// Mines a `MiningJob`, reporting the progress at every interval. Returns the
+    /// mined `BlockHeader`, or `None` if the mining has been cancelled, in which
+    /// case the job can be resumed from the lowest nonce not tried.
+    pub fn mine<F>(&self, job: &mut MiningJob, mut progress: F) -> Result<Option<BlockHeader>>
+        where F: FnMut(&MiningProgress)
+    {
+        if self.threads == 0 {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        let pow = job.block_header.pow()?;
+        let target = pow.target()?.digest();
+        let salt = pow.salt.to_bytes()?;
+
+        let next_nonce = Arc::new(AtomicU64::new(job.next_nonce));
+        let hashes = Arc::new(AtomicU64::new(0));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let (sender, receiver) = mpsc::channel();
+        let mut workers = Vec::new();
+
+        for _ in 0..self.threads {
+            let hasher = pow.hasher()?;
+            let salt = salt.clone();
+            let next_nonce = next_nonce.clone();
+            let hashes = hashes.clone();
+            let stopped = stopped.clone();
+            let cancelled = self.cancelled.clone();
+            let sender = sender.clone();
+
+            workers.push(thread::spawn(move || {
+                let worker = Worker {
+                    hasher: hasher,
+                    salt: salt,
+                    target: target,
+                    next_nonce: next_nonce,
+                    hashes: hashes,
+                    stopped: stopped,
+                    cancelled: cancelled,
+                };
+
+                let _ = sender.send(worker.mine());
+            }));
+        }
+
+        drop(sender);
+
+        let start = Instant::now();
+        let mut finished = 0;
+        let mut res = Ok(None);
+        let mut resume_nonce: Option<u64> = None;
+
+        while finished < self.threads {
+            match receiver.recv_timeout(self.progress_interval) {
+                Ok(Ok(WorkerResult::Found(nonce, digest))) => {
+                    res = Ok(Some((nonce, digest)));
+                    break;
+                },
+                Ok(Ok(WorkerResult::Stopped(unfinished))) => {
+                    if let Some(nonce) = unfinished {
+                        resume_nonce = Some(resume_nonce.map_or(nonce, |resume| resume.min(nonce)));
+                    }
+
+                    finished += 1;
+                },
+                Ok(Err(err)) => {
+                    res = Err(err);
+                    break;
+                },
+                Err(RecvTimeoutError::Timeout) => {
+                    let elapsed = start.elapsed();
+                    let hashes = hashes.load(Ordering::SeqCst);
+                    let seconds = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9;
+
+                    progress(&MiningProgress {
+                        hashes: hashes,
+                        elapsed: elapsed,
+                        hashes_per_second: hashes as f64 / seconds,
+                    });
+                },
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        stopped.store(true, Ordering::SeqCst);
+
+        for worker in workers {
+            let _ = worker.join();
+        }
+
+        // the chunks taken but not finished by the threads are resumed
+        job.next_nonce = resume_nonce.unwrap_or_else(|| next_nonce.load(Ordering::SeqCst));
+
+        let cancelled = self.cancelled.swap(false, Ordering::SeqCst);
+
+        match res? {
+            Some((nonce, digest)) => {
+                let mut block_header = job.block_header.clone();
+                block_header.pow_nonce = nonce;
+                block_header.pow_digest = digest;
+                block_header.id = block_header.id()?;
+
+                Ok(Some(block_header))
+            },
+            None => {
+                if cancelled {
+                    Ok(None)
+                } else {
+                    Err(ErrorKind::NotFound.into())
+                }
+            },
+        }
+    }
+}
+
+/// The result of a `Worker`.
+enum WorkerResult {
+    /// A nonce and its digest below the target.
+    Found(u64, Digest),
+    /// The thread stopped, with the first nonce it did not try in its chunk, if any.
+    Stopped(Option<u64>),
+}
+
+/// A `Worker` is a mining thread.
+struct Worker {
+    /// The hasher of the `PoW`.
+    hasher: BalloonHasher,
+    /// The binary salt of the `PoW`.
+    salt: Vec<u8>,
+    /// The digest to stay below.
+    target: Digest,
+    /// The next nonce not taken by any thread.
+    next_nonce: Arc<AtomicU64>,
+    /// The number of hashes computed by all the threads.
+    hashes: Arc<AtomicU64>,
+    /// Set when a thread finds a nonce or the nonces are over.
+    stopped: Arc<AtomicBool>,
+    /// Set when the mining is cancelled.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Worker {
+    /// Verifies if the thread has to stop.
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst) || self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Tries chunks of nonces until a nonce is found or the mining is stopped.
+    fn mine(&self) -> Result<WorkerResult> {
+        while !self.is_stopped() {
+            let start = self.next_nonce.fetch_add(NONCES_PER_CHUNK, Ordering::SeqCst);
+
+            if start > u64::MAX - NONCES_PER_CHUNK {
+                self.stopped.store(true, Ordering::SeqCst);
+                break;
+            }
+
+            for nonce in start..start + NONCES_PER_CHUNK {
+                if self.is_stopped() {
+                    return Ok(WorkerResult::Stopped(Some(nonce)));
+                }
+
+                let mut buf = self.salt.clone();
+                buf.write_u64::<BigEndian>(nonce)?;
+
+                let digest = self.hasher.hash(&buf)?;
+                self.hashes.fetch_add(1, Ordering::SeqCst);
+
+                if digest < self.target {
+                    self.stopped.store(true, Ordering::SeqCst);
+
+                    return Ok(WorkerResult::Found(nonce, digest));
+                }
+            }
+        }
+
+        Ok(WorkerResult::Stopped(None))
+    }
+}
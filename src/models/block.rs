@@ -7,23 +7,23 @@
 
 //! The `block` module provides the block type and methods.
 
-use serde_json as json;
-use rmp_serde as messagepack;
 use hex;
-use byteorder::{BigEndian, WriteBytesExt};
 use itertools::Itertools;
 
 use constants::{TESTWITNESS, MAINWITNESS};
 use error::ErrorKind;
-use result::Result;
-use traits::{Identify, Validate, BinarySerialize, HexSerialize, Serialize};
-use utils::{Version, NetworkType, Timestamp};
+use result::{Result, ResultExt};
+use traits::{Identify, Validate, ValidateReport, Json, Serialize, Encode};
+use utils::{Version, NetworkType, Timestamp, MerkleTree, MerkleProof};
+use utils::encoding::{self, Encoder, Decoder};
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
 use crypto::{Digest, ZKPWitness};
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoHexSerialize;
 use models::transaction::Transaction;
+use models::report::{Rule, ValidationReport};
 
-use std::io::Write;
+use std::io::{Read, Write};
 
 /// A `Block` is a commitment of validated transactions.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -48,11 +48,14 @@ impl Block {
     /// Creates a new `Block`.
     pub fn new(network_type: NetworkType,
                transactions: &[Transaction]) -> Result<Block> {
-        for transaction in transactions {
-            transaction.validate()?;
+        for (index, transaction) in transactions.iter().enumerate() {
+            transaction.validate().with_index("transactions", index as u32)?;
 
             if transaction.network_type != network_type {
-                return Err(ErrorKind::InvalidNetwork.into());
+                return Err(ErrorKind::InvalidNetwork
+                           .with_field("network_type")
+                           .with_index("transactions", index as u32)
+                           .with_values(network_type, transaction.network_type));
             }
         }
 
@@ -72,7 +75,7 @@ impl Block {
 
         if transactions_binary_ids.iter().unique().count() !=
             transactions.len() {
-            return Err(ErrorKind::DuplicatesFound.into());
+            return Err(ErrorKind::DuplicatesFound.with_field("transactions"));
         }
 
         let mut block = Block::default();
@@ -88,23 +91,23 @@ impl Block {
 
     /// Creates a new genesis `Block`.
     pub fn new_genesis(version: &Version, network_type: NetworkType, genesis_witness: Option<ZKPWitness>) -> Result<Block> {
-        version.validate()?;
+        version.validate().with_field("version")?;
 
         if let Some(gw) = genesis_witness {
             if network_type == NetworkType::TestNet {
                 if gw != ZKPWitness::from_hex(TESTWITNESS)? {
-                    return Err(ErrorKind::InvalidWitness.into());
+                    return Err(ErrorKind::InvalidWitness.with_field("genesis_witness"));
                 }
             }
 
             if network_type == NetworkType::MainNet {
                 if gw != ZKPWitness::from_hex(MAINWITNESS)? {
-                    return Err(ErrorKind::InvalidWitness.into());
+                    return Err(ErrorKind::InvalidWitness.with_field("genesis_witness"));
                 }
             }
         } else {
             if network_type == NetworkType::RegTest {
-                return Err(ErrorKind::InvalidNetwork.into());
+                return Err(ErrorKind::InvalidNetwork.with_field("network_type"));
             }
         }
 
@@ -165,15 +168,17 @@ impl Block {
 
             if transaction_id == testnet_genesis.id {
                 if self.network_type != NetworkType::TestNet {
-                    return Err(ErrorKind::InvalidNetwork.into());
+                    return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::TestNet, self.network_type));
                 }
 
                 if self.timestamp != Timestamp::min_value() {
-                    return Err(ErrorKind::InvalidTimestamp.into());
+                    return Err(ErrorKind::InvalidTimestamp.with_field("timestamp").with_values(Timestamp::min_value(), self.timestamp));
                 }
 
-                if self.transactions_size != testnet_genesis.size()? {
-                    return Err(ErrorKind::InvalidLength.into());
+                let size = testnet_genesis.size()?;
+
+                if self.transactions_size != size {
+                    return Err(ErrorKind::InvalidLength.with_field("transactions_size").with_values(size, self.transactions_size));
                 }
                 
                 return Ok(true);
@@ -181,30 +186,32 @@ impl Block {
 
             if transaction_id == mainnet_genesis.id {
                 if self.network_type != NetworkType::MainNet {
-                    return Err(ErrorKind::InvalidNetwork.into());
+                    return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::MainNet, self.network_type));
                 }
 
                 if self.timestamp != Timestamp::min_value() {
-                    return Err(ErrorKind::InvalidTimestamp.into());
+                    return Err(ErrorKind::InvalidTimestamp.with_field("timestamp").with_values(Timestamp::min_value(), self.timestamp));
                 }
 
-                if self.transactions_size != mainnet_genesis.size()? {
-                    return Err(ErrorKind::InvalidLength.into());
+                let size = mainnet_genesis.size()?;
+
+                if self.transactions_size != size {
+                    return Err(ErrorKind::InvalidLength.with_field("transactions_size").with_values(size, self.transactions_size));
                 }
 
                 return Ok(true);
             }
 
             if self.network_type != NetworkType::RegTest {
-                return Err(ErrorKind::InvalidNetwork.into());
+                return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::RegTest, self.network_type));
             }
 
             Ok(true)
         } else {
-            for transaction_id in self.transactions_ids.clone() {
-                if transaction_id == testnet_genesis.id ||
-                    transaction_id == mainnet_genesis.id {
-                    return Err(ErrorKind::InvalidBlock.into());
+            for (index, transaction_id) in self.transactions_ids.iter().enumerate() {
+                if *transaction_id == testnet_genesis.id ||
+                    *transaction_id == mainnet_genesis.id {
+                    return Err(ErrorKind::InvalidBlock.with_index("transactions_ids", index as u32));
                 }
             }
 
@@ -212,6 +219,16 @@ impl Block {
         }
     }
 
+    /// Returns the Merkle root of the `Block` transactions' ids.
+    pub fn transactions_root(&self) -> Result<Digest> {
+        Ok(MerkleTree::new(&self.transactions_ids)?.root())
+    }
+
+    /// Returns the `MerkleProof` of inclusion of a transaction in the `Block`.
+    pub fn transaction_proof(&self, transaction_id: Digest) -> Result<MerkleProof> {
+        MerkleTree::new(&self.transactions_ids)?.proof(transaction_id)
+    }
+
     /// Returns the size of the `Block`.
     pub fn size(&self) -> Result<u32> {
         Ok(self.to_bytes()?.len() as u32)
@@ -236,21 +253,7 @@ impl<'a> Identify<'a> for Block {
     type ID = Digest;
 
     fn id(&self) -> Result<Self::ID> {
-        let mut buf = Vec::new();
-
-        buf.write_all(&self.version.to_bytes()?)?;
-        buf.write_all(&self.network_type.to_bytes()?)?;
-        buf.write_all(&self.timestamp.to_bytes()?)?;
-
-        buf.write_u32::<BigEndian>(self.transactions_size)?;
-        buf.write_u32::<BigEndian>(self.transactions_length)?;
-        
-        for i in 0..self.transactions_length as usize {
-            let id = &self.transactions_ids[i];
-            buf.write_all(&id.to_bytes()?)?;
-        }
-
-        Ok(Digest::hash(&buf))
+        encoding::digest(|encoder| self.encode_body(encoder))
     }
 
     fn id_from_bytes(b: &[u8]) -> Result<Self::ID> {
@@ -282,119 +285,145 @@ impl<'a> Identify<'a> for Block {
     }
 }
 
+impl Block {
+    /// Encodes the fields of the `Block` identified by its id.
+    fn encode_body<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write(&self.version)?;
+        encoder.write(&self.network_type)?;
+        encoder.write(&self.timestamp)?;
+        encoder.write_u32(self.transactions_size)?;
+        encoder.write_u32(self.transactions_length)?;
+        encoder.write_digests(&self.transactions_ids)
+    }
+}
+
+impl Encode for Block {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.id)?;
+        self.encode_body(encoder)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Block> {
+        let block = Block {
+            id: decoder.read_digest()?,
+            version: decoder.read()?,
+            network_type: decoder.read()?,
+            timestamp: decoder.read()?,
+            transactions_size: decoder.read_u32()?,
+            transactions_length: decoder.read_u32()?,
+            transactions_ids: decoder.read_digests()?,
+        };
+
+        Ok(block)
+    }
+}
+
 impl Validate for Block {
     fn validate(&self) -> Result<()> {
-        self.version.validate()?;
-        self.timestamp.validate()?;
-        
-        if self.id != self.id()? {
-            return Err(ErrorKind::InvalidDigest.into());
+        self.validate_report()?.to_result()
+    }
+}
+
+impl ValidateReport for Block {
+    fn validate_report(&self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+
+        if self.version.validate().is_err() {
+            report.add(Rule::BlockVersion);
         }
 
-        if self.transactions_size == 0 ||
-            self.transactions_length == 0 {
-            return Err(ErrorKind::InvalidLength.into());    
+        if self.timestamp.validate().is_err() {
+            report.add(Rule::BlockTimestamp);
+        }
+
+        let id = self.id()?;
+
+        if self.id != id {
+            report.add_values(Rule::BlockId, id, self.id);
+        }
+
+        if self.transactions_size == 0 {
+            report.add(Rule::BlockTransactionsSize);
+        }
+
+        if self.transactions_length == 0 {
+            report.add(Rule::BlockTransactionsLength);
         }
 
         if self.transactions_length as usize != self.transactions_ids.len() {
-            return Err(ErrorKind::InvalidLength.into()); 
+            report.add_values(Rule::BlockTransactionsIds, self.transactions_length, self.transactions_ids.len());
         }
 
         let mut transactions_binary_ids = Vec::new();
-        for id in self.transactions_ids.clone() {
+        for id in &self.transactions_ids {
             transactions_binary_ids.push(id.to_bytes()?);
         }
 
-        if transactions_binary_ids.iter().unique().count() != self.transactions_length as usize {
-            return Err(ErrorKind::DuplicatesFound.into()); 
+        if transactions_binary_ids.iter().unique().count() != self.transactions_ids.len() {
+            report.add(Rule::BlockDuplicateTransactions);
         }
 
-        let _ = self.is_genesis()?;
+        // the genesis rules read the declared transaction ids
+        if self.transactions_length as usize == self.transactions_ids.len() && self.is_genesis().is_err() {
+            report.add(Rule::BlockGenesis);
+        }
 
-        Ok(())
+        Ok(report)
     }
 }
 
-impl<'a> Serialize<'a> for Block {
-    fn to_json(&self) -> Result<String> {
-        let mut json_transactions_ids = Vec::new();
-        for id in self.transactions_ids.clone() {
-            json_transactions_ids.push(id.to_hex()?);
-        }
-
-        let obj = json!({
-            "id": self.string_id()?,
-            "version": self.version.to_string(),
-            "network_type": self.network_type.to_hex()?,
-            "timestamp": self.timestamp.to_string(),
-            "transactions_size": self.transactions_size,
-            "transactions_length": self.transactions_length,
-            "transactions_ids": json_transactions_ids,
-        });
-
-        let s = obj.to_string();
-
-        Ok(s)
+impl Json for Block {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_version("version", &self.version)?;
+        writer.write_network_type("network_type", self.network_type)?;
+        writer.write_timestamp("timestamp", self.timestamp)?;
+        writer.write_u32("transactions_size", self.transactions_size)?;
+        writer.write_u32("transactions_length", self.transactions_length)?;
+        writer.write_digests("transactions_ids", &self.transactions_ids)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_str: String = json::from_value(id_value)?;
-        let id = Block::id_from_string(&id_str)?;
-        
-        let version_value = obj["version"].clone();
-        let version_str: String = json::from_value(version_value)?;
-        let version = Version::from_string(&version_str)?;
-        
-        let network_type_value = obj["network_type"].clone();
-        let network_type_hex: String = json::from_value(network_type_value)?;
-        let network_type = NetworkType::from_hex(&network_type_hex)?;
-        
-        let timestamp_value = obj["timestamp"].clone();
-        let timestamp_str: String = json::from_value(timestamp_value)?;
-        let timestamp = Timestamp::from_string(&timestamp_str)?;
-
-        let transactions_size_value = obj["transactions_size"].clone();
-        let transactions_size: u32 = json::from_value(transactions_size_value)?;
-
-        let transactions_length_value = obj["transactions_length"].clone();
-        let transactions_length: u32 = json::from_value(transactions_length_value)?;
-
-        let transactions_ids_value = obj["transactions_ids"].clone();
-        let transactions_ids_json: Vec<String> = json::from_value(transactions_ids_value)?;
-
-        let mut transactions_ids = Vec::new();
-
-        for id_hex in transactions_ids_json {
-            let id = Digest::from_hex(&id_hex)?;
-            transactions_ids.push(id);
-        }
 
+    fn read_json(reader: &mut JsonReader) -> Result<Block> {
         let block = Block {
-            id: id,
-            version: version,
-            network_type: network_type,
-            timestamp: timestamp,
-            transactions_size: transactions_size,
-            transactions_length: transactions_length,
-            transactions_ids: transactions_ids,
+            id: reader.read_digest("id")?,
+            version: reader.read_version("version")?,
+            network_type: reader.read_network_type("network_type")?,
+            timestamp: reader.read_timestamp("timestamp")?,
+            transactions_size: reader.read_u32("transactions_size")?,
+            transactions_length: reader.read_u32("transactions_length")?,
+            transactions_ids: reader.read_digests("transactions_ids")?,
         };
 
         Ok(block)
     }
-    
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::to_vec(self)?;
 
-        Ok(buf)
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("A block of transactions.")
+            .reference("id", "Digest")
+            .reference("version", "Version")
+            .reference("network_type", "NetworkType")
+            .reference("timestamp", "Timestamp")
+            .reference("transactions_size", "U32")
+            .reference("transactions_length", "U32")
+            .list("transactions_ids", "Digest")
+    }
+}
+
+impl<'a> Serialize<'a> for Block {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
     
     fn from_bytes(b: &[u8]) -> Result<Self> {
-        let tx = messagepack::from_slice(b)?;
-
-        Ok(tx)
+        encoding::decode(b)
     }
     
     fn to_hex(&self) -> Result<String> {
@@ -0,0 +1,144 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `block_filter` module provides the `BlockFilter` type and methods, used by the
+//! wallets to find the blocks paying to their witnesses, coinbases included, or
+//! spending their coins without downloading them.
+
+use error::ErrorKind;
+use result::Result;
+use traits::{Validate, Encode};
+use utils::GolombCodedSet;
+use utils::encoding::{Encoder, Decoder};
+use crypto::{Digest, ZKPWitness};
+use crypto::BinarySerialize as CryptoBinarySerialize;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+
+use std::io::{Read, Write};
+
+/// The prefix of the filter items of the output witnesses.
+const WITNESS_PREFIX: u8 = 0;
+
+/// The prefix of the filter items of the spent output ids.
+const INPUT_PREFIX: u8 = 1;
+
+/// Returns the filter item of an output witness.
+fn witness_item(witness: ZKPWitness) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&[WITNESS_PREFIX])?;
+    buf.write_all(&witness.to_bytes()?)?;
+
+    Ok(buf)
+}
+
+/// Returns the filter item of a spent output id.
+fn input_item(id: Digest) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&[INPUT_PREFIX])?;
+    buf.write_all(&id.to_bytes()?)?;
+
+    Ok(buf)
+}
+
+/// A `BlockFilter` is a Golomb-coded set of the output witnesses and of the ids of
+/// the outputs spent by the transactions of a `Block`, together with the witness of
+/// the coinbase output of its `BlockHeader`, keyed with the block id.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Serialize, Deserialize)]
+pub struct BlockFilter {
+    /// The id of the filtered block.
+    pub block_id: Digest,
+    /// The filter of the block items.
+    pub filter: GolombCodedSet,
+}
+
+impl BlockFilter {
+    /// Creates a new `BlockFilter` from a `BlockHeader`, its `Block` and the block
+    /// transactions.
+    pub fn new(block_header: &BlockHeader,
+               block: &Block,
+               transactions: &[Transaction]) -> Result<BlockFilter> {
+        block_header.validate()?;
+        block.validate()?;
+
+        if block_header.block_id != block.id {
+            return Err(ErrorKind::InvalidBlock.with_field("block_id").with_values(block.id, block_header.block_id));
+        }
+
+        if transactions.len() != block.transactions_ids.len() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let mut items = vec![witness_item(block_header.coinbase_output.witness)?];
+
+        for (transaction, id) in transactions.iter().zip(block.transactions_ids.iter()) {
+            if transaction.id != *id {
+                return Err(ErrorKind::InvalidTransaction.into());
+            }
+
+            for output in &transaction.outputs {
+                items.push(witness_item(output.witness)?);
+            }
+
+            for input in &transaction.inputs {
+                items.push(input_item(input.id)?);
+            }
+        }
+
+        let block_filter = BlockFilter {
+            block_id: block.id,
+            filter: GolombCodedSet::new(block.id, &items)?,
+        };
+
+        Ok(block_filter)
+    }
+
+    /// Verifies if the block may have an output paying to a witness.
+    pub fn match_witness(&self, witness: ZKPWitness) -> Result<bool> {
+        self.filter.contains(self.block_id, &witness_item(witness)?)
+    }
+
+    /// Verifies if the block may spend an output.
+    pub fn match_input(&self, id: Digest) -> Result<bool> {
+        self.filter.contains(self.block_id, &input_item(id)?)
+    }
+
+    /// Verifies if the block may have an output paying to any of a list of witnesses,
+    /// or spend any of a list of outputs.
+    pub fn match_any(&self, witnesses: &[ZKPWitness], ids: &[Digest]) -> Result<bool> {
+        let mut items = Vec::new();
+
+        for witness in witnesses {
+            items.push(witness_item(*witness)?);
+        }
+
+        for id in ids {
+            items.push(input_item(*id)?);
+        }
+
+        self.filter.contains_any(self.block_id, &items)
+    }
+}
+
+impl Encode for BlockFilter {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.block_id)?;
+        encoder.write(&self.filter)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<BlockFilter> {
+        let block_filter = BlockFilter {
+            block_id: decoder.read_digest()?,
+            filter: decoder.read()?,
+        };
+
+        Ok(block_filter)
+    }
+}
@@ -7,83 +7,145 @@
 
 //! The `block_header` module provides the `BlockHeader` type and methods.
 
-use serde_json as json;
-use rmp_serde as messagepack;
 use hex;
-use byteorder::{BigEndian, WriteBytesExt};
 
-use constants::{CONFIRMATION_TIME, INTEREST_RATE, MIN_DIFFICULTY, MAX_DIFFICULTY};
+use constants::{CONFIRMATION_TIME, MIN_DIFFICULTY, MAX_DIFFICULTY};
+use constants::{RETARGET_TIME, MAX_RETARGET_FACTOR, MEDIAN_TIME_SPAN, MAX_TIMENOISE};
 use constants::{GENESIS_MEMORY, GENESIS_DIFFICULTY};
 use error::ErrorKind;
 use result::Result;
-use traits::{Identify, Validate, BinarySerialize, HexSerialize, Serialize};
-use utils::{Amount, Version, NetworkType, Timestamp};
+use traits::{Identify, Validate, ValidateReport, Json, Serialize, Encode};
+use utils::{Amount, Version, NetworkType, Timestamp, MerkleProof};
+use utils::encoding::{self, Encoder, Decoder};
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
 use crypto::{Memory, Digest, ZKPWitness, PoW};
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoHexSerialize;
 use models::output::Output;
+use models::transaction::Transaction;
 use models::block::Block;
+use models::monetary_policy::{get_subsidy, get_fees, verify_coinbase};
+use models::report::{Rule, ValidationReport};
 
 use std::convert::From;
-use std::io::Write;
+use std::io::{Read, Write};
+
+/// Returns the work needed to mine with a given difficulty and memory, that is
+/// the memory spent by each hash times the expected number of hashes.
+pub fn get_pow_work(difficulty: u32, memory: &Memory) -> Memory {
+    Memory::from(2u32).pow(difficulty) * memory
+}
+
+/// Verifies if a height starts a retarget period.
+fn is_retarget_height(height: u32) -> bool {
+    let period_position = height % RETARGET_TIME;
+
+    period_position == 0
+}
+
+/// Calculates the difficulty and the memory following a `BlockHeader`.
+///
+/// They change only at the start of a retarget period, every `RETARGET_TIME` heights,
+/// scaling the work by the ratio between the expected and the actual duration of
+/// the last period, bounded by `MAX_RETARGET_FACTOR`. The new work is reached raising
+/// the difficulty as much as possible, and the memory makes up for the rest.
+fn get_retarget(prev_block_header: &BlockHeader) -> Result<(u32, Memory)> {
+    let prev_difficulty = prev_block_header.pow_difficulty;
+    let prev_memory = &prev_block_header.pow_memory;
+
+    if !is_retarget_height(prev_block_header.height + 1) {
+        return Ok((prev_difficulty, prev_memory.clone()));
+    }
+
+    let timestamp = prev_block_header.timestamp;
+    let retarget_timestamp = prev_block_header.retarget_timestamp;
 
-/// Calculates the difficulty given the timestamp and the difficulty
-/// of the previous block. [it's not right, but it's ok]
-pub fn get_difficulty(timestamp: Timestamp, prev_timestamp: Timestamp, prev_diff: u32) -> Result<u32> {
     timestamp.validate()?;
-    prev_timestamp.validate()?;
+    retarget_timestamp.validate()?;
 
-    if timestamp < prev_timestamp {
+    if timestamp < retarget_timestamp {
         return Err(ErrorKind::InvalidDuration.into());
     }
 
-    let k = (timestamp.diff(prev_timestamp))/(CONFIRMATION_TIME as i64);
+    let max_factor = i64::from(MAX_RETARGET_FACTOR);
+    let expected_duration = i64::from(RETARGET_TIME) * i64::from(CONFIRMATION_TIME);
 
-    let difficulty = (k as u32) * prev_diff;
+    let mut duration = timestamp.diff(retarget_timestamp);
 
-    if difficulty < MIN_DIFFICULTY {
-        return Ok(MIN_DIFFICULTY);
+    if duration < expected_duration / max_factor {
+        duration = expected_duration / max_factor;
     }
 
-    if difficulty > MAX_DIFFICULTY {
-        return Ok(MAX_DIFFICULTY);
+    if duration > expected_duration * max_factor {
+        duration = expected_duration * max_factor;
     }
 
-    Ok(difficulty)
-}
+    let work = get_pow_work(prev_difficulty, prev_memory) *
+        &Memory::from(expected_duration) /
+        &Memory::from(duration);
 
-/// Calculates the memory to spend given the timestamp and the memory
-/// spent by the previous block. [it's not right, but it's ok]
-pub fn get_memory(timestamp: Timestamp, prev_timestamp: Timestamp, prev_memory: &Memory) -> Result<Memory> {
-    timestamp.validate()?;
-    prev_timestamp.validate()?;
+    let min_memory = PoW::default().memory()?;
 
-    if timestamp < prev_timestamp {
-        return Err(ErrorKind::InvalidDuration.into());
+    let mut difficulty = MIN_DIFFICULTY;
+
+    while difficulty < MAX_DIFFICULTY &&
+        get_pow_work(difficulty + 1, &min_memory) <= work {
+        difficulty += 1;
     }
 
-    let _k = (timestamp.diff(prev_timestamp))/(CONFIRMATION_TIME as i64);
-    let k = Memory::from(_k);
+    let memory = work / &Memory::from(2u32).pow(difficulty);
 
-    let default_memory = PoW::default().memory()?;
+    if memory < min_memory {
+        Ok((difficulty, min_memory))
+    } else {
+        Ok((difficulty, memory))
+    }
+}
+
+/// Calculates the difficulty of the `BlockHeader` following a given one.
+pub fn get_difficulty(prev_block_header: &BlockHeader) -> Result<u32> {
+    Ok(get_retarget(prev_block_header)?.0)
+}
 
-    let memory = &k * prev_memory;
+/// Calculates the memory to spend in the `BlockHeader` following a given one.
+pub fn get_memory(prev_block_header: &BlockHeader) -> Result<Memory> {
+    Ok(get_retarget(prev_block_header)?.1)
+}
 
-    if memory < default_memory {
-        Ok(default_memory)
+/// Returns the timestamp the retarget period of the `BlockHeader` following
+/// a given one starts from.
+pub fn get_retarget_timestamp(prev_block_header: &BlockHeader) -> Timestamp {
+    if is_retarget_height(prev_block_header.height + 1) {
+        prev_block_header.timestamp
     } else {
-        Ok(memory)
+        prev_block_header.retarget_timestamp
+    }
+}
+
+/// Returns the median timestamp of a set of `BlockHeader`s.
+pub fn get_median_time_past(block_headers: &[BlockHeader]) -> Result<Timestamp> {
+    if block_headers.is_empty() {
+        return Err(ErrorKind::InvalidLength.into());
     }
+
+    let mut timestamps: Vec<Timestamp> = block_headers.iter()
+        .map(|block_header| block_header.timestamp)
+        .collect();
+
+    timestamps.sort();
+
+    Ok(timestamps[timestamps.len() / 2])
 }
 
-/// Returns the coinbase amount gained at a given heigth.
-pub fn get_coinbase_amount(height: u32) -> Amount {
-    Amount::from(INTEREST_RATE).pow(height as i32)
+/// Returns the coinbase amount gained at a given heigth, collecting some fees.
+pub fn get_coinbase_amount(height: u32, fees: &Amount) -> Result<Amount> {
+    Ok(Amount::from(get_subsidy(height)?) + fees)
 }
 
-/// Returns the coinbase output and coin for a given height greater then 0.
-pub fn get_coinbase_output(height: u32, witness: ZKPWitness) -> Result<Output> {
-    let amount = get_coinbase_amount(height);
+/// Returns the coinbase output and coin for a given height greater then 0, collecting
+/// some fees.
+pub fn get_coinbase_output(height: u32, fees: &Amount, witness: ZKPWitness) -> Result<Output> {
+    let amount = get_coinbase_amount(height, fees)?;
 
     Output::new(&amount, witness)
 }
@@ -117,6 +179,8 @@ pub struct BlockHeader {
     pub coinbase_amount: Amount,
     /// The coinbase output.
     pub coinbase_output: Output,
+    /// The timestamp the retarget period of the `BlockHeader` starts from.
+    pub retarget_timestamp: Timestamp,
     /// The proof-of-work memory.
     pub pow_memory: Memory,
     /// The proof-of-work difficulty.
@@ -128,11 +192,39 @@ pub struct BlockHeader {
 }
 
 impl BlockHeader {
-    /// Creates a new `BlockHeader`.
-    pub fn new(block: &Block, prev_block_header: &BlockHeader, witness: ZKPWitness) -> Result<BlockHeader> {
+    /// Creates a new `BlockHeader` of a `Block` and its transactions, whose coinbase
+    /// collects their fees.
+    pub fn new(block: &Block,
+               transactions: &[Transaction],
+               prev_block_header: &BlockHeader,
+               witness: ZKPWitness) -> Result<BlockHeader> {
+        let mut block_header = BlockHeader::new_template(block, transactions, prev_block_header, witness)?;
+
+        let mut pow = block_header.pow()?;
+
+        pow.mine()?;
+
+        if !pow.verify()? {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        block_header.pow_nonce = pow.nonce.unwrap();
+        block_header.pow_digest = pow.digest.unwrap();
+        block_header.id = block_header.id()?;
+
+        Ok(block_header)
+    }
+
+    /// Creates a new `BlockHeader` not yet mined, with no `PoW` nonce, digest and id.
+    pub fn new_template(block: &Block,
+                        transactions: &[Transaction],
+                        prev_block_header: &BlockHeader,
+                        witness: ZKPWitness) -> Result<BlockHeader> {
         block.validate()?;
         prev_block_header.validate()?;
 
+        let fees = get_fees(block, transactions)?;
+
         if block.timestamp < prev_block_header.timestamp {
             return Err(ErrorKind::InvalidDuration.into());
         }
@@ -154,65 +246,59 @@ impl BlockHeader {
         block_header.transactions_size = block.transactions_size;
         block_header.transactions_length = block.transactions_length;
 
-        let mut buf = Vec::new();
-        for i in 0..block.transactions_length as usize {
-            // pros: easier to build
-            // cons: cannot have set membership verif or search in any form
-            buf.write_all(&block.transactions_ids[i].to_bytes()?)?;
-        }
-
-        block_header.transactions_root = Digest::hash(&buf);
+        block_header.transactions_root = block.transactions_root()?;
 
-        let coinbase_output = get_coinbase_output(height, witness)?;
+        let coinbase_output = get_coinbase_output(height, &fees, witness)?;
         block_header.coinbase_amount = coinbase_output.amount.clone();
         block_header.coinbase_output = coinbase_output;
 
-        let pow_memory = get_memory(timestamp, block.timestamp, &prev_block_header.pow_memory)?;
-        block_header.pow_memory = pow_memory.clone();
+        block_header.retarget_timestamp = get_retarget_timestamp(prev_block_header);
+
+        let pow_memory = get_memory(prev_block_header)?;
+        block_header.pow_memory = pow_memory;
         
-        let pow_difficulty = get_difficulty(timestamp, block.timestamp, prev_block_header.pow_difficulty)?;
+        let pow_difficulty = get_difficulty(prev_block_header)?;
         block_header.pow_difficulty = pow_difficulty;
 
-        let pow_salt = block_header.pow_salt()?;
-
-        let mut pow = PoW::from_memory(pow_salt, &pow_memory, pow_difficulty)?;
-
-        pow.mine()?;
-
-        if !pow.verify()? {
-            return Err(ErrorKind::NotFound.into());
-        }
+        Ok(block_header)
+    }
 
-        block_header.pow_nonce = pow.nonce.unwrap();
-        block_header.pow_digest = pow.digest.unwrap();
-        block_header.id = block_header.id()?;
+    /// Returns the `PoW` of the `BlockHeader`, not yet mined.
+    pub fn pow(&self) -> Result<PoW> {
+        let pow_salt = self.pow_salt()?;
 
-        Ok(block_header)
+        Ok(PoW::from_memory(pow_salt, &self.pow_memory, self.pow_difficulty)?)
     }
 
     /// Returns the `PoW` salt.
     pub fn pow_salt(&self) -> Result<Digest> {
-        let mut buf = Vec::new();
-
-        buf.write_all(&self.version.to_bytes()?)?;
-        buf.write_all(&self.network_type.to_bytes()?)?;
-        buf.write_all(&self.timestamp.to_bytes()?)?;
-
-        buf.write_all(&self.block_id.to_bytes()?)?;
-        buf.write_u32::<BigEndian>(self.height)?;
-        buf.write_all(&self.prev_id.to_bytes()?)?;
-
-        buf.write_u32::<BigEndian>(self.transactions_size)?;
-        buf.write_u32::<BigEndian>(self.transactions_length)?;
-        buf.write_all(&self.transactions_root.to_bytes()?)?;
-
-        buf.write_all(&self.coinbase_amount.to_bytes()?)?;
-        buf.write_all(&self.coinbase_output.to_bytes()?)?;
+        encoding::digest(|encoder| self.encode_pow_body(encoder))
+    }
 
-        buf.write_all(self.pow_memory.to_string().as_bytes())?;
-        buf.write_u32::<BigEndian>(self.pow_difficulty)?;
+    /// Encodes the fields of the `BlockHeader` mined by its PoW.
+    fn encode_pow_body<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write(&self.version)?;
+        encoder.write(&self.network_type)?;
+        encoder.write(&self.timestamp)?;
+        encoder.write_digest(self.block_id)?;
+        encoder.write_u32(self.height)?;
+        encoder.write_digest(self.prev_id)?;
+        encoder.write_u32(self.block_size)?;
+        encoder.write_u32(self.transactions_size)?;
+        encoder.write_u32(self.transactions_length)?;
+        encoder.write_digest(self.transactions_root)?;
+        encoder.write(&self.coinbase_amount)?;
+        encoder.write(&self.coinbase_output)?;
+        encoder.write(&self.retarget_timestamp)?;
+        encoder.write_memory(&self.pow_memory)?;
+        encoder.write_u32(self.pow_difficulty)
+    }
 
-        Ok(Digest::hash(&buf))
+    /// Encodes the fields of the `BlockHeader` identified by its id.
+    fn encode_body<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        self.encode_pow_body(encoder)?;
+        encoder.write_u64(self.pow_nonce)?;
+        encoder.write_digest(self.pow_digest)
     }
 
     /// Creates a new genesis `BlockHeader`.
@@ -236,14 +322,7 @@ impl BlockHeader {
         block_header.transactions_size = block.transactions_size;
         block_header.transactions_length = block.transactions_length;
 
-        let mut buf = Vec::new();
-        for i in 0..block.transactions_length as usize {
-            // pros: easier to build
-            // cons: cannot have set membership verif or search in any form
-            buf.write_all(&block.transactions_ids[i].to_bytes()?)?;
-        }
-
-        block_header.transactions_root = Digest::hash(&buf);
+        block_header.transactions_root = block.transactions_root()?;
 
         block_header.coinbase_amount = Amount::genesis_value();
         block_header.coinbase_output = if network_type == NetworkType::RegTest {
@@ -254,6 +333,8 @@ impl BlockHeader {
             Output::new_mainnet_genesis()?
         };
 
+        block_header.retarget_timestamp = timestamp;
+
         let pow_memory = Memory::from(GENESIS_MEMORY);
         block_header.pow_memory = pow_memory.clone();
 
@@ -309,24 +390,26 @@ impl BlockHeader {
 
         if self.height == 0 {
             if self.prev_id != Digest::default() {
-                return Err(ErrorKind::InvalidDigest.into());
+                return Err(ErrorKind::InvalidDigest.with_field("prev_id").with_values(Digest::default(), self.prev_id));
             }
 
             if self.transactions_length != 1 {
-                return Err(ErrorKind::InvalidLength.into());
+                return Err(ErrorKind::InvalidLength.with_field("transactions_length").with_values(1, self.transactions_length));
             }
 
             if block_id == Block::new_testnet_genesis()?.id {
                 if self.network_type != NetworkType::TestNet {
-                    return Err(ErrorKind::InvalidNetwork.into());
+                    return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::TestNet, self.network_type));
                 }
 
                 if self.timestamp != Timestamp::min_value() {
-                    return Err(ErrorKind::InvalidTimestamp.into());
+                    return Err(ErrorKind::InvalidTimestamp.with_field("timestamp").with_values(Timestamp::min_value(), self.timestamp));
                 }
 
-                if self.block_size != testnet_genesis.size()? {
-                    return Err(ErrorKind::InvalidLength.into());
+                let size = testnet_genesis.size()?;
+
+                if self.block_size != size {
+                    return Err(ErrorKind::InvalidLength.with_field("block_size").with_values(size, self.block_size));
                 }
                 
                 return Ok(true);
@@ -334,33 +417,35 @@ impl BlockHeader {
 
             if block_id == Block::new_mainnet_genesis()?.id {
                 if self.network_type != NetworkType::MainNet {
-                    return Err(ErrorKind::InvalidNetwork.into());
+                    return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::MainNet, self.network_type));
                 }
 
                 if self.timestamp != Timestamp::min_value() {
-                    return Err(ErrorKind::InvalidTimestamp.into());
+                    return Err(ErrorKind::InvalidTimestamp.with_field("timestamp").with_values(Timestamp::min_value(), self.timestamp));
                 }
 
-                if self.block_size != mainnet_genesis.size()? {
-                    return Err(ErrorKind::InvalidLength.into());
+                let size = mainnet_genesis.size()?;
+
+                if self.block_size != size {
+                    return Err(ErrorKind::InvalidLength.with_field("block_size").with_values(size, self.block_size));
                 }
 
                 return Ok(true);
             }
 
             if self.network_type != NetworkType::RegTest {
-                return Err(ErrorKind::InvalidNetwork.into());    
+                return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::RegTest, self.network_type));
             }
 
             if self.coinbase_amount != Amount::genesis_value() {
-                return Err(ErrorKind::InvalidGenesis.into());
+                return Err(ErrorKind::InvalidGenesis.with_field("coinbase_amount").with_values(Amount::genesis_value(), &self.coinbase_amount));
             }
 
             Ok(true)
         } else {
             if block_id == testnet_genesis.id ||
                 block_id == mainnet_genesis.id {
-                return Err(ErrorKind::InvalidBlock.into());
+                return Err(ErrorKind::InvalidBlock.with_field("block_id"));
             }
 
             Ok(false)
@@ -372,8 +457,12 @@ impl BlockHeader {
         Ok(self.to_bytes()?.len() as u32)
     }
 
-    /// Verifies the `BlockHeader` against a block and its previous `BlockHeader`.
-    pub fn verify(&self, block: &Block, prev_block_header: Option<&BlockHeader>) -> Result<bool> {
+    /// Verifies the `BlockHeader` against a block, its transactions and its previous
+    /// `BlockHeader`.
+    pub fn verify(&self,
+                  block: &Block,
+                  transactions: &[Transaction],
+                  prev_block_header: Option<&BlockHeader>) -> Result<bool> {
         self.validate()?;
         block.validate()?;
 
@@ -381,6 +470,8 @@ impl BlockHeader {
             if !block.is_genesis()? {
                 return Err(ErrorKind::InvalidBlock.into());
             }
+
+            verify_coinbase(self, &get_fees(block, transactions)?)?;
             
             return Ok(true);
         }
@@ -408,29 +499,54 @@ impl BlockHeader {
             return Ok(false);
         }
 
-        if self.height != prev_bh.height + 1 {
+        if self.transactions_size != block.transactions_size {
             return Ok(false);
         }
 
-        if self.prev_id != prev_bh.id {
+        if self.transactions_length != block.transactions_length {
             return Ok(false);
         }
 
-        if self.transactions_size != block.transactions_size {
+        if self.transactions_root != block.transactions_root()? {
             return Ok(false);
         }
 
-        if self.transactions_length != block.transactions_length {
+        // the fees are checked only against the block of the header
+        let fees = get_fees(block, transactions)?;
+        verify_coinbase(self, &fees)?;
+
+        self.verify_prev(prev_bh)
+    }
+
+    /// Verifies the `BlockHeader` against its previous `BlockHeader` only, as when
+    /// its block is not known yet.
+    pub fn verify_prev(&self, prev_block_header: &BlockHeader) -> Result<bool> {
+        self.validate()?;
+        prev_block_header.validate()?;
+
+        if self.network_type != prev_block_header.network_type {
+            return Ok(false);
+        }
+
+        if self.height != prev_block_header.height + 1 {
             return Ok(false);
         }
 
-        let pow_memory = get_memory(self.timestamp, block.timestamp, &prev_bh.pow_memory)?;
+        if self.prev_id != prev_block_header.id {
+            return Ok(false);
+        }
+
+        if self.retarget_timestamp != get_retarget_timestamp(prev_block_header) {
+            return Ok(false);
+        }
+
+        let pow_memory = get_memory(prev_block_header)?;
 
         if self.pow_memory != pow_memory {
             return Ok(false);
         }
         
-        let pow_difficulty = get_difficulty(self.timestamp, block.timestamp, prev_bh.pow_difficulty)?;
+        let pow_difficulty = get_difficulty(prev_block_header)?;
 
         if self.pow_difficulty != pow_difficulty {
             return Ok(false);
@@ -444,6 +560,52 @@ impl BlockHeader {
 
         Ok(pow.verify()?)
     }
+
+    /// Verifies the `BlockHeader` timestamp against its ancestors and the local time.
+    ///
+    /// The ancestors are the last `MEDIAN_TIME_SPAN` block headers of the chain, or all of
+    /// them when the chain is shorter, sorted by height and ending with the previous block
+    /// header. The timestamp has to be above their median timestamp and no more than
+    /// `MAX_TIMENOISE` seconds ahead of the local time.
+    pub fn verify_timestamp(&self, ancestors: &[BlockHeader], now: Timestamp) -> Result<bool> {
+        let window_length = self.height.min(MEDIAN_TIME_SPAN) as usize;
+
+        if ancestors.len() != window_length {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let mut child = self;
+
+        for ancestor in ancestors.iter().rev() {
+            if ancestor.id != child.prev_id ||
+                ancestor.height + 1 != child.height {
+                return Err(ErrorKind::InvalidChain.into());
+            }
+
+            child = ancestor;
+        }
+
+        if self.timestamp.diff(now) > MAX_TIMENOISE {
+            return Ok(false);
+        }
+
+        if ancestors.is_empty() {
+            return Ok(true);
+        }
+
+        Ok(self.timestamp > get_median_time_past(ancestors)?)
+    }
+
+    /// Verifies a `MerkleProof` of inclusion of a transaction in the `BlockHeader` block.
+    pub fn verify_transaction(&self, proof: &MerkleProof) -> Result<bool> {
+        proof.validate()?;
+
+        if proof.leaves_length != self.transactions_length {
+            return Ok(false);
+        }
+
+        proof.verify(self.transactions_root)
+    }
 }
 
 impl Default for BlockHeader {
@@ -462,6 +624,7 @@ impl Default for BlockHeader {
             transactions_root: Digest::default(),
             coinbase_amount: Amount::zero(),
             coinbase_output: Output::default(),
+            retarget_timestamp: Timestamp::default(),
             pow_memory: Memory::zero(),
             pow_difficulty: 0,
             pow_nonce: 0,
@@ -474,27 +637,7 @@ impl<'a> Identify<'a> for BlockHeader {
     type ID = Digest;
 
     fn id(&self) -> Result<Self::ID> {
-        let mut buf = Vec::new();
-
-        buf.write_all(&self.version.to_bytes()?)?;
-        buf.write_all(&self.network_type.to_bytes()?)?;
-        buf.write_all(&self.timestamp.to_bytes()?)?;
-
-        buf.write_all(&self.block_id.to_bytes()?)?;
-        buf.write_u32::<BigEndian>(self.height)?;
-        buf.write_all(&self.prev_id.to_bytes()?)?;
-
-        buf.write_u32::<BigEndian>(self.transactions_size)?;
-        buf.write_u32::<BigEndian>(self.transactions_length)?;
-        buf.write_all(&self.transactions_root.to_bytes()?)?;
-        buf.write_all(&self.coinbase_amount.to_bytes()?)?;
-        buf.write_all(&self.coinbase_output.to_bytes()?)?;
-        buf.write_all(&self.pow_memory.to_string().as_bytes())?;
-        buf.write_u32::<BigEndian>(self.pow_difficulty)?;
-        buf.write_u64::<BigEndian>(self.pow_nonce)?;
-        buf.write_all(&self.pow_digest.to_bytes()?)?;
-
-        Ok(Digest::hash(&buf))
+        encoding::digest(|encoder| self.encode_body(encoder))
     }
 
     fn id_from_bytes(b: &[u8]) -> Result<Self::ID> {
@@ -526,197 +669,227 @@ impl<'a> Identify<'a> for BlockHeader {
     }
 }
 
+impl Encode for BlockHeader {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.id)?;
+        self.encode_body(encoder)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<BlockHeader> {
+        let block_header = BlockHeader {
+            id: decoder.read_digest()?,
+            version: decoder.read()?,
+            network_type: decoder.read()?,
+            timestamp: decoder.read()?,
+            block_id: decoder.read_digest()?,
+            height: decoder.read_u32()?,
+            prev_id: decoder.read_digest()?,
+            block_size: decoder.read_u32()?,
+            transactions_size: decoder.read_u32()?,
+            transactions_length: decoder.read_u32()?,
+            transactions_root: decoder.read_digest()?,
+            coinbase_amount: decoder.read()?,
+            coinbase_output: decoder.read()?,
+            retarget_timestamp: decoder.read()?,
+            pow_memory: decoder.read_memory()?,
+            pow_difficulty: decoder.read_u32()?,
+            pow_nonce: decoder.read_u64()?,
+            pow_digest: decoder.read_digest()?,
+        };
+
+        Ok(block_header)
+    }
+}
+
 impl Validate for BlockHeader {
     fn validate(&self) -> Result<()> {
-        self.version.validate()?;
-        self.timestamp.validate()?;
-        
-        if self.id != self.id()? {
-            return Err(ErrorKind::InvalidDigest.into());
+        self.validate_report()?.to_result()
+    }
+}
+
+impl ValidateReport for BlockHeader {
+    fn validate_report(&self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+
+        if self.version.validate().is_err() {
+            report.add(Rule::BlockHeaderVersion);
+        }
+
+        if self.timestamp.validate().is_err() {
+            report.add(Rule::BlockHeaderTimestamp);
+        }
+
+        if self.retarget_timestamp.validate().is_err() || self.retarget_timestamp > self.timestamp {
+            report.add(Rule::BlockHeaderRetargetTimestamp);
+        }
+
+        let id = self.id()?;
+
+        if self.id != id {
+            report.add_values(Rule::BlockHeaderId, id, self.id);
         }
 
         if self.prev_id == self.id {
-            return Err(ErrorKind::InvalidDigest.into());
+            report.add(Rule::BlockHeaderPrevId);
         }
 
-        if self.transactions_size == 0 ||
-            self.transactions_length == 0 {
-            return Err(ErrorKind::InvalidLength.into()); 
+        if self.transactions_size == 0 {
+            report.add(Rule::BlockHeaderTransactionsSize);
         }
 
-        self.coinbase_output.validate()?;
+        if self.transactions_length == 0 {
+            report.add(Rule::BlockHeaderTransactionsLength);
+        }
 
-        if self.height == 0 {
-            if self.coinbase_amount != Amount::genesis_value() {
-                return Err(ErrorKind::OutOfBound.into());
-            }
-        } else {
-            if self.coinbase_amount != get_coinbase_amount(self.height) {
-                return Err(ErrorKind::OutOfBound.into());
-            }
+        report.merge_field("coinbase_output", self.coinbase_output.validate_report()?);
+
+        let subsidy = Amount::from(get_subsidy(self.height)?);
+
+        // the fees are known only with the transactions of the block
+        if self.coinbase_amount < subsidy {
+            report.add_values(Rule::BlockHeaderCoinbaseAmount, &subsidy, &self.coinbase_amount);
         }
 
         if self.coinbase_amount != self.coinbase_output.amount {
-            return Err(ErrorKind::OutOfBound.into());
+            report.add_values(Rule::BlockHeaderCoinbaseOutput, &self.coinbase_amount, &self.coinbase_output.amount);
+        }
+
+        if self.height == 0 {
+            let genesis_output = match self.network_type {
+                NetworkType::TestNet => Some(Output::new_testnet_genesis()?),
+                NetworkType::MainNet => Some(Output::new_mainnet_genesis()?),
+                NetworkType::RegTest => None,
+            };
+
+            if let Some(genesis_output) = genesis_output {
+                if self.coinbase_output != genesis_output {
+                    report.add(Rule::BlockHeaderCoinbaseGenesis);
+                }
+            }
         }
 
         let pow_salt = self.pow_salt()?;
 
-        let mut pow = PoW::from_memory(pow_salt, &self.pow_memory, self.pow_difficulty)?;
-        pow.nonce = Some(self.pow_nonce);
-        pow.digest = Some(self.pow_digest);
+        let pow_verified = match PoW::from_memory(pow_salt, &self.pow_memory, self.pow_difficulty) {
+            Ok(mut pow) => {
+                pow.nonce = Some(self.pow_nonce);
+                pow.digest = Some(self.pow_digest);
+                pow.verify().unwrap_or(false)
+            },
+            Err(_) => false,
+        };
 
-        if !pow.verify()? {
-            return Err(ErrorKind::InvalidPoW.into());
+        if !pow_verified {
+            report.add(Rule::BlockHeaderPoW);
         }
 
-        let _ = self.is_genesis()?;
+        if self.is_genesis().is_err() {
+            report.add(Rule::BlockHeaderGenesis);
+        }
 
         if self.height == 1 {
-            let prev_id = self.prev_id; 
-
-            if self.network_type == NetworkType::TestNet {
-                if prev_id != BlockHeader::new_testnet_genesis()?.id {
-                    return Err(ErrorKind::InvalidNetwork.into());
-                }
-            }
-
-            if self.network_type == NetworkType::MainNet {
-                if prev_id != BlockHeader::new_mainnet_genesis()?.id {
-                    return Err(ErrorKind::InvalidNetwork.into());
+            let genesis_id = match self.network_type {
+                NetworkType::TestNet => Some(BlockHeader::new_testnet_genesis()?.id),
+                NetworkType::MainNet => Some(BlockHeader::new_mainnet_genesis()?.id),
+                NetworkType::RegTest => None,
+            };
+
+            if let Some(genesis_id) = genesis_id {
+                if self.prev_id != genesis_id {
+                    report.add_values(Rule::BlockHeaderNetwork, genesis_id, self.prev_id);
                 }
             }
         }
 
-        Ok(())
+        Ok(report)
     }
 }
 
-impl<'a> Serialize<'a> for BlockHeader {
-    fn to_json(&self) -> Result<String> {
-        let obj = json!({
-            "id": self.string_id()?,
-            "version": self.version.to_string(),
-            "network_type": self.network_type.to_hex()?,
-            "timestamp": self.timestamp.to_string(),
-            "block_id": self.block_id.to_hex()?,
-            "height": self.height,
-            "prev_id": self.prev_id.to_hex()?,
-            "block_size": self.block_size,
-            "transactions_size": self.transactions_size,
-            "transactions_length": self.transactions_length,
-            "transactions_root": self.transactions_root.to_hex()?,
-            "coinbase_amount": self.coinbase_amount.to_string(),
-            "coinbase_output": self.coinbase_output.to_json()?,
-            "pow_memory": self.pow_memory.to_string(),
-            "pow_difficulty": self.pow_difficulty,
-            "pow_nonce": self.pow_nonce,
-            "pow_digest": self.pow_digest.to_hex()?,
-        });
-
-        let s = obj.to_string();
-
-        Ok(s)
+impl Json for BlockHeader {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_version("version", &self.version)?;
+        writer.write_network_type("network_type", self.network_type)?;
+        writer.write_timestamp("timestamp", self.timestamp)?;
+        writer.write_digest("block_id", self.block_id)?;
+        writer.write_u32("height", self.height)?;
+        writer.write_digest("prev_id", self.prev_id)?;
+        writer.write_u32("block_size", self.block_size)?;
+        writer.write_u32("transactions_size", self.transactions_size)?;
+        writer.write_u32("transactions_length", self.transactions_length)?;
+        writer.write_digest("transactions_root", self.transactions_root)?;
+        writer.write_amount("coinbase_amount", &self.coinbase_amount)?;
+        writer.write("coinbase_output", &self.coinbase_output)?;
+        writer.write_timestamp("retarget_timestamp", self.retarget_timestamp)?;
+        writer.write_memory("pow_memory", &self.pow_memory)?;
+        writer.write_u32("pow_difficulty", self.pow_difficulty)?;
+        writer.write_u64("pow_nonce", self.pow_nonce)?;
+        writer.write_digest("pow_digest", self.pow_digest)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_str: String = json::from_value(id_value)?;
-        let id = Block::id_from_string(&id_str)?;
-        
-        let version_value = obj["version"].clone();
-        let version_str: String = json::from_value(version_value)?;
-        let version = Version::from_string(&version_str)?;
-        
-        let network_type_value = obj["network_type"].clone();
-        let network_type_hex: String = json::from_value(network_type_value)?;
-        let network_type = NetworkType::from_hex(&network_type_hex)?;
-        
-        let timestamp_value = obj["timestamp"].clone();
-        let timestamp_str: String = json::from_value(timestamp_value)?;
-        let timestamp = Timestamp::from_string(&timestamp_str)?;
-        
-        let block_id_value = obj["block_id"].clone();
-        let block_id_str: String = json::from_value(block_id_value)?;
-        let block_id = Block::id_from_string(&block_id_str)?;
-
-        let height_value = obj["height"].clone();
-        let height: u32 = json::from_value(height_value)?;
-        
-        let prev_id_value = obj["prev_id"].clone();
-        let prev_id_str: String = json::from_value(prev_id_value)?;
-        let prev_id = Block::id_from_string(&prev_id_str)?;
-
-        let block_size_value = obj["block_size"].clone();
-        let block_size: u32 = json::from_value(block_size_value)?;
-
-        let transactions_size_value = obj["transactions_size"].clone();
-        let transactions_size: u32 = json::from_value(transactions_size_value)?;
-
-        let transactions_length_value = obj["transactions_length"].clone();
-        let transactions_length: u32 = json::from_value(transactions_length_value)?;
-
-        let txs_root_value = obj["transactions_root"].clone();
-        let txs_root_str: String = json::from_value(txs_root_value)?;
-        let txs_root = Block::id_from_string(&txs_root_str)?;
-
-        let coinbase_amount_value = obj["coinbase_amount"].clone();
-        let coinbase_amount_str: String = json::from_value(coinbase_amount_value)?;
-        let coinbase_amount = Amount::from_string(&coinbase_amount_str)?;
-
-        let coinbase_output_value = obj["coinbase_output"].clone();
-        let coinbase_output_json: String = json::from_value(coinbase_output_value)?;
-        let coinbase_output = Output::from_json(&coinbase_output_json)?;
-
-        let pow_memory_value = obj["pow_memory"].clone();
-        let pow_memory_str: String = json::from_value(pow_memory_value)?;
-        let pow_memory = Memory::from_string(&pow_memory_str)?;
-
-        let pow_difficulty_value = obj["pow_difficulty"].clone();
-        let pow_difficulty: u32 = json::from_value(pow_difficulty_value)?;
-
-        let pow_nonce_value = obj["pow_nonce"].clone();
-        let pow_nonce: u64 = json::from_value(pow_nonce_value)?;
-
-        let pow_digest_value = obj["pow_digest"].clone();
-        let pow_digest_str: String = json::from_value(pow_digest_value)?;
-        let pow_digest = Digest::from_hex(&pow_digest_str)?;
 
+    fn read_json(reader: &mut JsonReader) -> Result<BlockHeader> {
         let block_header = BlockHeader {
-            id: id,
-            version: version,
-            network_type: network_type,
-            timestamp: timestamp,
-            block_id: block_id,
-            height: height,
-            prev_id: prev_id,
-            block_size: block_size,
-            transactions_size: transactions_size,
-            transactions_length: transactions_length,
-            transactions_root: txs_root,
-            coinbase_amount: coinbase_amount,
-            coinbase_output: coinbase_output,
-            pow_memory: pow_memory,
-            pow_difficulty: pow_difficulty,
-            pow_nonce: pow_nonce,
-            pow_digest: pow_digest,
+            id: reader.read_digest("id")?,
+            version: reader.read_version("version")?,
+            network_type: reader.read_network_type("network_type")?,
+            timestamp: reader.read_timestamp("timestamp")?,
+            block_id: reader.read_digest("block_id")?,
+            height: reader.read_u32("height")?,
+            prev_id: reader.read_digest("prev_id")?,
+            block_size: reader.read_u32("block_size")?,
+            transactions_size: reader.read_u32("transactions_size")?,
+            transactions_length: reader.read_u32("transactions_length")?,
+            transactions_root: reader.read_digest("transactions_root")?,
+            coinbase_amount: reader.read_amount("coinbase_amount")?,
+            coinbase_output: reader.read("coinbase_output")?,
+            retarget_timestamp: reader.read_timestamp("retarget_timestamp")?,
+            pow_memory: reader.read_memory("pow_memory")?,
+            pow_difficulty: reader.read_u32("pow_difficulty")?,
+            pow_nonce: reader.read_u64("pow_nonce")?,
+            pow_digest: reader.read_digest("pow_digest")?,
         };
 
         Ok(block_header)
     }
-    
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::to_vec(self)?;
 
-        Ok(buf)
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("The header of a block.")
+            .reference("id", "Digest")
+            .reference("version", "Version")
+            .reference("network_type", "NetworkType")
+            .reference("timestamp", "Timestamp")
+            .reference("block_id", "Digest")
+            .reference("height", "U32")
+            .reference("prev_id", "Digest")
+            .reference("block_size", "U32")
+            .reference("transactions_size", "U32")
+            .reference("transactions_length", "U32")
+            .reference("transactions_root", "Digest")
+            .reference("coinbase_amount", "Amount")
+            .reference("coinbase_output", "Output")
+            .reference("retarget_timestamp", "Timestamp")
+            .reference("pow_memory", "Memory")
+            .reference("pow_difficulty", "U32")
+            .reference("pow_nonce", "U64")
+            .reference("pow_digest", "Digest")
+    }
+}
+
+impl<'a> Serialize<'a> for BlockHeader {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
     
     fn from_bytes(b: &[u8]) -> Result<Self> {
-        let tx = messagepack::from_slice(b)?;
-
-        Ok(tx)
+        encoding::decode(b)
     }
     
     fn to_hex(&self) -> Result<String> {
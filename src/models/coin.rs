@@ -7,18 +7,18 @@
 
 //! The `coin` module provides the coin type and methods.
 
-use serde_json as json;
 use rmp_serde as messagepack;
 use hex;
 
 use error::ErrorKind;
 use result::Result;
-use traits::{Validate, Identify, Serialize};
+use traits::{Validate, Identify, Json, Serialize};
 use crypto::{Digest, Scalar, ZKPWitness, ZKPProof};
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoHexSerialize;
 use crypto::Validate as CryptoValidate;
 use utils::Amount;
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
 use models::output::Output;
 
 /// A `Coin` is an `Output` enriched with the instance needed to redeem it.
@@ -122,49 +122,43 @@ impl Validate for Coin {
     }
 }
 
-impl<'a> Serialize<'a> for Coin {
-    fn to_json(&self) -> Result<String> {
-        let obj = json!({
-            "id": self.string_id()?,
-            "instance": self.instance.to_hex()?,
-            "witness": self.witness.to_hex()?,
-            "amount": self.amount.to_string(),
-        });
-
-        let s = obj.to_string();
-
-        Ok(s)
+impl Json for Coin {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_scalar("instance", self.instance)?;
+        writer.write_witness("witness", self.witness)?;
+        writer.write_amount("amount", &self.amount)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_hex: String = json::from_value(id_value)?;
-        let id = Digest::from_hex(&id_hex)?;
-        
-        let instance_value = obj["instance"].clone();
-        let instance_hex: String = json::from_value(instance_value)?;
-        let instance = Scalar::from_hex(&instance_hex)?;
-        
-        let witness_value = obj["witness"].clone();
-        let witness_hex: String = json::from_value(witness_value)?;
-        let witness = ZKPWitness::from_hex(&witness_hex)?;
-
-        let amount_value = obj["amount"].clone();
-        let amount_str: String = json::from_value(amount_value)?;
-        let amount = Amount::from_string(&amount_str)?;
 
+    fn read_json(reader: &mut JsonReader) -> Result<Coin> {
         let coin = Coin {
-            id: id,
-            instance: instance,
-            witness: witness,
-            amount: amount,
+            id: reader.read_digest("id")?,
+            instance: reader.read_scalar("instance")?,
+            witness: reader.read_witness("witness")?,
+            amount: reader.read_amount("amount")?,
         };
 
         Ok(coin)
     }
-    
+
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("An output with the instance needed to spend it.")
+            .reference("id", "Digest")
+            .reference("instance", "Scalar")
+            .reference("witness", "Point")
+            .reference("amount", "Amount")
+    }
+}
+
+impl<'a> Serialize<'a> for Coin {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
     fn to_bytes(&self) -> Result<Vec<u8>> {
         let buf = messagepack::to_vec(self)?;
 
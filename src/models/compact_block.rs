@@ -0,0 +1,298 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `compact_block` module provides the `CompactBlock` type and methods, used to
+//! relay a block as the short ids of its transactions, and the `PartialBlock` type,
+//! used to reconstruct the block from the transactions already known by a node.
+
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+
+use constants::SHORT_ID_LENGTH;
+use error::ErrorKind;
+use result::{Result, ResultExt};
+use traits::{Identify, Validate, Encode};
+use utils::{Version, NetworkType, Timestamp};
+use utils::encoding::{Encoder, Decoder};
+use crypto::Digest;
+use crypto::BinarySerialize as CryptoBinarySerialize;
+use models::transaction::Transaction;
+use models::block::Block;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+
+/// Returns the key of the short ids of a block, salted to make the collisions
+/// differ between the relays of the same block.
+pub fn get_short_id_key(block_id: Digest, salt: u64) -> Result<Digest> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&block_id.to_bytes()?)?;
+    buf.write_u64::<BigEndian>(salt)?;
+
+    Ok(Digest::hash(&buf))
+}
+
+/// Returns the short id of a transaction: the first `SHORT_ID_LENGTH` bytes of
+/// the hash of the short ids key and of the transaction id.
+pub fn get_short_id(key: Digest, transaction_id: Digest) -> Result<u64> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&key.to_bytes()?)?;
+    buf.write_all(&transaction_id.to_bytes()?)?;
+
+    let digest = Digest::hash(&buf).to_bytes()?;
+
+    Ok(BigEndian::read_uint(&digest, SHORT_ID_LENGTH))
+}
+
+/// A `CompactBlock` is a `Block` whose transactions ids are replaced by salted
+/// short ids.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CompactBlock {
+    /// The block id.
+    pub block_id: Digest,
+    /// The version of the library.
+    pub version: Version,
+    /// The protocol network type.
+    pub network_type: NetworkType,
+    /// The unix timestamp of the time the block.
+    pub timestamp: Timestamp,
+    /// The size of the block transactions.
+    pub transactions_size: u32,
+    /// The length of the block transactions.
+    pub transactions_length: u32,
+    /// The salt of the short ids.
+    pub salt: u64,
+    /// The short ids of the block transactions, in the block order.
+    pub short_ids: Vec<u64>,
+}
+
+impl CompactBlock {
+    /// Creates a new `CompactBlock` from a `Block` and a salt.
+    pub fn new(block: &Block, salt: u64) -> Result<CompactBlock> {
+        block.validate()?;
+
+        let key = get_short_id_key(block.id, salt)?;
+
+        let mut short_ids = Vec::new();
+
+        for id in &block.transactions_ids {
+            short_ids.push(get_short_id(key, *id)?);
+        }
+
+        let compact_block = CompactBlock {
+            block_id: block.id,
+            version: block.version.clone(),
+            network_type: block.network_type,
+            timestamp: block.timestamp,
+            transactions_size: block.transactions_size,
+            transactions_length: block.transactions_length,
+            salt: salt,
+            short_ids: short_ids,
+        };
+
+        compact_block.validate()?;
+
+        Ok(compact_block)
+    }
+
+    /// Returns the short id of a transaction in the `CompactBlock`.
+    pub fn short_id(&self, transaction_id: Digest) -> Result<u64> {
+        let key = get_short_id_key(self.block_id, self.salt)?;
+
+        get_short_id(key, transaction_id)
+    }
+
+    /// Starts the reconstruction of the `Block` from a pool of known transactions.
+    /// The transactions whose short id is not found in the pool, or is found more
+    /// than once, are left missing.
+    pub fn reconstruct<'a, I>(&self, pool: I) -> Result<PartialBlock>
+        where I: IntoIterator<Item=&'a Transaction>
+    {
+        self.validate()?;
+
+        let key = get_short_id_key(self.block_id, self.salt)?;
+
+        let mut indexes = BTreeMap::new();
+
+        for (index, short_id) in self.short_ids.iter().enumerate() {
+            indexes.insert(*short_id, index);
+        }
+
+        let mut transactions = vec![None; self.short_ids.len()];
+        let mut collisions = BTreeSet::new();
+
+        for transaction in pool {
+            let short_id = get_short_id(key, transaction.id)?;
+
+            let index = match indexes.get(&short_id) {
+                Some(index) => *index,
+                None => continue,
+            };
+
+            match transactions[index] {
+                Some(ref found) if found != transaction => {
+                    collisions.insert(index);
+                },
+                Some(_) => {},
+                None => transactions[index] = Some(transaction.clone()),
+            }
+        }
+
+        for index in collisions {
+            transactions[index] = None;
+        }
+
+        let partial_block = PartialBlock {
+            compact_block: self.clone(),
+            transactions: transactions,
+        };
+
+        Ok(partial_block)
+    }
+}
+
+impl Encode for CompactBlock {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.block_id)?;
+        encoder.write(&self.version)?;
+        encoder.write(&self.network_type)?;
+        encoder.write(&self.timestamp)?;
+        encoder.write_u32(self.transactions_size)?;
+        encoder.write_u32(self.transactions_length)?;
+        encoder.write_u64(self.salt)?;
+        encoder.write(&self.short_ids)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<CompactBlock> {
+        let compact_block = CompactBlock {
+            block_id: decoder.read_digest()?,
+            version: decoder.read()?,
+            network_type: decoder.read()?,
+            timestamp: decoder.read()?,
+            transactions_size: decoder.read_u32()?,
+            transactions_length: decoder.read_u32()?,
+            salt: decoder.read_u64()?,
+            short_ids: decoder.read()?,
+        };
+
+        Ok(compact_block)
+    }
+}
+
+impl Validate for CompactBlock {
+    fn validate(&self) -> Result<()> {
+        self.version.validate()?;
+        self.timestamp.validate()?;
+
+        if self.transactions_size == 0 ||
+            self.transactions_length == 0 {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        if self.transactions_length as usize != self.short_ids.len() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        for short_id in &self.short_ids {
+            if *short_id >> (8 * SHORT_ID_LENGTH) != 0 {
+                return Err(ErrorKind::OutOfBound.into());
+            }
+        }
+
+        let short_ids: BTreeSet<u64> = self.short_ids.iter().cloned().collect();
+
+        if short_ids.len() != self.short_ids.len() {
+            return Err(ErrorKind::DuplicatesFound.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// A `PartialBlock` is a `Block` being reconstructed from a `CompactBlock`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PartialBlock {
+    /// The compact block.
+    pub compact_block: CompactBlock,
+    /// The block transactions, in the block order, if known.
+    transactions: Vec<Option<Transaction>>,
+}
+
+impl PartialBlock {
+    /// Returns the indexes of the missing transactions.
+    pub fn missing_indexes(&self) -> Vec<u32> {
+        self.transactions.iter()
+            .enumerate()
+            .filter(|&(_, transaction)| transaction.is_none())
+            .map(|(index, _)| index as u32)
+            .collect()
+    }
+
+    /// Verifies if all the transactions are known.
+    pub fn is_complete(&self) -> bool {
+        self.transactions.iter().all(|transaction| transaction.is_some())
+    }
+
+    /// Fills the missing transactions, given in the order of their indexes. The
+    /// transactions are validated, as their short ids only cover their ids.
+    pub fn fill(&mut self, transactions: &[Transaction]) -> Result<()> {
+        let missing_indexes = self.missing_indexes();
+
+        if transactions.len() != missing_indexes.len() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        for (index, transaction) in missing_indexes.iter().zip(transactions.iter()) {
+            transaction.validate().with_index("transactions", *index)?;
+
+            let short_id = self.compact_block.short_id(transaction.id)?;
+
+            if short_id != self.compact_block.short_ids[*index as usize] {
+                return Err(ErrorKind::InvalidTransaction.into());
+            }
+        }
+
+        for (index, transaction) in missing_indexes.into_iter().zip(transactions.iter()) {
+            self.transactions[index as usize] = Some(transaction.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the reconstructed `Block` and its transactions. A block id not
+    /// matching the compact block one means that a wrong transaction was taken
+    /// from the pool, and the full block should be requested.
+    pub fn to_block(&self) -> Result<(Block, Vec<Transaction>)> {
+        if !self.is_complete() {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        let transactions: Vec<Transaction> = self.transactions.iter()
+            .cloned()
+            .map(|transaction| transaction.unwrap())
+            .collect();
+
+        let block = Block {
+            id: self.compact_block.block_id,
+            version: self.compact_block.version.clone(),
+            network_type: self.compact_block.network_type,
+            timestamp: self.compact_block.timestamp,
+            transactions_size: self.compact_block.transactions_size,
+            transactions_length: self.compact_block.transactions_length,
+            transactions_ids: transactions.iter().map(|transaction| transaction.id).collect(),
+        };
+
+        if block.id != block.id()? {
+            return Err(ErrorKind::InvalidID.into());
+        }
+
+        block.validate()?;
+
+        Ok((block, transactions))
+    }
+}
@@ -7,21 +7,21 @@
 
 //! The `data` module provides the data type and methods.
 
-use serde_json as json;
-use rmp_serde as messagepack;
 use hex;
-use byteorder::{BigEndian, WriteBytesExt};
 
 use error::ErrorKind;
 use result::Result;
-use traits::{Identify, Validate, Serialize};
+use traits::{Identify, Validate, ValidateReport, Json, Serialize, Encode};
 use crypto::{Digest, SecretKey, PublicKey};
 use crypto::{assym_encrypt, assym_decrypt};
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoSerialize;
 use utils::Timestamp;
+use utils::encoding::{self, Encoder, Decoder};
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
+use models::report::{Rule, ValidationReport};
 
-use std::io::Write;
+use std::io::{Read, Write};
 
 /// A `Data` is custom encrypted data written on the blockchain.
 #[derive(Clone, Eq, PartialEq, Default, Debug, Serialize, Deserialize)]
@@ -90,16 +90,7 @@ impl<'a> Identify<'a> for Data {
     type ID = Digest;
 
     fn id(&self) -> Result<Self::ID> {
-        let mut buf = Vec::new();
-
-        buf.write_all(&self.from.to_bytes()?)?;
-        buf.write_all(&self.to.to_bytes()?)?;
-        buf.write_u32::<BigEndian>(self.plain_size)?;
-        buf.write_u32::<BigEndian>(self.cyph_size)?;
-        buf.write_u32::<BigEndian>(self.duration)?;
-        buf.write_all(&self.cyphertext)?;
-
-        Ok(Digest::hash(&buf))
+        encoding::digest(|encoder| self.encode_body(encoder))
     }
 
     fn id_from_bytes(b: &[u8]) -> Result<Self::ID> {
@@ -131,117 +122,138 @@ impl<'a> Identify<'a> for Data {
     }
 }
 
+impl Data {
+    /// Encodes the fields of the `Data` identified by its id.
+    fn encode_body<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_public_key(self.from)?;
+        encoder.write_public_key(self.to)?;
+        encoder.write_u32(self.plain_size)?;
+        encoder.write_u32(self.cyph_size)?;
+        encoder.write_u32(self.duration)?;
+        encoder.write_bytes(&self.cyphertext)
+    }
+}
+
+impl Encode for Data {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.id)?;
+        self.encode_body(encoder)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Data> {
+        let data = Data {
+            id: decoder.read_digest()?,
+            from: decoder.read_public_key()?,
+            to: decoder.read_public_key()?,
+            plain_size: decoder.read_u32()?,
+            cyph_size: decoder.read_u32()?,
+            duration: decoder.read_u32()?,
+            cyphertext: decoder.read_bytes()?,
+        };
+
+        Ok(data)
+    }
+}
+
 impl Validate for Data {
     fn validate(&self) -> Result<()> {
-        if self.id != self.id()? {
-            return Err(ErrorKind::InvalidDigest.into());
+        self.validate_report()?.to_result()
+    }
+}
+
+impl ValidateReport for Data {
+    fn validate_report(&self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+
+        let id = self.id()?;
+
+        if self.id != id {
+            report.add_values(Rule::DataId, id, self.id);
         }
-        
+
         if self.from == self.to {
-            return Err(ErrorKind::InvalidPublicKey.into());
+            report.add(Rule::DataKeys);
         }
 
         let plain_size = self.plain_size;
         let cyph_size = self.cyph_size;
 
-        if plain_size > cyph_size {
-            return Err(ErrorKind::InvalidLength.into());
-        }
-
-        if plain_size % 16 == 0 {
-            return Err(ErrorKind::InvalidLength.into());
-        }
+        let plain_remainder = plain_size % 16;
 
-        if cyph_size % 16 != 0 {
-            return Err(ErrorKind::InvalidLength.into());
+        if plain_size > cyph_size || plain_remainder == 0 {
+            report.add(Rule::DataPlainSize);
         }
 
-        let padding = 16 - (plain_size % 16);
+        let padded_size = u64::from(plain_size) + 16 - u64::from(plain_remainder);
 
-        if cyph_size != plain_size + padding {
-            return Err(ErrorKind::InvalidLength.into());
+        if u64::from(cyph_size) != padded_size {
+            report.add_values(Rule::DataCyphSize, padded_size, cyph_size);
         }
 
         if self.cyph_size as usize != self.cyphertext.len() {
-            return Err(ErrorKind::InvalidLength.into());
+            report.add_values(Rule::DataCyphertext, self.cyph_size, self.cyphertext.len());
         }
 
         if self.duration == 0 {
-            return Err(ErrorKind::InvalidDuration.into());
+            report.add(Rule::DataDuration);
         }
 
-        Ok(())
+        Ok(report)
     }
 }
 
-impl<'a> Serialize<'a> for Data {
-    fn to_json(&self) -> Result<String> {
-        let obj = json!({
-            "id": self.string_id()?,
-            "from": self.from.to_hex()?,
-            "to": self.to.to_hex()?,
-            "plain_size": self.plain_size,
-            "cyph_size": self.cyph_size,
-            "duration": self.duration,
-            "cyphertext": hex::encode(&self.cyphertext),
-        });
-
-        let s = obj.to_string();
-
-        Ok(s)
+impl Json for Data {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_public_key("from", self.from)?;
+        writer.write_public_key("to", self.to)?;
+        writer.write_u32("plain_size", self.plain_size)?;
+        writer.write_u32("cyph_size", self.cyph_size)?;
+        writer.write_u32("duration", self.duration)?;
+        writer.write_bytes("cyphertext", &self.cyphertext)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_str: String = json::from_value(id_value)?;
-        let id = Data::id_from_string(&id_str)?;
-
-        let from_value = obj["from"].clone();
-        let from_hex: String = json::from_value(from_value)?;
-        let from = PublicKey::from_hex(&from_hex)?;
-        
-        let to_value = obj["to"].clone();
-        let to_hex: String = json::from_value(to_value)?;
-        let to = PublicKey::from_hex(&to_hex)?;
-
-        let plain_size_value = obj["plain_size"].clone();
-        let plain_size: u32 = json::from_value(plain_size_value)?;
-
-        let cyph_size_value = obj["cyph_size"].clone();
-        let cyph_size: u32 = json::from_value(cyph_size_value)?;
-
-        let duration_value = obj["duration"].clone();
-        let duration: u32 = json::from_value(duration_value)?;
-        
-        let cyphertext_value = obj["cyphertext"].clone();
-        let cyphertext_hex: String = json::from_value(cyphertext_value)?;
-        let cyphertext = hex::decode(&cyphertext_hex)?;
 
+    fn read_json(reader: &mut JsonReader) -> Result<Data> {
         let data = Data {
-            id: id,
-            from: from,
-            to: to,
-            plain_size: plain_size,
-            cyph_size: cyph_size,
-            duration: duration,
-            cyphertext: cyphertext,
+            id: reader.read_digest("id")?,
+            from: reader.read_public_key("from")?,
+            to: reader.read_public_key("to")?,
+            plain_size: reader.read_u32("plain_size")?,
+            cyph_size: reader.read_u32("cyph_size")?,
+            duration: reader.read_u32("duration")?,
+            cyphertext: reader.read_bytes("cyphertext")?,
         };
 
         Ok(data)
     }
-    
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::to_vec(self)?;
 
-        Ok(buf)
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("Data encrypted from a user to another.")
+            .reference("id", "Digest")
+            .reference("from", "Point")
+            .reference("to", "Point")
+            .reference("plain_size", "U32")
+            .reference("cyph_size", "U32")
+            .reference("duration", "U32")
+            .reference("cyphertext", "Bytes")
+    }
+}
+
+impl<'a> Serialize<'a> for Data {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
     
     fn from_bytes(b: &[u8]) -> Result<Self> {
-        let data = messagepack::from_slice(b)?;
-
-        Ok(data)
+        encoding::decode(b)
     }
     
     fn to_hex(&self) -> Result<String> {
@@ -7,18 +7,21 @@
 
 //! The `input` module provides the transaction input type and methods.
 
-use serde_json as json;
-use rmp_serde as messagepack;
 use hex;
 
 use result::Result;
-use traits::{Identify, Validate, Serialize};
+use traits::{Identify, Validate, ValidateReport, Json, Serialize, Encode};
+use utils::encoding::{self, Encoder, Decoder};
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
 use crypto::{Digest, ZKPProof};
 use crypto::Validate as CryptoValidate;
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoHexSerialize;
 use models::output::Output;
 use models::coin::Coin;
+use models::report::{Rule, ValidationReport};
+
+use std::io::{Read, Write};
 
 /// An `Input` is a reference to a past output used in transactions
 /// to spend the output.
@@ -88,55 +91,77 @@ impl<'a> Identify<'a> for Input {
     }
 }
 
+impl Encode for Input {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.id)?;
+        encoder.write_proof(self.proof)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Input> {
+        let input = Input {
+            id: decoder.read_digest()?,
+            proof: decoder.read_proof()?,
+        };
+
+        Ok(input)
+    }
+}
+
 impl Validate for Input {
     fn validate(&self) -> Result<()> {
-        self.proof.validate()?;
-
-        Ok(())
+        self.validate_report()?.to_result()
     }
 }
 
-impl<'a> Serialize<'a> for Input {
-    fn to_json(&self) -> Result<String> {
-        let obj = json!({
-            "id": self.id.to_hex()?,
-            "proof": self.proof.to_hex()?,
-        });
+impl ValidateReport for Input {
+    fn validate_report(&self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
 
-        let s = obj.to_string();
+        if self.proof.validate().is_err() {
+            report.add(Rule::InputProof);
+        }
 
-        Ok(s)
+        Ok(report)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_hex: String = json::from_value(id_value)?;
-        let id = Digest::from_hex(&id_hex)?;
+}
 
-        let proof_value = obj["proof"].clone();
-        let proof_hex: String = json::from_value(proof_value)?;
-        let proof = ZKPProof::from_hex(&proof_hex)?;
+impl Json for Input {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_proof("proof", self.proof)
+    }
 
+    fn read_json(reader: &mut JsonReader) -> Result<Input> {
         let input = Input {
-            id: id,
-            proof: proof,
+            id: reader.read_digest("id")?,
+            proof: reader.read_proof("proof")?,
         };
 
         Ok(input)
     }
-    
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::to_vec(self)?;
 
-        Ok(buf)
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("An input of a transaction, spending an output.")
+            .reference("id", "Digest")
+            .reference("proof", "Proof")
+    }
+}
+
+impl<'a> Serialize<'a> for Input {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
     
     fn from_bytes(b: &[u8]) -> Result<Self> {
-        let input = messagepack::from_slice(b)?;
-
-        Ok(input)
+        encoding::decode(b)
     }
     
     fn to_hex(&self) -> Result<String> {
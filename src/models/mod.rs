@@ -7,6 +7,7 @@
 
 //! The `models` module provides the Yobicash model types and methods.
 
+pub mod report;
 pub mod data;
 pub mod output;
 pub mod coin;
@@ -14,7 +15,12 @@ pub mod input;
 pub mod transaction;
 pub mod block;
 pub mod block_header;
+pub mod monetary_policy;
+pub mod compact_block;
+pub mod block_filter;
+pub mod schema;
 
+pub use self::report::*;
 pub use self::data::*;
 pub use self::output::*;
 pub use self::coin::*;
@@ -22,3 +28,7 @@ pub use self::input::*;
 pub use self::transaction::*;
 pub use self::block::*;
 pub use self::block_header::*;
+pub use self::monetary_policy::*;
+pub use self::compact_block::*;
+pub use self::block_filter::*;
+pub use self::schema::*;
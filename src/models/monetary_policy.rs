@@ -0,0 +1,153 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `monetary_policy` module provides the coin issuance schedule and the consensus
+//! check of the coinbase of the block headers.
+//!
+//! The coinbase of a block header claims the subsidy of its height and the fees of
+//! the transactions of its block.
+//!
+//! The genesis block header issues `GENESIS_AMOUNT` coins. A block header at a height
+//! `h` greater than zero issues `INTEREST_RATE` to the power of `h` coins, rounded down
+//! to a base unit, until the supply reaches `MAX_SUPPLY`: the subsidy reaching it is
+//! cut to the remaining supply, and the following ones are zero. All the networks
+//! share the same schedule.
+
+use rug::Integer;
+use rug::ops::Pow;
+
+use constants::{GENESIS_AMOUNT, INTEREST_RATE, AMOUNT_UNIT, MAX_SUPPLY};
+use error::ErrorKind;
+use result::Result;
+use utils::{NetworkType, Amount, FixedAmount};
+use models::output::Output;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+
+use std::cmp::min;
+
+/// Returns the subsidy at a given height, before the cut to the maximum supply.
+fn get_scheduled_subsidy(height: u32) -> Result<FixedAmount> {
+    if height == 0 {
+        return FixedAmount::from_coins(u64::from(GENESIS_AMOUNT));
+    }
+
+    let (numer, denom) = INTEREST_RATE;
+    let units = Integer::from(AMOUNT_UNIT) * Integer::from(numer).pow(height) / Integer::from(denom).pow(height);
+
+    match units.to_u64() {
+        Some(units) if units <= MAX_SUPPLY => FixedAmount::from_units(units),
+        _ => Ok(FixedAmount::max_value()),
+    }
+}
+
+/// Returns the subsidy and the supply at a given height.
+fn get_issuance(height: u32) -> Result<(FixedAmount, FixedAmount)> {
+    let mut subsidy = FixedAmount::zero();
+    let mut supply = FixedAmount::zero();
+
+    for h in 0..(u64::from(height) + 1) {
+        let remaining = FixedAmount::max_value().checked_sub(supply)?;
+
+        // the schedule ends with the maximum supply
+        if remaining.is_zero() {
+            return Ok((FixedAmount::zero(), supply));
+        }
+
+        subsidy = min(get_scheduled_subsidy(h as u32)?, remaining);
+        supply = supply.checked_add(subsidy)?;
+    }
+
+    Ok((subsidy, supply))
+}
+
+/// Returns the subsidy issued by the coinbase at a given height.
+pub fn get_subsidy(height: u32) -> Result<FixedAmount> {
+    Ok(get_issuance(height)?.0)
+}
+
+/// Returns the supply issued up to a given height, included.
+pub fn get_supply(height: u32) -> Result<FixedAmount> {
+    Ok(get_issuance(height)?.1)
+}
+
+/// Returns the height at which the supply reaches the maximum supply.
+pub fn get_max_supply_height() -> Result<u32> {
+    let mut height = 0;
+
+    while get_supply(height)? < FixedAmount::max_value() {
+        height += 1;
+    }
+
+    Ok(height)
+}
+
+/// Returns the sum of the fees of the transactions of a `Block`, failing if they are
+/// not the transactions of the block.
+pub fn get_fees(block: &Block, transactions: &[Transaction]) -> Result<Amount> {
+    if transactions.len() != block.transactions_ids.len() {
+        return Err(ErrorKind::InvalidLength.with_field("transactions").with_values(block.transactions_ids.len(), transactions.len()));
+    }
+
+    let mut fees = Amount::zero();
+
+    for (index, (transaction, id)) in transactions.iter().zip(block.transactions_ids.iter()).enumerate() {
+        if transaction.id != *id {
+            return Err(ErrorKind::InvalidID.with_index("transactions", index as u32).with_values(id, transaction.id));
+        }
+
+        fees += &transaction.fee;
+    }
+
+    Ok(fees)
+}
+
+/// Verifies that the coinbase of a `BlockHeader` issues at least the subsidy of its
+/// height, and that the genesis coinbase output is the one of its network. The fees
+/// claimed are verified with the transactions of the block by `verify_coinbase`.
+pub fn verify_coinbase_subsidy(block_header: &BlockHeader) -> Result<()> {
+    let subsidy = Amount::from(get_subsidy(block_header.height)?);
+
+    if block_header.coinbase_amount < subsidy {
+        return Err(ErrorKind::OutOfBound.with_field("coinbase_amount").with_values(subsidy, &block_header.coinbase_amount));
+    }
+
+    if block_header.coinbase_amount != block_header.coinbase_output.amount {
+        return Err(ErrorKind::OutOfBound.with_field("coinbase_output").with_values(&block_header.coinbase_amount, &block_header.coinbase_output.amount));
+    }
+
+    if block_header.height == 0 {
+        let genesis_output = match block_header.network_type {
+            NetworkType::TestNet => Some(Output::new_testnet_genesis()?),
+            NetworkType::MainNet => Some(Output::new_mainnet_genesis()?),
+            NetworkType::RegTest => None,
+        };
+
+        if let Some(genesis_output) = genesis_output {
+            if block_header.coinbase_output != genesis_output {
+                return Err(ErrorKind::InvalidGenesis.with_field("coinbase_output"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies that the coinbase of a `BlockHeader` issues exactly the subsidy of its
+/// height and the fees of the transactions of its block.
+pub fn verify_coinbase(block_header: &BlockHeader, fees: &Amount) -> Result<()> {
+    verify_coinbase_subsidy(block_header)?;
+
+    let amount = Amount::from(get_subsidy(block_header.height)?) + fees;
+
+    if block_header.coinbase_amount != amount {
+        return Err(ErrorKind::OutOfBound.with_field("coinbase_amount").with_values(amount, &block_header.coinbase_amount));
+    }
+
+    Ok(())
+}
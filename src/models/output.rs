@@ -7,22 +7,23 @@
 
 //! The `output` module provides the transaction output type and methods.
 
-use serde_json as json;
-use rmp_serde as messagepack;
 use hex;
 
 use constants::{TESTWITNESS, MAINWITNESS};
 use error::ErrorKind;
 use result::Result;
-use traits::{Validate, Identify, BinarySerialize, Serialize};
+use traits::{Validate, ValidateReport, Identify, Json, Serialize, Encode};
 use utils::Amount;
+use utils::encoding::{self, Encoder, Decoder};
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
 use crypto::{Digest, ZKPWitness};
 use crypto::Validate as CryptoValidate;
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoHexSerialize;
 use models::input::Input;
+use models::report::{Rule, ValidationReport};
 
-use std::io::Write;
+use std::io::{Read, Write};
 
 /// An `Output` is an allocation of balance to a user. It can be spent only
 /// providing a zero-knowledge proof verifing its zero-knowledge challenge.
@@ -118,12 +119,7 @@ impl<'a> Identify<'a> for Output {
     type ID = Digest;
 
     fn id(&self) -> Result<Self::ID> {
-        let mut buf = Vec::new();
-
-        buf.write_all(&self.amount.to_bytes()?)?;
-        buf.write_all(&self.witness.to_bytes()?)?;
-
-        Ok(Digest::hash(&buf))
+        encoding::digest(|encoder| self.encode_body(encoder))
     }
 
     fn id_from_bytes(b: &[u8]) -> Result<Self::ID> {
@@ -153,65 +149,99 @@ impl<'a> Identify<'a> for Output {
     }
 }
 
+impl Output {
+    /// Encodes the fields of the `Output` identified by its id.
+    fn encode_body<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write(&self.amount)?;
+        encoder.write_witness(self.witness)
+    }
+}
+
+impl Encode for Output {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.id)?;
+        self.encode_body(encoder)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Output> {
+        let output = Output {
+            id: decoder.read_digest()?,
+            amount: decoder.read()?,
+            witness: decoder.read_witness()?,
+        };
+
+        Ok(output)
+    }
+}
+
 impl Validate for Output {
     fn validate(&self) -> Result<()> {
-        if self.id != self.id()? {
-            return Err(ErrorKind::InvalidDigest.into());
-        }
-        
-        let _ = self.is_genesis()?;
-
-        Ok(())
+        self.validate_report()?.to_result()
     }
 }
 
-impl<'a> Serialize<'a> for Output {
-    fn to_json(&self) -> Result<String> {
-        let obj = json!({
-            "id": self.string_id()?,
-            "amount": self.amount.to_string(),
-            "witness": self.witness.to_hex()?,
-        });
+impl ValidateReport for Output {
+    fn validate_report(&self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+
+        let id = self.id()?;
+
+        if self.id != id {
+            report.add_values(Rule::OutputId, id, self.id);
+        }
 
-        let s = obj.to_string();
+        if self.amount.to_fixed().is_err() {
+            report.add(Rule::OutputAmountUnits);
+        }
+
+        if self.is_genesis().is_err() {
+            report.add_values(Rule::OutputGenesisWitness, Amount::genesis_value(), &self.amount);
+        }
 
-        Ok(s)
+        Ok(report)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_str: String = json::from_value(id_value)?;
-        let id = Output::id_from_string(&id_str)?;
+}
 
-        let amount_value = obj["amount"].clone();
-        let amount_str: String = json::from_value(amount_value)?;
-        let amount = Amount::from_string(&amount_str)?;
-        
-        let witness_value = obj["witness"].clone();
-        let witness_hex: String = json::from_value(witness_value)?;
-        let witness = ZKPWitness::from_hex(&witness_hex)?;
+impl Json for Output {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_amount("amount", &self.amount)?;
+        writer.write_witness("witness", self.witness)
+    }
 
+    fn read_json(reader: &mut JsonReader) -> Result<Output> {
         let output = Output {
-            id: id,
-            amount: amount,
-            witness: witness,
+            id: reader.read_digest("id")?,
+            amount: reader.read_amount("amount")?,
+            witness: reader.read_witness("witness")?,
         };
 
         Ok(output)
     }
-    
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::to_vec(self)?;
 
-        Ok(buf)
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("An output of a transaction.")
+            .reference("id", "Digest")
+            .reference("amount", "Amount")
+            .reference("witness", "Point")
+    }
+}
+
+impl<'a> Serialize<'a> for Output {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
     
     fn from_bytes(b: &[u8]) -> Result<Self> {
-        let output = messagepack::from_slice(b)?;
-
-        Ok(output)
+        encoding::decode(b)
     }
     
     fn to_hex(&self) -> Result<String> {
@@ -0,0 +1,426 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `report` module provides the validation report type and methods, listing
+//! all the consensus rules violated by a model, each with a stable rule code.
+
+use error::{Error, ErrorKind, ErrorField, ErrorDetails};
+use result::Result;
+
+use std::fmt::{self, Display};
+
+/// A `Rule` is a consensus rule checked in the validation of a model.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Rule {
+    /// The output id is the digest of the output.
+    OutputId,
+    /// The output paying to a genesis witness has the genesis amount.
+    OutputGenesisWitness,
+    /// The output amount is a whole number of base units.
+    OutputAmountUnits,
+    /// The input proof is a valid zero-knowledge proof.
+    InputProof,
+    /// The data id is the digest of the data.
+    DataId,
+    /// The data sender and receiver keys are different.
+    DataKeys,
+    /// The data plain size is consistent with the cyphertext size.
+    DataPlainSize,
+    /// The data cyphertext size is the padded plain size.
+    DataCyphSize,
+    /// The data cyphertext has the declared size.
+    DataCyphertext,
+    /// The data duration is not zero.
+    DataDuration,
+    /// The transaction version is supported.
+    TransactionVersion,
+    /// The transaction timestamp is in the allowed range.
+    TransactionTimestamp,
+    /// The transaction id is the digest of the transaction.
+    TransactionId,
+    /// The transaction has the declared number of inputs.
+    TransactionInputsLength,
+    /// The transaction has the declared number of outputs.
+    TransactionOutputsLength,
+    /// The transaction has the declared number of data ids.
+    TransactionDataLength,
+    /// The transaction inputs are distinct.
+    TransactionDuplicateInputs,
+    /// The transaction outputs are distinct.
+    TransactionDuplicateOutputs,
+    /// The transaction data ids are distinct.
+    TransactionDuplicateData,
+    /// The coinbase transaction has a single output of at least the genesis amount
+    /// and no data.
+    TransactionCoinbase,
+    /// The genesis transaction belongs to its network.
+    TransactionGenesis,
+    /// The outputs of a regular transaction are not genesis outputs.
+    TransactionGenesisOutput,
+    /// The transaction outputs amount is the sum of the amounts of its outputs.
+    TransactionOutputsAmount,
+    /// The transaction outputs have positive amounts.
+    TransactionOutputAmount,
+    /// The transaction fee is not negative.
+    TransactionFee,
+    /// The transaction outputs amounts are whole numbers of base units.
+    TransactionOutputUnits,
+    /// The transaction fee is a whole number of base units.
+    TransactionFeeUnits,
+    /// The block version is supported.
+    BlockVersion,
+    /// The block timestamp is in the allowed range.
+    BlockTimestamp,
+    /// The block id is the digest of the block.
+    BlockId,
+    /// The block transactions size is not zero.
+    BlockTransactionsSize,
+    /// The block transactions length is not zero.
+    BlockTransactionsLength,
+    /// The block has the declared number of transaction ids.
+    BlockTransactionsIds,
+    /// The block transaction ids are distinct.
+    BlockDuplicateTransactions,
+    /// The genesis block belongs to its network.
+    BlockGenesis,
+    /// The block header version is supported.
+    BlockHeaderVersion,
+    /// The block header timestamp is in the allowed range.
+    BlockHeaderTimestamp,
+    /// The block header retarget timestamp is in the allowed range and not after
+    /// the timestamp.
+    BlockHeaderRetargetTimestamp,
+    /// The block header id is the digest of the block header.
+    BlockHeaderId,
+    /// The block header does not follow itself.
+    BlockHeaderPrevId,
+    /// The block header transactions size is not zero.
+    BlockHeaderTransactionsSize,
+    /// The block header transactions length is not zero.
+    BlockHeaderTransactionsLength,
+    /// The block header coinbase amount issues at least the subsidy of its height.
+    BlockHeaderCoinbaseAmount,
+    /// The block header coinbase output pays the coinbase amount.
+    BlockHeaderCoinbaseOutput,
+    /// The genesis block header coinbase output is the genesis output of its network.
+    BlockHeaderCoinbaseGenesis,
+    /// The block header PoW is valid.
+    BlockHeaderPoW,
+    /// The genesis block header belongs to its network.
+    BlockHeaderGenesis,
+    /// The block header at height 1 follows the genesis of its network.
+    BlockHeaderNetwork,
+}
+
+impl Rule {
+    /// Returns the stable code of the `Rule`.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Rule::OutputId => "output-id",
+            Rule::OutputGenesisWitness => "output-genesis-witness",
+            Rule::OutputAmountUnits => "output-amount-units",
+            Rule::InputProof => "input-proof",
+            Rule::DataId => "data-id",
+            Rule::DataKeys => "data-keys",
+            Rule::DataPlainSize => "data-plain-size",
+            Rule::DataCyphSize => "data-cyph-size",
+            Rule::DataCyphertext => "data-cyphertext",
+            Rule::DataDuration => "data-duration",
+            Rule::TransactionVersion => "transaction-version",
+            Rule::TransactionTimestamp => "transaction-timestamp",
+            Rule::TransactionId => "transaction-id",
+            Rule::TransactionInputsLength => "transaction-inputs-length",
+            Rule::TransactionOutputsLength => "transaction-outputs-length",
+            Rule::TransactionDataLength => "transaction-data-length",
+            Rule::TransactionDuplicateInputs => "transaction-duplicate-inputs",
+            Rule::TransactionDuplicateOutputs => "transaction-duplicate-outputs",
+            Rule::TransactionDuplicateData => "transaction-duplicate-data",
+            Rule::TransactionCoinbase => "transaction-coinbase",
+            Rule::TransactionGenesis => "transaction-genesis",
+            Rule::TransactionGenesisOutput => "transaction-genesis-output",
+            Rule::TransactionOutputsAmount => "transaction-outputs-amount",
+            Rule::TransactionOutputAmount => "transaction-output-amount",
+            Rule::TransactionFee => "transaction-fee",
+            Rule::TransactionOutputUnits => "transaction-output-units",
+            Rule::TransactionFeeUnits => "transaction-fee-units",
+            Rule::BlockVersion => "block-version",
+            Rule::BlockTimestamp => "block-timestamp",
+            Rule::BlockId => "block-id",
+            Rule::BlockTransactionsSize => "block-transactions-size",
+            Rule::BlockTransactionsLength => "block-transactions-length",
+            Rule::BlockTransactionsIds => "block-transactions-ids",
+            Rule::BlockDuplicateTransactions => "block-duplicate-transactions",
+            Rule::BlockGenesis => "block-genesis",
+            Rule::BlockHeaderVersion => "block-header-version",
+            Rule::BlockHeaderTimestamp => "block-header-timestamp",
+            Rule::BlockHeaderRetargetTimestamp => "block-header-retarget-timestamp",
+            Rule::BlockHeaderId => "block-header-id",
+            Rule::BlockHeaderPrevId => "block-header-prev-id",
+            Rule::BlockHeaderTransactionsSize => "block-header-transactions-size",
+            Rule::BlockHeaderTransactionsLength => "block-header-transactions-length",
+            Rule::BlockHeaderCoinbaseAmount => "block-header-coinbase-amount",
+            Rule::BlockHeaderCoinbaseOutput => "block-header-coinbase-output",
+            Rule::BlockHeaderCoinbaseGenesis => "block-header-coinbase-genesis",
+            Rule::BlockHeaderPoW => "block-header-pow",
+            Rule::BlockHeaderGenesis => "block-header-genesis",
+            Rule::BlockHeaderNetwork => "block-header-network",
+        }
+    }
+
+    /// Returns the `ErrorKind` of a violation of the `Rule`.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Rule::OutputId |
+            Rule::DataId |
+            Rule::TransactionId |
+            Rule::BlockId |
+            Rule::BlockHeaderId |
+            Rule::BlockHeaderPrevId => ErrorKind::InvalidDigest,
+            Rule::OutputGenesisWitness => ErrorKind::InvalidWitness,
+            Rule::InputProof => ErrorKind::InvalidProof,
+            Rule::DataKeys => ErrorKind::InvalidPublicKey,
+            Rule::DataPlainSize |
+            Rule::DataCyphSize |
+            Rule::DataCyphertext |
+            Rule::TransactionInputsLength |
+            Rule::TransactionOutputsLength |
+            Rule::TransactionDataLength |
+            Rule::BlockTransactionsSize |
+            Rule::BlockTransactionsLength |
+            Rule::BlockTransactionsIds |
+            Rule::BlockHeaderTransactionsSize |
+            Rule::BlockHeaderTransactionsLength => ErrorKind::InvalidLength,
+            Rule::DataDuration => ErrorKind::InvalidDuration,
+            Rule::TransactionVersion |
+            Rule::BlockVersion |
+            Rule::BlockHeaderVersion => ErrorKind::InvalidVersion,
+            Rule::TransactionTimestamp |
+            Rule::BlockTimestamp |
+            Rule::BlockHeaderTimestamp |
+            Rule::BlockHeaderRetargetTimestamp => ErrorKind::InvalidTimestamp,
+            Rule::TransactionDuplicateInputs |
+            Rule::TransactionDuplicateOutputs |
+            Rule::TransactionDuplicateData |
+            Rule::BlockDuplicateTransactions => ErrorKind::DuplicatesFound,
+            Rule::TransactionCoinbase |
+            Rule::TransactionGenesisOutput => ErrorKind::InvalidTransaction,
+            Rule::TransactionGenesis |
+            Rule::BlockGenesis |
+            Rule::BlockHeaderGenesis |
+            Rule::BlockHeaderCoinbaseGenesis => ErrorKind::InvalidGenesis,
+            Rule::TransactionOutputsAmount => ErrorKind::InvalidBalance,
+            Rule::TransactionOutputAmount |
+            Rule::TransactionFee |
+            Rule::BlockHeaderCoinbaseAmount |
+            Rule::BlockHeaderCoinbaseOutput => ErrorKind::OutOfBound,
+            Rule::OutputAmountUnits |
+            Rule::TransactionOutputUnits |
+            Rule::TransactionFeeUnits => ErrorKind::InvalidFormat,
+            Rule::BlockHeaderPoW => ErrorKind::InvalidPoW,
+            Rule::BlockHeaderNetwork => ErrorKind::InvalidNetwork,
+        }
+    }
+
+    /// Returns the name of the field checked by the `Rule`.
+    pub fn field(&self) -> &'static str {
+        match *self {
+            Rule::OutputId |
+            Rule::DataId |
+            Rule::TransactionId |
+            Rule::BlockId |
+            Rule::BlockHeaderId => "id",
+            Rule::OutputGenesisWitness |
+            Rule::OutputAmountUnits => "amount",
+            Rule::InputProof => "proof",
+            Rule::DataKeys => "to",
+            Rule::DataPlainSize => "plain_size",
+            Rule::DataCyphSize => "cyph_size",
+            Rule::DataCyphertext => "cyphertext",
+            Rule::DataDuration => "duration",
+            Rule::TransactionVersion |
+            Rule::BlockVersion |
+            Rule::BlockHeaderVersion => "version",
+            Rule::TransactionTimestamp |
+            Rule::BlockTimestamp |
+            Rule::BlockHeaderTimestamp => "timestamp",
+            Rule::BlockHeaderRetargetTimestamp => "retarget_timestamp",
+            Rule::TransactionInputsLength |
+            Rule::TransactionDuplicateInputs => "inputs",
+            Rule::TransactionOutputsLength |
+            Rule::TransactionDuplicateOutputs |
+            Rule::TransactionCoinbase |
+            Rule::TransactionGenesis |
+            Rule::TransactionGenesisOutput |
+            Rule::TransactionOutputAmount |
+            Rule::TransactionOutputUnits => "outputs",
+            Rule::TransactionOutputsAmount => "outputs_amount",
+            Rule::TransactionFee |
+            Rule::TransactionFeeUnits => "fee",
+            Rule::TransactionDataLength |
+            Rule::TransactionDuplicateData => "data_ids",
+            Rule::BlockTransactionsSize |
+            Rule::BlockHeaderTransactionsSize => "transactions_size",
+            Rule::BlockTransactionsLength |
+            Rule::BlockHeaderTransactionsLength => "transactions_length",
+            Rule::BlockTransactionsIds |
+            Rule::BlockDuplicateTransactions |
+            Rule::BlockGenesis => "transactions_ids",
+            Rule::BlockHeaderPrevId |
+            Rule::BlockHeaderNetwork => "prev_id",
+            Rule::BlockHeaderCoinbaseAmount => "coinbase_amount",
+            Rule::BlockHeaderCoinbaseOutput |
+            Rule::BlockHeaderCoinbaseGenesis => "coinbase_output",
+            Rule::BlockHeaderPoW => "pow_digest",
+            Rule::BlockHeaderGenesis => "block_id",
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// A `Violation` is a `Rule` violated by a model, with the `ErrorDetails` of the
+/// field which violated it.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Violation {
+    /// The violated rule.
+    pub rule: Rule,
+    /// The details of the violation.
+    pub details: ErrorDetails,
+}
+
+impl Violation {
+    /// Creates a new `Violation` of a `Rule`.
+    pub fn new(rule: Rule) -> Violation {
+        let mut details = ErrorDetails::default();
+        details.fields.push(ErrorField { name: rule.field(), index: None });
+
+        Violation {
+            rule: rule,
+            details: details,
+        }
+    }
+
+    /// Converts the `Violation` to an `Error`.
+    pub fn to_error(&self) -> Error {
+        let mut error = Error::from(self.rule.kind());
+
+        for field in self.details.fields.iter().rev() {
+            error = match field.index {
+                Some(index) => error.with_index(field.name, index),
+                None => error.with_field(field.name),
+            };
+        }
+
+        if let (Some(ref expected), Some(ref actual)) = (&self.details.expected, &self.details.actual) {
+            error = error.with_values(expected, actual);
+        }
+
+        error
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} {}", self.rule, self.rule.kind(), self.details)
+    }
+}
+
+/// A `ValidationReport` lists all the `Violation`s found validating a model.
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct ValidationReport {
+    /// The violations, in the order they were found.
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Creates a new empty `ValidationReport`.
+    pub fn new() -> ValidationReport {
+        ValidationReport::default()
+    }
+
+    /// Verifies if no rule was violated.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Verifies if a `Rule` was violated.
+    pub fn contains(&self, rule: Rule) -> bool {
+        self.violations.iter().any(|violation| violation.rule == rule)
+    }
+
+    /// Returns the codes of the violated rules, in the order they were found.
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.violations.iter()
+            .map(|violation| violation.rule.code())
+            .collect()
+    }
+
+    /// Adds a violation of a `Rule`.
+    pub fn add(&mut self, rule: Rule) {
+        self.violations.push(Violation::new(rule));
+    }
+
+    /// Adds a violation of a `Rule` by an item of its list field.
+    pub fn add_index(&mut self, rule: Rule, index: u32) {
+        let mut violation = Violation::new(rule);
+        violation.details.fields[0].index = Some(index);
+
+        self.violations.push(violation);
+    }
+
+    /// Adds a violation of a `Rule` with the expected and actual values.
+    pub fn add_values<E: Display, A: Display>(&mut self, rule: Rule, expected: E, actual: A) {
+        let mut violation = Violation::new(rule);
+        violation.details.expected = Some(expected.to_string());
+        violation.details.actual = Some(actual.to_string());
+
+        self.violations.push(violation);
+    }
+
+    /// Adds the violations of the model in a field.
+    pub fn merge_field(&mut self, name: &'static str, report: ValidationReport) {
+        for mut violation in report.violations {
+            violation.details.fields.insert(0, ErrorField { name: name, index: None });
+            self.violations.push(violation);
+        }
+    }
+
+    /// Adds the violations of the model in an item of a list field.
+    pub fn merge_index(&mut self, name: &'static str, index: u32, report: ValidationReport) {
+        for mut violation in report.violations {
+            violation.details.fields.insert(0, ErrorField { name: name, index: Some(index) });
+            self.violations.push(violation);
+        }
+    }
+
+    /// Converts the `ValidationReport` to a `Result`, failing with the first
+    /// `Violation`, if any.
+    pub fn to_result(&self) -> Result<()> {
+        match self.violations.first() {
+            Some(violation) => Err(violation.to_error()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, violation) in self.violations.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}", violation)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `schema` module provides the JSON Schema of the JSON representation of the
+//! models.
+
+use serde_json::Value;
+
+use constants::JSON_VERSION;
+use traits::Json;
+use utils::json;
+use models::data::Data;
+use models::output::Output;
+use models::coin::Coin;
+use models::input::Input;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+
+/// Returns the JSON Schema document of the models, with the definitions of the
+/// models and of their field types.
+pub fn json_schema() -> Value {
+    let mut definitions = json::definitions();
+
+    definitions.insert("Data".to_string(), Data::json_schema().into_value());
+    definitions.insert("Output".to_string(), Output::json_schema().into_value());
+    definitions.insert("Coin".to_string(), Coin::json_schema().into_value());
+    definitions.insert("Input".to_string(), Input::json_schema().into_value());
+    definitions.insert("Transaction".to_string(), Transaction::json_schema().into_value());
+    definitions.insert("Block".to_string(), Block::json_schema().into_value());
+    definitions.insert("BlockHeader".to_string(), BlockHeader::json_schema().into_value());
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Yobicash models",
+        "json_version": JSON_VERSION,
+        "definitions": definitions,
+    })
+}
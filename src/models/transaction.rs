@@ -7,16 +7,16 @@
 
 //! The `transaction` module provides the transaction type and methods.
 
-use serde_json as json;
-use rmp_serde as messagepack;
 use hex;
 use byteorder::{BigEndian, WriteBytesExt};
 use itertools::Itertools;
 
 use error::ErrorKind;
 use result::Result;
-use traits::{Identify, Validate, BinarySerialize, HexSerialize, Serialize};
+use traits::{Identify, Validate, ValidateReport, BinarySerialize, Json, Serialize, Encode};
 use utils::{Version, NetworkType, Timestamp, Amount};
+use utils::encoding::{self, Encoder, Decoder};
+use utils::json::{self, JsonWriter, JsonReader, JsonSchema};
 use crypto::{Digest, ZKPWitness};
 use crypto::BinarySerialize as CryptoBinarySerialize;
 use crypto::HexSerialize as CryptoHexSerialize;
@@ -24,8 +24,9 @@ use models::output::Output;
 use models::data::Data;
 use models::coin::Coin;
 use models::input::Input;
+use models::report::{Rule, ValidationReport};
 
-use std::io::Write;
+use std::io::{Read, Write};
 
 /// A `Transaction` is a transfer of balance from the inputs of
 /// (generally) one user to one or more users.
@@ -248,15 +249,15 @@ impl Transaction {
     pub fn is_coinbase(&self) -> Result<bool> {
         if self.inputs_length == 0 {
             if self.outputs_length != 1 {
-                return Err(ErrorKind::InvalidLength.into());
+                return Err(ErrorKind::InvalidLength.with_field("outputs_length").with_values(1, self.outputs_length));
             }
             
             if self.data_length != 0 {
-                return Err(ErrorKind::InvalidLength.into());
+                return Err(ErrorKind::InvalidLength.with_field("data_length").with_values(0, self.data_length));
             }
            
             if self.outputs_amount < Amount::genesis_value() {
-                return Err(ErrorKind::OutOfBound.into());
+                return Err(ErrorKind::OutOfBound.with_field("outputs_amount").with_values(Amount::genesis_value(), &self.outputs_amount));
             }
 
             Ok(true)
@@ -279,7 +280,7 @@ impl Transaction {
 
         if output.id == Output::new_testnet_genesis()?.id {
             if self.network_type != NetworkType::TestNet {
-                return Err(ErrorKind::InvalidNetwork.into());
+                return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::TestNet, self.network_type));
             }
             
             return Ok(true);
@@ -287,22 +288,29 @@ impl Transaction {
 
         if output.id == Output::new_mainnet_genesis()?.id {
             if self.network_type != NetworkType::MainNet {
-                return Err(ErrorKind::InvalidNetwork.into());
+                return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::MainNet, self.network_type));
             }
 
             return Ok(true);
         }
 
         if self.network_type != NetworkType::RegTest {
-            return Err(ErrorKind::InvalidNetwork.into());    
+            return Err(ErrorKind::InvalidNetwork.with_field("network_type").with_values(NetworkType::RegTest, self.network_type));
         }
 
         Ok(true)
     }
 
-    /// Returns the total amount sent in the `Transaction`.
+    /// Returns the total amount sent in the `Transaction`, summing the amounts of
+    /// its outputs and not the declared outputs amount.
     pub fn total_amount(&self) -> Amount {
-        &self.outputs_amount + &self.fee
+        let mut amount = self.fee.clone();
+
+        for output in &self.outputs {
+            amount += &output.amount;
+        }
+
+        amount
     }
 
     /// Returns the size of the `Transaction`.
@@ -335,30 +343,7 @@ impl<'a> Identify<'a> for Transaction {
     type ID = Digest;
 
     fn id(&self) -> Result<Self::ID> {
-        let mut buf = Vec::new();
-
-        buf.write_all(&self.version.to_bytes()?)?;
-        buf.write_all(&self.network_type.to_bytes()?)?;
-        buf.write_all(&self.timestamp.to_bytes()?)?;
-        buf.write_u32::<BigEndian>(self.inputs_length)?;
-        
-        for i in 0..self.inputs_length as usize {
-            let input = self.inputs[i];
-            let input_buf = input.to_bytes()?;
-            buf.write_all(&input_buf)?;
-        }
-
-        buf.write_all(&self.outputs_amount.to_bytes()?)?;
-        
-        buf.write_u32::<BigEndian>(self.outputs_length)?;
-        for i in 0..self.outputs_length as usize {
-            let id = &self.outputs[i].id;
-            buf.write_all(&id.to_bytes()?)?;
-        }
-
-        buf.write_all(&self.fee.to_bytes()?)?;
-
-        Ok(Digest::hash(&buf))
+        encoding::digest(|encoder| self.encode_body(encoder))
     }
 
     fn id_from_bytes(b: &[u8]) -> Result<Self::ID> {
@@ -390,210 +375,230 @@ impl<'a> Identify<'a> for Transaction {
     }
 }
 
+impl Transaction {
+    /// Encodes the fields of the `Transaction` identified by its id.
+    fn encode_body<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write(&self.version)?;
+        encoder.write(&self.network_type)?;
+        encoder.write(&self.timestamp)?;
+        encoder.write_u32(self.inputs_length)?;
+        encoder.write_list(&self.inputs)?;
+        encoder.write(&self.outputs_amount)?;
+        encoder.write_u32(self.outputs_length)?;
+        encoder.write_list(&self.outputs)?;
+        encoder.write_u32(self.data_size)?;
+        encoder.write_u32(self.data_length)?;
+        encoder.write_digests(&self.data_ids)?;
+        encoder.write(&self.fee)
+    }
+}
+
+impl Encode for Transaction {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.id)?;
+        self.encode_body(encoder)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Transaction> {
+        let transaction = Transaction {
+            id: decoder.read_digest()?,
+            version: decoder.read()?,
+            network_type: decoder.read()?,
+            timestamp: decoder.read()?,
+            inputs_length: decoder.read_u32()?,
+            inputs: decoder.read_list()?,
+            outputs_amount: decoder.read()?,
+            outputs_length: decoder.read_u32()?,
+            outputs: decoder.read_list()?,
+            data_size: decoder.read_u32()?,
+            data_length: decoder.read_u32()?,
+            data_ids: decoder.read_digests()?,
+            fee: decoder.read()?,
+        };
+
+        Ok(transaction)
+    }
+}
+
 impl Validate for Transaction {
     fn validate(&self) -> Result<()> {
-        self.version.validate()?;
-        self.timestamp.validate()?;
+        self.validate_report()?.to_result()
+    }
+}
+
+impl ValidateReport for Transaction {
+    fn validate_report(&self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+
+        if self.version.validate().is_err() {
+            report.add(Rule::TransactionVersion);
+        }
 
-        if self.id != self.id()? {
-            return Err(ErrorKind::InvalidDigest.into());
+        if self.timestamp.validate().is_err() {
+            report.add(Rule::TransactionTimestamp);
+        }
+
+        let id = self.id()?;
+
+        if self.id != id {
+            report.add_values(Rule::TransactionId, id, self.id);
         }
 
         if self.inputs_length as usize != self.inputs.len() {
-            return Err(ErrorKind::InvalidLength.into()); 
+            report.add_values(Rule::TransactionInputsLength, self.inputs_length, self.inputs.len());
         }
 
         if self.outputs_length as usize != self.outputs.len() {
-            return Err(ErrorKind::InvalidLength.into()); 
+            report.add_values(Rule::TransactionOutputsLength, self.outputs_length, self.outputs.len());
         }
 
         if self.data_length as usize != self.data_ids.len() {
-            return Err(ErrorKind::InvalidLength.into()); 
+            report.add_values(Rule::TransactionDataLength, self.data_length, self.data_ids.len());
         }
 
         let mut inputs_binary_ids = Vec::new();
-        for input in self.inputs.clone() {
-            input.validate()?;
+        for (index, input) in self.inputs.iter().enumerate() {
+            report.merge_index("inputs", index as u32, input.validate_report()?);
             inputs_binary_ids.push(input.binary_id()?);
         }
 
-        if inputs_binary_ids.iter().unique().count() !=
-            self.inputs_length as usize {
-            return Err(ErrorKind::DuplicatesFound.into()); 
+        if inputs_binary_ids.iter().unique().count() != self.inputs.len() {
+            report.add(Rule::TransactionDuplicateInputs);
         }
 
         let mut outputs_binary_ids = Vec::new();
-        for output in self.outputs.clone() {
+        for output in &self.outputs {
             outputs_binary_ids.push(output.id.to_bytes()?);
         }
 
-        if outputs_binary_ids.iter().unique().count() !=
-            self.outputs_length as usize {
-            return Err(ErrorKind::DuplicatesFound.into()); 
+        if outputs_binary_ids.iter().unique().count() != self.outputs.len() {
+            report.add(Rule::TransactionDuplicateOutputs);
         }
 
         let mut data_binary_ids = Vec::new();
-        for id in self.data_ids.clone() {
+        for id in &self.data_ids {
             data_binary_ids.push(id.to_bytes()?);
         }
 
-        if data_binary_ids.iter().unique().count() !=
-            self.data_length as usize {
-            return Err(ErrorKind::DuplicatesFound.into()); 
-        }
-
-        if !self.is_genesis()? && !self.is_coinbase()? {
-            if self.inputs_length == 0 {
-                return Err(ErrorKind::InvalidLength.into());
-            } 
-            
-            for output in self.outputs.clone() {
-                if output.is_genesis()? {
-                    return Err(ErrorKind::InvalidTransaction.into());
-                }
-            }
+        if data_binary_ids.iter().unique().count() != self.data_ids.len() {
+            report.add(Rule::TransactionDuplicateData);
         }
 
-        Ok(())
-    }
-}
+        let mut outputs_amount = Amount::zero();
 
-impl<'a> Serialize<'a> for Transaction {
-    fn to_json(&self) -> Result<String> {
-        let mut json_inputs = Vec::new();
-        for input in self.inputs.clone() {
-            json_inputs.push(input.to_json()?);
-        }
+        for (index, output) in self.outputs.iter().enumerate() {
+            if output.amount <= Amount::zero() {
+                report.add_index(Rule::TransactionOutputAmount, index as u32);
+            } else if output.amount.to_fixed().is_err() {
+                report.add_index(Rule::TransactionOutputUnits, index as u32);
+            }
 
-        let mut json_outputs = Vec::new();
-        for output in self.outputs.clone() {
-            json_outputs.push(output.to_json()?);
+            outputs_amount += &output.amount;
         }
 
-        let mut json_data_ids = Vec::new();
-        for id in self.data_ids.clone() {
-            json_data_ids.push(id.to_hex()?);
+        if self.outputs_amount != outputs_amount {
+            report.add_values(Rule::TransactionOutputsAmount, outputs_amount, &self.outputs_amount);
+        }
+
+        if self.fee < Amount::zero() {
+            report.add_values(Rule::TransactionFee, Amount::zero(), &self.fee);
+        } else if self.fee.to_fixed().is_err() {
+            report.add(Rule::TransactionFeeUnits);
+        }
+
+        // the coinbase and genesis rules read the declared outputs
+        if self.outputs_length as usize == self.outputs.len() {
+            match self.is_coinbase() {
+                Err(_) => report.add(Rule::TransactionCoinbase),
+                Ok(true) => {
+                    if self.is_genesis().is_err() {
+                        report.add(Rule::TransactionGenesis);
+                    }
+                },
+                Ok(false) => {
+                    for (index, output) in self.outputs.iter().enumerate() {
+                        if output.is_genesis().unwrap_or(true) {
+                            report.add_index(Rule::TransactionGenesisOutput, index as u32);
+                        }
+                    }
+                },
+            }
         }
 
-        let obj = json!({
-            "id": self.string_id()?,
-            "version": self.version.to_string(),
-            "network_type": self.network_type.to_hex()?,
-            "timestamp": self.timestamp.to_string(),
-            "inputs_length": self.inputs_length,
-            "inputs": json_inputs,
-            "outputs_amount": self.outputs_amount.to_string(),
-            "outputs_length": self.outputs_length,
-            "outputs": json_outputs,
-            "data_size": self.data_size,
-            "data_length": self.data_length,
-            "data_ids": json_data_ids,
-            "fee": self.fee.to_hex()?,
-        });
-
-        let s = obj.to_string();
-
-        Ok(s)
+        Ok(report)
     }
-    
-    fn from_json(s: &str) -> Result<Self> {
-        let obj: json::Value = json::from_str(s)?;
-        
-        let id_value = obj["id"].clone();
-        let id_str: String = json::from_value(id_value)?;
-        let id = Transaction::id_from_string(&id_str)?;
-        
-        let version_value = obj["version"].clone();
-        let version_str: String = json::from_value(version_value)?;
-        let version = Version::from_string(&version_str)?;
-        
-        let network_type_value = obj["network_type"].clone();
-        let network_type_hex: String = json::from_value(network_type_value)?;
-        let network_type = NetworkType::from_hex(&network_type_hex)?;
-        
-        let timestamp_value = obj["timestamp"].clone();
-        let timestamp_str: String = json::from_value(timestamp_value)?;
-        let timestamp = Timestamp::from_string(&timestamp_str)?;
-
-        let inputs_length_value = obj["inputs_length"].clone();
-        let inputs_length: u32 = json::from_value(inputs_length_value)?;
-
-        let inputs_value = obj["inputs"].clone();
-        let inputs_json: Vec<String> = json::from_value(inputs_value)?;
-
-        let mut inputs = Vec::new();
-
-        for input_json in inputs_json {
-            let input = Input::from_json(&input_json)?;
-            inputs.push(input);
-        }
-
-        let outputs_amount_value = obj["outputs_amount"].clone();
-        let outputs_amount_str: String = json::from_value(outputs_amount_value)?;
-        let outputs_amount = Amount::from_string(&outputs_amount_str)?;
-
-        let outputs_length_value = obj["outputs_length"].clone();
-        let outputs_length: u32 = json::from_value(outputs_length_value)?;
-
-        let outputs_value = obj["outputs"].clone();
-        let outputs_json: Vec<String> = json::from_value(outputs_value)?;
-
-        let mut outputs = Vec::new();
-
-        for output_json in outputs_json {
-            let output = Output::from_json(&output_json)?;
-            outputs.push(output);
-        }
-
-        let data_size_value = obj["data_size"].clone();
-        let data_size: u32 = json::from_value(data_size_value)?;
-
-        let data_length_value = obj["data_length"].clone();
-        let data_length: u32 = json::from_value(data_length_value)?;
-
-        let data_ids_value = obj["data_ids"].clone();
-        let data_ids_json: Vec<String> = json::from_value(data_ids_value)?;
-
-        let mut data_ids = Vec::new();
+}
 
-        for data_id_hex in data_ids_json {
-            let id = Digest::from_hex(&data_id_hex)?;
-            data_ids.push(id);
-        }
-        
-        let fee_value = obj["fee"].clone();
-        let fee_hex: String = json::from_value(fee_value)?;
-        let fee = Amount::from_hex(&fee_hex)?;
+impl Json for Transaction {
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()> {
+        writer.write_digest("id", self.id)?;
+        writer.write_version("version", &self.version)?;
+        writer.write_network_type("network_type", self.network_type)?;
+        writer.write_timestamp("timestamp", self.timestamp)?;
+        writer.write_u32("inputs_length", self.inputs_length)?;
+        writer.write_list("inputs", &self.inputs)?;
+        writer.write_amount("outputs_amount", &self.outputs_amount)?;
+        writer.write_u32("outputs_length", self.outputs_length)?;
+        writer.write_list("outputs", &self.outputs)?;
+        writer.write_u32("data_size", self.data_size)?;
+        writer.write_u32("data_length", self.data_length)?;
+        writer.write_digests("data_ids", &self.data_ids)?;
+        writer.write_amount("fee", &self.fee)
+    }
 
+    fn read_json(reader: &mut JsonReader) -> Result<Transaction> {
         let transaction = Transaction {
-            id: id,
-            version: version,
-            network_type: network_type,
-            timestamp: timestamp,
-            inputs_length: inputs_length,
-            inputs: inputs,
-            outputs_amount: outputs_amount,
-            outputs_length: outputs_length,
-            outputs: outputs,
-            data_size: data_size,
-            data_length: data_length,
-            data_ids: data_ids,
-            fee: fee,
+            id: reader.read_digest("id")?,
+            version: reader.read_version("version")?,
+            network_type: reader.read_network_type("network_type")?,
+            timestamp: reader.read_timestamp("timestamp")?,
+            inputs_length: reader.read_u32("inputs_length")?,
+            inputs: reader.read_list("inputs")?,
+            outputs_amount: reader.read_amount("outputs_amount")?,
+            outputs_length: reader.read_u32("outputs_length")?,
+            outputs: reader.read_list("outputs")?,
+            data_size: reader.read_u32("data_size")?,
+            data_length: reader.read_u32("data_length")?,
+            data_ids: reader.read_digests("data_ids")?,
+            fee: reader.read_amount("fee")?,
         };
 
         Ok(transaction)
     }
-    
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::to_vec(self)?;
 
-        Ok(buf)
+    fn json_schema() -> JsonSchema {
+        JsonSchema::new("A transaction.")
+            .reference("id", "Digest")
+            .reference("version", "Version")
+            .reference("network_type", "NetworkType")
+            .reference("timestamp", "Timestamp")
+            .reference("inputs_length", "U32")
+            .list("inputs", "Input")
+            .reference("outputs_amount", "Amount")
+            .reference("outputs_length", "U32")
+            .list("outputs", "Output")
+            .reference("data_size", "U32")
+            .reference("data_length", "U32")
+            .list("data_ids", "Digest")
+            .reference("fee", "Amount")
+    }
+}
+
+impl<'a> Serialize<'a> for Transaction {
+    fn to_json(&self) -> Result<String> {
+        json::to_string(self)
+    }
+
+    fn from_json(s: &str) -> Result<Self> {
+        json::from_str(s)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
     
     fn from_bytes(b: &[u8]) -> Result<Self> {
-        let tx = messagepack::from_slice(b)?;
-
-        Ok(tx)
+        encoding::decode(b)
     }
     
     fn to_hex(&self) -> Result<String> {
@@ -0,0 +1,699 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `node` module provides the node type and methods, used to exchange block
+//! headers, blocks and transactions with the peers.
+
+pub mod peer;
+pub mod sync;
+
+pub use self::peer::*;
+pub use self::sync::*;
+
+use futures::{Future, Stream, Poll, Async};
+use futures::sync::mpsc::{self, UnboundedSender, UnboundedReceiver};
+
+use constants::{MAX_HEADERS_LENGTH, MEDIAN_TIME_SPAN};
+use error::{Error, ErrorKind};
+use result::Result;
+use traits::Validate;
+use utils::{Version, NetworkType, Timestamp};
+use crypto::Digest;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+use models::block_filter::BlockFilter;
+use store::Store;
+use protocol::{Message, Handshake, GetHeaders, GetBlockTransactions, BlockTransactions};
+use protocol::{InventoryKind, InventoryItem};
+
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+/// The commands sent to a `Node` by its handles.
+#[derive(Debug)]
+enum NodeCommand {
+    /// Adds a peer.
+    AddPeer(PeerConnection),
+    /// Accepts a message as if received from a peer and relays it.
+    Broadcast(Box<Message>),
+    /// Stops the node.
+    Shutdown,
+}
+
+/// A `NodeHandle` is used to control a running `Node` from another thread.
+#[derive(Clone, Debug)]
+pub struct NodeHandle {
+    /// The sender of the commands to the node.
+    sender: UnboundedSender<NodeCommand>,
+}
+
+impl NodeHandle {
+    /// Sends a command to the node.
+    fn send(&self, command: NodeCommand) -> Result<()> {
+        if self.sender.unbounded_send(command).is_err() {
+            return Err(ErrorKind::Disconnected.into());
+        }
+
+        Ok(())
+    }
+
+    /// Adds a peer to the `Node`, which starts the handshake.
+    pub fn add_peer(&self, connection: PeerConnection) -> Result<()> {
+        self.send(NodeCommand::AddPeer(connection))
+    }
+
+    /// Makes the `Node` accept a block header, block or transaction and relay it
+    /// to its peers.
+    pub fn broadcast(&self, message: Message) -> Result<()> {
+        self.send(NodeCommand::Broadcast(Box::new(message)))
+    }
+
+    /// Stops the `Node`.
+    pub fn shutdown(&self) -> Result<()> {
+        self.send(NodeCommand::Shutdown)
+    }
+
+    /// Connects the `Node` to a peer listening on a given address.
+    pub fn connect(&self, address: SocketAddr) -> Result<()> {
+        let stream = TcpStream::connect(address)?;
+
+        self.add_peer(PeerConnection::from_tcp(stream)?)
+    }
+
+    /// Accepts the peers connecting on a given address, returning the address
+    /// actually bound. The peers are accepted by a thread that stops on the first
+    /// connection after the `Node` is stopped.
+    pub fn listen_on(&self, address: SocketAddr) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(address)?;
+        let local_address = listener.local_addr()?;
+        let handle = self.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let connection = match stream.map(PeerConnection::from_tcp) {
+                    Ok(Ok(connection)) => connection,
+                    _ => continue,
+                };
+
+                if handle.add_peer(connection).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(local_address)
+    }
+
+    /// Accepts the peers connecting on the port of a `NetworkType`.
+    pub fn listen(&self, network_type: NetworkType) -> Result<SocketAddr> {
+        self.listen_on(SocketAddr::from(([0, 0, 0, 0], network_type.port())))
+    }
+}
+
+/// A `Peer` is a connection with a peer and its state.
+#[derive(Debug)]
+struct Peer {
+    /// The connection with the peer.
+    connection: PeerConnection,
+    /// The handshake received from the peer, if any.
+    handshake: Option<Handshake>,
+}
+
+/// A `Node` exchanges block headers, blocks and transactions with its peers, keeping
+/// them in a `Store`. The new items received from a peer are validated and relayed
+/// to the other peers, and the peers sending invalid messages are disconnected.
+///
+/// The `Node` is a future running until it is stopped through a `NodeHandle`.
+#[derive(Debug)]
+pub struct Node<S: Store> {
+    /// The network type of the node.
+    pub network_type: NetworkType,
+    /// The version of the node.
+    pub version: Version,
+    /// The store of the node.
+    store: S,
+    /// The height of the highest verified block header received.
+    height: u32,
+    /// The peers, by peer id.
+    peers: BTreeMap<u64, Peer>,
+    /// The id of the next peer.
+    next_peer_id: u64,
+    /// The sender of the commands, cloned by the handles.
+    commands_sender: UnboundedSender<NodeCommand>,
+    /// The receiver of the commands.
+    commands: UnboundedReceiver<NodeCommand>,
+}
+
+impl<S: Store> Node<S> {
+    /// Creates a new `Node`.
+    pub fn new(network_type: NetworkType, store: S) -> Result<Node<S>> {
+        let (commands_sender, commands) = mpsc::unbounded();
+
+        let node = Node {
+            network_type: network_type,
+            version: Version::current()?,
+            store: store,
+            height: 0,
+            peers: BTreeMap::new(),
+            next_peer_id: 0,
+            commands_sender: commands_sender,
+            commands: commands,
+        };
+
+        Ok(node)
+    }
+
+    /// Returns a `NodeHandle` used to control the `Node`.
+    pub fn handle(&self) -> NodeHandle {
+        NodeHandle {
+            sender: self.commands_sender.clone(),
+        }
+    }
+
+    /// Returns the `Store` of the `Node`.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the height of the highest verified block header received.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the number of peers.
+    pub fn peers_len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Verifies the `Handshake` of a peer, that is its network type and the
+    /// compatibility of its `Version`.
+    pub fn verify_handshake(&self, handshake: &Handshake) -> Result<()> {
+        handshake.verify(self.network_type, &self.version)
+    }
+
+    /// Adds a peer and sends it the `Handshake`.
+    fn add_peer(&mut self, connection: PeerConnection) {
+        let id = self.next_peer_id;
+        self.next_peer_id += 1;
+
+        let peer = Peer {
+            connection: connection,
+            handshake: None,
+        };
+
+        self.peers.insert(id, peer);
+
+        if let Ok(handshake) = Handshake::new(self.network_type, self.height) {
+            self.send(id, Message::Handshake(handshake));
+        }
+    }
+
+    /// Sends a `Message` to a peer, disconnecting it on failure.
+    fn send(&mut self, peer_id: u64, message: Message) {
+        let sent = match self.peers.get(&peer_id) {
+            Some(peer) => peer.connection.send(message).is_ok(),
+            None => return,
+        };
+
+        if !sent {
+            self.peers.remove(&peer_id);
+        }
+    }
+
+    /// Sends a `Message` to the peers which completed the handshake, except the
+    /// one it came from.
+    fn relay(&mut self, source: Option<u64>, message: &Message) {
+        let peers_ids: Vec<u64> = self.peers.iter()
+            .filter(|&(id, peer)| Some(*id) != source && peer.handshake.is_some())
+            .map(|(id, _)| *id)
+            .collect();
+
+        for peer_id in peers_ids {
+            self.send(peer_id, message.clone());
+        }
+    }
+
+    /// Handles a `Message` received from a peer. On error the peer is disconnected.
+    fn handle_message(&mut self, peer_id: u64, message: Message) -> Result<()> {
+        message.validate()?;
+
+        if let Message::Handshake(handshake) = message {
+            if self.peers[&peer_id].handshake.is_some() {
+                return Err(ErrorKind::AlreadyFound.into());
+            }
+
+            self.verify_handshake(&handshake)?;
+
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.handshake = Some(handshake);
+            }
+
+            return Ok(());
+        }
+
+        if self.peers[&peer_id].handshake.is_none() {
+            return Err(ErrorKind::NotSupported.into());
+        }
+
+        match message {
+            Message::Ping(nonce) => {
+                self.send(peer_id, Message::Pong(nonce));
+            },
+            Message::GetHeaders(get_headers) => {
+                let block_headers = self.get_headers(&get_headers)?;
+                self.send(peer_id, Message::Headers(block_headers));
+            },
+            Message::GetBlock(id) => {
+                if self.store.has_block(id)? {
+                    let block = self.store.get_block(id)?;
+                    self.send(peer_id, Message::Block(block));
+                } else {
+                    let item = InventoryItem::new(InventoryKind::Block, id);
+                    self.send(peer_id, Message::NotFound(vec![item]));
+                }
+            },
+            Message::GetTransactions(ids) => {
+                let mut not_found = Vec::new();
+
+                for id in ids {
+                    if self.store.has_transaction(id)? {
+                        let transaction = self.store.get_transaction(id)?;
+                        self.send(peer_id, Message::Transaction(transaction));
+                    } else {
+                        not_found.push(InventoryItem::new(InventoryKind::Transaction, id));
+                    }
+                }
+
+                if !not_found.is_empty() {
+                    self.send(peer_id, Message::NotFound(not_found));
+                }
+            },
+            Message::GetData(ids) => {
+                let mut not_found = Vec::new();
+
+                for id in ids {
+                    if self.store.has_data(id)? {
+                        let data = self.store.get_data(id)?;
+                        self.send(peer_id, Message::Data(data));
+                    } else {
+                        not_found.push(InventoryItem::new(InventoryKind::Data, id));
+                    }
+                }
+
+                if !not_found.is_empty() {
+                    self.send(peer_id, Message::NotFound(not_found));
+                }
+            },
+            Message::GetBlockTransactions(get_block_transactions) => {
+                if self.store.has_block(get_block_transactions.block_id)? {
+                    let block_transactions = self.get_block_transactions(&get_block_transactions)?;
+                    self.send(peer_id, Message::BlockTransactions(block_transactions));
+                } else {
+                    let item = InventoryItem::new(InventoryKind::Block, get_block_transactions.block_id);
+                    self.send(peer_id, Message::NotFound(vec![item]));
+                }
+            },
+            Message::GetBlockFilter(id) => {
+                match self.get_block_filter(id)? {
+                    Some(block_filter) => {
+                        self.send(peer_id, Message::BlockFilter(block_filter));
+                    },
+                    None => {
+                        let item = InventoryItem::new(InventoryKind::BlockHeader, id);
+                        self.send(peer_id, Message::NotFound(vec![item]));
+                    },
+                }
+            },
+            Message::Inventory(items) => {
+                self.request_inventory(peer_id, &items)?;
+            },
+            Message::CompactBlock(compact_block) => {
+                // without a pool of transactions to reconstruct it, the full block
+                // is requested
+                compact_block.validate()?;
+
+                if !self.store.has_block(compact_block.block_id)? {
+                    self.send(peer_id, Message::GetBlock(compact_block.block_id));
+                }
+            },
+            Message::Headers(_) |
+            Message::Block(_) |
+            Message::Transaction(_) |
+            Message::Data(_) => {
+                self.accept(Some(peer_id), message)?;
+            },
+            _ => {},
+        }
+
+        Ok(())
+    }
+
+    /// Returns the stored block headers following the first known block header of
+    /// the locator of a `GetHeaders`, up to its stop id. Where the chain forks the
+    /// first stored block header is followed.
+    fn get_headers(&self, get_headers: &GetHeaders) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+        let mut prev_id = None;
+
+        for id in &get_headers.locator {
+            if self.store.has_block_header(*id)? {
+                prev_id = Some(*id);
+                break;
+            }
+        }
+
+        let mut prev_id = match prev_id {
+            Some(prev_id) => prev_id,
+            None => return Ok(block_headers),
+        };
+
+        while block_headers.len() < MAX_HEADERS_LENGTH as usize {
+            let block_header = match self.store.get_block_headers_by_prev_id(prev_id)?.into_iter().next() {
+                Some(block_header) => block_header,
+                None => break,
+            };
+
+            prev_id = block_header.id;
+            block_headers.push(block_header);
+
+            if prev_id == get_headers.stop_id {
+                break;
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    /// Returns the stored transactions of a block requested by a `GetBlockTransactions`.
+    fn get_block_transactions(&self, get_block_transactions: &GetBlockTransactions) -> Result<BlockTransactions> {
+        let block = self.store.get_block(get_block_transactions.block_id)?;
+
+        let mut transactions = Vec::new();
+
+        for index in &get_block_transactions.indexes {
+            let id = match block.transactions_ids.get(*index as usize) {
+                Some(id) => *id,
+                None => return Err(ErrorKind::OutOfBound.into()),
+            };
+
+            transactions.push(self.store.get_transaction(id)?);
+        }
+
+        let block_transactions = BlockTransactions {
+            block_id: block.id,
+            transactions: transactions,
+        };
+
+        Ok(block_transactions)
+    }
+
+    /// Returns the `BlockFilter` of the block of a stored block header, if the block
+    /// and its transactions are stored.
+    fn get_block_filter(&self, id: Digest) -> Result<Option<BlockFilter>> {
+        if !self.store.has_block_header(id)? {
+            return Ok(None);
+        }
+
+        let block_header = self.store.get_block_header(id)?;
+
+        if !self.store.has_block(block_header.block_id)? {
+            return Ok(None);
+        }
+
+        let block = self.store.get_block(block_header.block_id)?;
+
+        let mut transactions = Vec::new();
+
+        for id in &block.transactions_ids {
+            if !self.store.has_transaction(*id)? {
+                return Ok(None);
+            }
+
+            transactions.push(self.store.get_transaction(*id)?);
+        }
+
+        Ok(Some(BlockFilter::new(&block_header, &block, &transactions)?))
+    }
+
+    /// Requests to a peer the announced items missing from the `Store`.
+    fn request_inventory(&mut self, peer_id: u64, items: &[InventoryItem]) -> Result<()> {
+        let mut transactions_ids = Vec::new();
+        let mut data_ids = Vec::new();
+
+        for item in items {
+            match item.kind {
+                InventoryKind::Block => {
+                    if !self.store.has_block(item.id)? {
+                        self.send(peer_id, Message::GetBlock(item.id));
+                    }
+                },
+                InventoryKind::Transaction => {
+                    if !self.store.has_transaction(item.id)? {
+                        transactions_ids.push(item.id);
+                    }
+                },
+                InventoryKind::Data => {
+                    if !self.store.has_data(item.id)? {
+                        data_ids.push(item.id);
+                    }
+                },
+                InventoryKind::BlockHeader => {},
+            }
+        }
+
+        if !transactions_ids.is_empty() {
+            self.send(peer_id, Message::GetTransactions(transactions_ids));
+        }
+
+        if !data_ids.is_empty() {
+            self.send(peer_id, Message::GetData(data_ids));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a `BlockHeader` against its stored ancestors, that is its PoW, retarget
+    /// and timestamp, so that only the block headers of a verified chain are stored.
+    fn verify_block_header(&self, block_header: &BlockHeader) -> Result<()> {
+        if block_header.height == 0 {
+            if !block_header.is_genesis()? {
+                return Err(ErrorKind::InvalidGenesis.into());
+            }
+
+            return Ok(());
+        }
+
+        if !self.store.has_block_header(block_header.prev_id)? {
+            return Err(ErrorKind::NotFound.with_field("prev_id"));
+        }
+
+        let prev_block_header = self.store.get_block_header(block_header.prev_id)?;
+
+        if !block_header.verify_prev(&prev_block_header)? {
+            return Err(ErrorKind::InvalidChain.into());
+        }
+
+        let window_length = block_header.height.min(MEDIAN_TIME_SPAN);
+
+        let mut ancestors = vec![prev_block_header];
+
+        while ancestors.len() < window_length as usize {
+            let prev_id = ancestors[ancestors.len() - 1].prev_id;
+            ancestors.push(self.store.get_block_header(prev_id)?);
+        }
+
+        ancestors.reverse();
+
+        if !block_header.verify_timestamp(&ancestors, Timestamp::now())? {
+            return Err(ErrorKind::InvalidTimestamp.into());
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a `Block` and its transactions against the stored block headers
+    /// referencing it, returning if any of them verifies the block.
+    fn verify_block(&self,
+                    block_headers: &[BlockHeader],
+                    block: &Block,
+                    transactions: &[Transaction]) -> Result<bool> {
+        for block_header in block_headers {
+            let prev_block_header = if block_header.height == 0 {
+                None
+            } else {
+                Some(self.store.get_block_header(block_header.prev_id)?)
+            };
+
+            if block_header.verify(block, transactions, prev_block_header.as_ref())? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Validates and stores the new block headers, block, transaction or data of a
+    /// `Message`, relaying the new block headers, blocks and transactions. The block
+    /// headers are stored only if they follow a stored block header, and the blocks
+    /// only if they are verified by a stored block header against their transactions.
+    /// The missing transactions of a block are requested to its peer, and the block
+    /// is accepted when received again.
+    fn accept(&mut self, source: Option<u64>, message: Message) -> Result<()> {
+        let relayed = match message {
+            Message::Headers(block_headers) => {
+                let mut new_block_headers = Vec::new();
+
+                for block_header in block_headers {
+                    if self.store.has_block_header(block_header.id)? {
+                        continue;
+                    }
+
+                    block_header.validate()?;
+
+                    if block_header.network_type != self.network_type {
+                        return Err(ErrorKind::InvalidNetwork.into());
+                    }
+
+                    self.verify_block_header(&block_header)?;
+
+                    self.store.put_block_header(&block_header)?;
+
+                    if block_header.height > self.height {
+                        self.height = block_header.height;
+                    }
+
+                    new_block_headers.push(block_header);
+                }
+
+                if new_block_headers.is_empty() {
+                    None
+                } else {
+                    Some(Message::Headers(new_block_headers))
+                }
+            },
+            Message::Block(block) => {
+                if self.store.has_block(block.id)? {
+                    return Ok(());
+                }
+
+                block.validate()?;
+
+                if block.network_type != self.network_type {
+                    return Err(ErrorKind::InvalidNetwork.into());
+                }
+
+                let block_headers = self.store.get_block_headers_by_block_id(block.id)?;
+
+                if block_headers.is_empty() {
+                    return Err(ErrorKind::NotFound.with_field("block_id"));
+                }
+
+                let mut transactions = Vec::new();
+                let mut missing_ids = Vec::new();
+
+                for id in &block.transactions_ids {
+                    if self.store.has_transaction(*id)? {
+                        transactions.push(self.store.get_transaction(*id)?);
+                    } else {
+                        missing_ids.push(*id);
+                    }
+                }
+
+                if !missing_ids.is_empty() {
+                    if let Some(peer_id) = source {
+                        self.send(peer_id, Message::GetTransactions(missing_ids));
+                    }
+
+                    return Ok(());
+                }
+
+                if !self.verify_block(&block_headers, &block, &transactions)? {
+                    return Err(ErrorKind::InvalidBlock.into());
+                }
+
+                self.store.put_block(&block)?;
+
+                Some(Message::Block(block))
+            },
+            Message::Transaction(transaction) => {
+                if self.store.has_transaction(transaction.id)? {
+                    return Ok(());
+                }
+
+                transaction.validate()?;
+
+                if transaction.network_type != self.network_type {
+                    return Err(ErrorKind::InvalidNetwork.into());
+                }
+
+                self.store.put_transaction(&transaction)?;
+
+                Some(Message::Transaction(transaction))
+            },
+            Message::Data(data) => {
+                if !self.store.has_data(data.id)? {
+                    data.validate()?;
+                    self.store.put_data(&data)?;
+                }
+
+                None
+            },
+            _ => return Err(ErrorKind::NotSupported.into()),
+        };
+
+        if let Some(message) = relayed {
+            self.relay(source, &message);
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: Store> Future for Node<S> {
+    type Item = ();
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<(), Error> {
+        loop {
+            match self.commands.poll() {
+                Ok(Async::Ready(Some(NodeCommand::AddPeer(connection)))) => {
+                    self.add_peer(connection);
+                },
+                Ok(Async::Ready(Some(NodeCommand::Broadcast(message)))) => {
+                    // an invalid item is just not relayed
+                    let _ = self.accept(None, *message);
+                },
+                Ok(Async::Ready(Some(NodeCommand::Shutdown))) |
+                Ok(Async::Ready(None)) |
+                Err(_) => return Ok(Async::Ready(())),
+                Ok(Async::NotReady) => break,
+            }
+        }
+
+        let peers_ids: Vec<u64> = self.peers.keys().cloned().collect();
+
+        for peer_id in peers_ids {
+            while let Some(peer) = self.peers.get_mut(&peer_id) {
+                match peer.connection.poll_recv() {
+                    Async::Ready(Some(message)) => {
+                        if self.handle_message(peer_id, message).is_err() {
+                            self.peers.remove(&peer_id);
+                        }
+                    },
+                    Async::Ready(None) => {
+                        self.peers.remove(&peer_id);
+                    },
+                    Async::NotReady => break,
+                }
+            }
+        }
+
+        Ok(Async::NotReady)
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `peer` module provides the peer connection type and methods, used to
+//! exchange protocol messages with a peer.
+
+use futures::{Stream, Async};
+use futures::sync::mpsc::{self, UnboundedSender, UnboundedReceiver};
+
+use error::ErrorKind;
+use result::Result;
+use protocol::Message;
+
+use std::net::{TcpStream, Shutdown};
+use std::thread;
+
+/// A `PeerConnection` is one end of a connection with a peer.
+#[derive(Debug)]
+pub struct PeerConnection {
+    /// The sender of the messages to the peer.
+    sender: UnboundedSender<Message>,
+    /// The receiver of the messages from the peer.
+    receiver: UnboundedReceiver<Message>,
+}
+
+impl PeerConnection {
+    /// Creates the two ends of an in-process connection.
+    pub fn loopback() -> (PeerConnection, PeerConnection) {
+        let (sender_a, receiver_b) = mpsc::unbounded();
+        let (sender_b, receiver_a) = mpsc::unbounded();
+
+        let connection_a = PeerConnection {
+            sender: sender_a,
+            receiver: receiver_a,
+        };
+
+        let connection_b = PeerConnection {
+            sender: sender_b,
+            receiver: receiver_b,
+        };
+
+        (connection_a, connection_b)
+    }
+
+    /// Creates a `PeerConnection` over a TCP stream. The stream is read and written
+    /// by two threads, which stop when the connection is closed by either side.
+    pub fn from_tcp(stream: TcpStream) -> Result<PeerConnection> {
+        let (connection, remote) = PeerConnection::loopback();
+        let PeerConnection { sender, receiver } = remote;
+
+        let mut reader = stream.try_clone()?;
+        let mut writer = stream;
+
+        thread::spawn(move || {
+            while let Ok(message) = Message::read_from(&mut reader) {
+                if sender.unbounded_send(message).is_err() {
+                    break;
+                }
+            }
+
+            let _ = reader.shutdown(Shutdown::Both);
+        });
+
+        thread::spawn(move || {
+            for message in receiver.wait() {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(_) => break,
+                };
+
+                if message.write_to(&mut writer).is_err() {
+                    break;
+                }
+            }
+
+            let _ = writer.shutdown(Shutdown::Both);
+        });
+
+        Ok(connection)
+    }
+
+    /// Sends a `Message` to the peer.
+    pub fn send(&self, message: Message) -> Result<()> {
+        if self.sender.unbounded_send(message).is_err() {
+            return Err(ErrorKind::Disconnected.into());
+        }
+
+        Ok(())
+    }
+
+    /// Blocks until a `Message` is received from the peer. Returns `None` if the
+    /// connection is closed.
+    pub fn recv(&mut self) -> Option<Message> {
+        match (&mut self.receiver).wait().next() {
+            Some(Ok(message)) => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Polls the next `Message` from the peer, without blocking. Returns `None` if
+    /// the connection is closed.
+    pub fn poll_recv(&mut self) -> Async<Option<Message>> {
+        match self.receiver.poll() {
+            Ok(state) => state,
+            Err(_) => Async::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `sync` module provides the sync engine, used to download the chain of a peer
+//! headers-first: the block headers are downloaded and verified as a chain from the
+//! genesis, then the blocks and their transactions are downloaded and verified
+//! against the block headers.
+
+use constants::{MAX_HEADERS_LENGTH, MEDIAN_TIME_SPAN, MAX_IGNORED_MESSAGES};
+use error::ErrorKind;
+use result::Result;
+use traits::Validate;
+use utils::{Version, NetworkType, Timestamp};
+use crypto::Digest;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+use store::Store;
+use protocol::{Message, Handshake, GetHeaders};
+use node::peer::PeerConnection;
+
+use std::collections::BTreeMap;
+
+/// Trait for the transports used by a `SyncEngine` to request items to a peer.
+pub trait SyncTransport {
+    /// Requests the block headers following the first block header of a locator
+    /// known by the peer.
+    fn get_headers(&mut self, locator: &[Digest]) -> Result<Vec<BlockHeader>>;
+
+    /// Requests a block by id.
+    fn get_block(&mut self, id: Digest) -> Result<Block>;
+
+    /// Requests transactions by id, returned in the same order.
+    fn get_transactions(&mut self, ids: &[Digest]) -> Result<Vec<Transaction>>;
+}
+
+/// The states of a `SyncEngine`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum SyncState {
+    /// Downloading the block headers.
+    Headers,
+    /// Downloading the blocks of the block headers.
+    Blocks,
+    /// Synced.
+    Done,
+}
+
+/// A `SyncProgress` is the progress of a `SyncEngine`, which can be saved to resume
+/// the sync later.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SyncProgress {
+    /// The state of the sync.
+    pub state: SyncState,
+    /// The verified block headers, from the genesis.
+    pub block_headers: Vec<BlockHeader>,
+    /// The number of block headers whose block has been downloaded.
+    pub blocks_length: u32,
+}
+
+/// A `SyncEngine` downloads the chain of a peer through a `SyncTransport`, putting
+/// the verified items in a `Store`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SyncEngine {
+    /// The network type of the chain.
+    pub network_type: NetworkType,
+    /// The progress of the sync.
+    progress: SyncProgress,
+}
+
+impl SyncEngine {
+    /// Creates a new `SyncEngine` from the genesis `BlockHeader`.
+    pub fn new(genesis: &BlockHeader) -> Result<SyncEngine> {
+        genesis.validate()?;
+
+        if genesis.height != 0 {
+            return Err(ErrorKind::InvalidGenesis.into());
+        }
+
+        let progress = SyncProgress {
+            state: SyncState::Headers,
+            block_headers: vec![genesis.clone()],
+            blocks_length: 0,
+        };
+
+        SyncEngine::resume(progress)
+    }
+
+    /// Creates a `SyncEngine` resuming a previous `SyncProgress`.
+    pub fn resume(progress: SyncProgress) -> Result<SyncEngine> {
+        let network_type = match progress.block_headers.first() {
+            Some(genesis) => genesis.network_type,
+            None => return Err(ErrorKind::InvalidLength.into()),
+        };
+
+        if progress.blocks_length as usize > progress.block_headers.len() {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        let engine = SyncEngine {
+            network_type: network_type,
+            progress: progress,
+        };
+
+        Ok(engine)
+    }
+
+    /// Returns the `SyncProgress`.
+    pub fn progress(&self) -> &SyncProgress {
+        &self.progress
+    }
+
+    /// Returns the `SyncState`.
+    pub fn state(&self) -> SyncState {
+        self.progress.state
+    }
+
+    /// Returns the height of the last verified block header.
+    pub fn height(&self) -> u32 {
+        self.progress.block_headers.len() as u32 - 1
+    }
+
+    /// Makes a single request to the peer, advancing the sync. Returns the new state.
+    pub fn step<T: SyncTransport, S: Store>(&mut self, transport: &mut T, store: &mut S) -> Result<SyncState> {
+        match self.progress.state {
+            SyncState::Headers => self.sync_headers(transport)?,
+            SyncState::Blocks => self.sync_block(transport, store)?,
+            SyncState::Done => {},
+        }
+
+        Ok(self.progress.state)
+    }
+
+    /// Syncs until the chain of the peer is downloaded. On error the sync can be
+    /// resumed from the `SyncProgress`.
+    pub fn run<T: SyncTransport, S: Store>(&mut self, transport: &mut T, store: &mut S) -> Result<()> {
+        while self.step(transport, store)? != SyncState::Done {}
+
+        Ok(())
+    }
+
+    /// Downloads and verifies the block headers following the last one. When there
+    /// are no more block headers the blocks are downloaded.
+    fn sync_headers<T: SyncTransport>(&mut self, transport: &mut T) -> Result<()> {
+        let tip_id = self.progress.block_headers.last().unwrap().id;
+        let block_headers = transport.get_headers(&[tip_id])?;
+
+        if block_headers.len() > MAX_HEADERS_LENGTH as usize {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        if block_headers.is_empty() {
+            self.progress.state = SyncState::Blocks;
+            return Ok(());
+        }
+
+        let verified_length = self.progress.block_headers.len();
+        let now = Timestamp::now();
+
+        for block_header in block_headers {
+            if let Err(err) = self.verify_block_header(&block_header, now) {
+                self.progress.block_headers.truncate(verified_length);
+                return Err(err);
+            }
+
+            self.progress.block_headers.push(block_header);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a `BlockHeader` following the last verified one.
+    fn verify_block_header(&self, block_header: &BlockHeader, now: Timestamp) -> Result<()> {
+        let block_headers = &self.progress.block_headers;
+        let prev_block_header = block_headers.last().unwrap();
+
+        if !block_header.verify_prev(prev_block_header)? {
+            return Err(ErrorKind::InvalidChain.into());
+        }
+
+        let window_length = block_header.height.min(MEDIAN_TIME_SPAN) as usize;
+        let ancestors = &block_headers[block_headers.len() - window_length..];
+
+        if !block_header.verify_timestamp(ancestors, now)? {
+            return Err(ErrorKind::InvalidTimestamp.into());
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the block of the first block header without it, with its transactions,
+    /// verifying them against the block header.
+    fn sync_block<T: SyncTransport, S: Store>(&mut self, transport: &mut T, store: &mut S) -> Result<()> {
+        let index = self.progress.blocks_length as usize;
+
+        if index == self.progress.block_headers.len() {
+            self.progress.state = SyncState::Done;
+            return Ok(());
+        }
+
+        let block_header = &self.progress.block_headers[index];
+
+        let block = transport.get_block(block_header.block_id)?;
+
+        if block.id != block_header.block_id {
+            return Err(ErrorKind::InvalidID.into());
+        }
+
+        let transactions = transport.get_transactions(&block.transactions_ids)?;
+
+        if transactions.len() != block.transactions_ids.len() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        for (transaction, id) in transactions.iter().zip(block.transactions_ids.iter()) {
+            if transaction.id != *id {
+                return Err(ErrorKind::InvalidTransaction.into());
+            }
+
+            transaction.validate()?;
+        }
+
+        let prev_block_header = if index == 0 {
+            None
+        } else {
+            Some(&self.progress.block_headers[index - 1])
+        };
+
+        if !block_header.verify(&block, &transactions, prev_block_header)? {
+            return Err(ErrorKind::InvalidBlock.into());
+        }
+
+        if !store.has_block_header(block_header.id)? {
+            store.put_block_header(block_header)?;
+        }
+
+        if !store.has_block(block.id)? {
+            store.put_block(&block)?;
+        }
+
+        for transaction in &transactions {
+            if !store.has_transaction(transaction.id)? {
+                store.put_transaction(transaction)?;
+            }
+        }
+
+        self.progress.blocks_length += 1;
+
+        if self.progress.blocks_length as usize == self.progress.block_headers.len() {
+            self.progress.state = SyncState::Done;
+        }
+
+        Ok(())
+    }
+}
+
+/// A `PeerTransport` is a `SyncTransport` requesting the items to a peer with
+/// protocol messages. While waiting for a response, at most `MAX_IGNORED_MESSAGES`
+/// unrelated messages are received from the peer.
+#[derive(Debug)]
+pub struct PeerTransport {
+    /// The connection with the peer.
+    connection: PeerConnection,
+    /// The number of unrelated messages received since the last request.
+    ignored: u32,
+}
+
+impl PeerTransport {
+    /// Creates a new `PeerTransport`, exchanging the handshakes with the peer.
+    pub fn new(connection: PeerConnection, network_type: NetworkType, height: u32) -> Result<PeerTransport> {
+        let mut transport = PeerTransport {
+            connection: connection,
+            ignored: 0,
+        };
+
+        transport.send(Message::Handshake(Handshake::new(network_type, height)?))?;
+
+        loop {
+            match transport.recv()? {
+                Message::Handshake(handshake) => {
+                    handshake.verify(network_type, &Version::current()?)?;
+                    break;
+                },
+                _ => transport.ignore()?,
+            }
+        }
+
+        Ok(transport)
+    }
+
+    /// Sends a request to the peer.
+    fn send(&mut self, message: Message) -> Result<()> {
+        self.ignored = 0;
+
+        self.connection.send(message)
+    }
+
+    /// Receives the next `Message`, answering the pings of the peer.
+    fn recv(&mut self) -> Result<Message> {
+        loop {
+            match self.connection.recv() {
+                Some(Message::Ping(nonce)) => {
+                    self.connection.send(Message::Pong(nonce))?;
+                    self.ignore()?;
+                },
+                Some(message) => return Ok(message),
+                None => return Err(ErrorKind::Disconnected.into()),
+            }
+        }
+    }
+
+    /// Counts an unrelated `Message` received while waiting for a response.
+    fn ignore(&mut self) -> Result<()> {
+        self.ignored += 1;
+
+        if self.ignored > MAX_IGNORED_MESSAGES {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncTransport for PeerTransport {
+    fn get_headers(&mut self, locator: &[Digest]) -> Result<Vec<BlockHeader>> {
+        let get_headers = GetHeaders {
+            locator: locator.to_vec(),
+            stop_id: Digest::default(),
+        };
+
+        self.send(Message::GetHeaders(get_headers))?;
+
+        loop {
+            match self.recv()? {
+                Message::Headers(block_headers) => return Ok(block_headers),
+                _ => self.ignore()?,
+            }
+        }
+    }
+
+    fn get_block(&mut self, id: Digest) -> Result<Block> {
+        self.send(Message::GetBlock(id))?;
+
+        loop {
+            match self.recv()? {
+                Message::Block(ref block) if block.id == id => return Ok(block.clone()),
+                Message::NotFound(ref items) if items.iter().any(|item| item.id == id) => {
+                    return Err(ErrorKind::NotFound.into());
+                },
+                _ => self.ignore()?,
+            }
+        }
+    }
+
+    fn get_transactions(&mut self, ids: &[Digest]) -> Result<Vec<Transaction>> {
+        self.send(Message::GetTransactions(ids.to_vec()))?;
+
+        let mut transactions: BTreeMap<Digest, Option<Transaction>> = ids.iter()
+            .map(|id| (*id, None))
+            .collect();
+
+        let mut missing = transactions.len();
+
+        while missing > 0 {
+            match self.recv()? {
+                Message::Transaction(transaction) => {
+                    let entry = transactions.get_mut(&transaction.id);
+
+                    if let Some(entry @ &mut None) = entry {
+                        *entry = Some(transaction);
+                        missing -= 1;
+                    } else {
+                        self.ignore()?;
+                    }
+                },
+                Message::NotFound(ref items) if items.iter().any(|item| transactions.contains_key(&item.id)) => {
+                    return Err(ErrorKind::NotFound.into());
+                },
+                _ => self.ignore()?,
+            }
+        }
+
+        Ok(ids.iter()
+            .map(|id| transactions[id].clone().unwrap())
+            .collect())
+    }
+}
@@ -0,0 +1,600 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `message` module provides the protocol messages and their binary encoding.
+//!
+//! A message is made of a header and a payload. The header holds the big endian `u32`
+//! protocol version, the kind byte and the big endian `u32` length of the payload,
+//! and the payload is the canonical encoding of the message content, the same used
+//! to compute the ids of the models, prefixed by the `ENCODING_VERSION`.
+
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+
+use constants::{PROTOCOL_VERSION, MAX_MESSAGE_SIZE, MAX_HEADERS_LENGTH};
+use constants::{MAX_INVENTORY_LENGTH, MAX_LOCATOR_LENGTH};
+use error::ErrorKind;
+use result::Result;
+use traits::{Validate, BinarySerialize, Encode};
+use utils::{Version, NetworkType};
+use utils::encoding::{Encoder, Decoder, DecodeLimits, DIGEST_SIZE, encode_to, decode_from};
+use crypto::Digest;
+use models::data::Data;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::compact_block::CompactBlock;
+use models::block_filter::BlockFilter;
+use models::block_header::BlockHeader;
+
+use std::io::{Read, Write};
+
+/// The length of a message header: protocol version, kind and payload length.
+pub const MESSAGE_HEADER_LENGTH: usize = 4 + 1 + 4;
+
+/// The kinds of the protocol messages.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MessageKind {
+    Handshake=0,
+    Ping=1,
+    Pong=2,
+    GetHeaders=3,
+    Headers=4,
+    GetBlock=5,
+    Block=6,
+    GetTransactions=7,
+    Transaction=8,
+    GetData=9,
+    Data=10,
+    Inventory=11,
+    NotFound=12,
+    CompactBlock=13,
+    GetBlockTransactions=14,
+    BlockTransactions=15,
+    GetBlockFilter=16,
+    BlockFilter=17,
+}
+
+impl MessageKind {
+    /// Creates a `MessageKind` from its byte.
+    pub fn from_u8(n: u8) -> Result<MessageKind> {
+        match n {
+            0 => Ok(MessageKind::Handshake),
+            1 => Ok(MessageKind::Ping),
+            2 => Ok(MessageKind::Pong),
+            3 => Ok(MessageKind::GetHeaders),
+            4 => Ok(MessageKind::Headers),
+            5 => Ok(MessageKind::GetBlock),
+            6 => Ok(MessageKind::Block),
+            7 => Ok(MessageKind::GetTransactions),
+            8 => Ok(MessageKind::Transaction),
+            9 => Ok(MessageKind::GetData),
+            10 => Ok(MessageKind::Data),
+            11 => Ok(MessageKind::Inventory),
+            12 => Ok(MessageKind::NotFound),
+            13 => Ok(MessageKind::CompactBlock),
+            14 => Ok(MessageKind::GetBlockTransactions),
+            15 => Ok(MessageKind::BlockTransactions),
+            16 => Ok(MessageKind::GetBlockFilter),
+            17 => Ok(MessageKind::BlockFilter),
+            _ => Err(ErrorKind::InvalidVariant.into()),
+        }
+    }
+
+    /// Returns the `DecodeLimits` of the payload of a message of the kind, bounding
+    /// its lists by the maximum length of the message lists.
+    pub fn decode_limits(&self) -> DecodeLimits {
+        let max_length = match *self {
+            MessageKind::GetHeaders => MAX_LOCATOR_LENGTH,
+            MessageKind::Headers => MAX_HEADERS_LENGTH,
+            MessageKind::GetTransactions |
+            MessageKind::GetData |
+            MessageKind::Inventory |
+            MessageKind::NotFound |
+            MessageKind::CompactBlock |
+            MessageKind::GetBlockTransactions |
+            MessageKind::BlockTransactions => MAX_INVENTORY_LENGTH,
+            // the lists of the models are bounded by the ids fitting in a message
+            _ => MAX_MESSAGE_SIZE / DIGEST_SIZE as u32,
+        };
+
+        DecodeLimits::new(u64::from(MAX_MESSAGE_SIZE), max_length)
+    }
+}
+
+/// A `MessageHeader` precedes the payload of every protocol message.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MessageHeader {
+    /// The protocol version.
+    pub version: u32,
+    /// The kind of the message.
+    pub kind: MessageKind,
+    /// The length of the payload.
+    pub length: u32,
+}
+
+impl MessageHeader {
+    /// Creates a new `MessageHeader` for the current protocol version.
+    pub fn new(kind: MessageKind, length: u32) -> Result<MessageHeader> {
+        let header = MessageHeader {
+            version: PROTOCOL_VERSION,
+            kind: kind,
+            length: length,
+        };
+
+        header.validate()?;
+
+        Ok(header)
+    }
+}
+
+impl Validate for MessageHeader {
+    fn validate(&self) -> Result<()> {
+        if self.version != PROTOCOL_VERSION {
+            return Err(ErrorKind::InvalidVersion.into());
+        }
+
+        if self.length > MAX_MESSAGE_SIZE {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl BinarySerialize for MessageHeader {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+
+        let mut buf = Vec::new();
+
+        buf.write_u32::<BigEndian>(self.version)?;
+        buf.write_u8(self.kind as u8)?;
+        buf.write_u32::<BigEndian>(self.length)?;
+
+        Ok(buf)
+    }
+
+    fn from_bytes(b: &[u8]) -> Result<MessageHeader> {
+        if b.len() != MESSAGE_HEADER_LENGTH {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let header = MessageHeader {
+            version: BigEndian::read_u32(&b[0..4]),
+            kind: MessageKind::from_u8(b[4])?,
+            length: BigEndian::read_u32(&b[5..9]),
+        };
+
+        header.validate()?;
+
+        Ok(header)
+    }
+}
+
+/// A `Handshake` is the first message sent by a node to a peer.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Handshake {
+    /// The version of the node.
+    pub version: Version,
+    /// The network type of the node.
+    pub network_type: NetworkType,
+    /// The height of the best chain of the node.
+    pub height: u32,
+}
+
+impl Handshake {
+    /// Creates a new `Handshake` with the current `Version`.
+    pub fn new(network_type: NetworkType, height: u32) -> Result<Handshake> {
+        let handshake = Handshake {
+            version: Version::current()?,
+            network_type: network_type,
+            height: height,
+        };
+
+        Ok(handshake)
+    }
+
+    /// Verifies if the `Handshake` `Version` is compatible with a local one, that is
+    /// if it has the same major version, and the same minor version before 1.0.
+    pub fn is_compatible(&self, version: &Version) -> bool {
+        if self.version.major != version.major {
+            return false;
+        }
+
+        version.major != 0 || self.version.minor == version.minor
+    }
+
+    /// Verifies the `Handshake` of a peer against the local network type and `Version`.
+    pub fn verify(&self, network_type: NetworkType, version: &Version) -> Result<()> {
+        if self.network_type != network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        if !self.is_compatible(version) {
+            return Err(ErrorKind::InvalidVersion.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Encode for Handshake {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write(&self.version)?;
+        encoder.write(&self.network_type)?;
+        encoder.write_u32(self.height)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Handshake> {
+        let handshake = Handshake {
+            version: decoder.read()?,
+            network_type: decoder.read()?,
+            height: decoder.read_u32()?,
+        };
+
+        Ok(handshake)
+    }
+}
+
+/// A `GetHeaders` requests the block headers following the first known block header
+/// of a locator.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct GetHeaders {
+    /// The ids of block headers of the requesting node, from its tip backwards.
+    pub locator: Vec<Digest>,
+    /// The id of the block header to stop at, or the default digest to get as many
+    /// block headers as possible.
+    pub stop_id: Digest,
+}
+
+impl Encode for GetHeaders {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digests(&self.locator)?;
+        encoder.write_digest(self.stop_id)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<GetHeaders> {
+        let get_headers = GetHeaders {
+            locator: decoder.read_digests()?,
+            stop_id: decoder.read_digest()?,
+        };
+
+        Ok(get_headers)
+    }
+}
+
+/// The kinds of the items announced by an inventory.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum InventoryKind {
+    BlockHeader,
+    Block,
+    Transaction,
+    Data,
+}
+
+impl Encode for InventoryKind {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_u8(*self as u8)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<InventoryKind> {
+        match decoder.read_u8()? {
+            0 => Ok(InventoryKind::BlockHeader),
+            1 => Ok(InventoryKind::Block),
+            2 => Ok(InventoryKind::Transaction),
+            3 => Ok(InventoryKind::Data),
+            _ => Err(ErrorKind::InvalidVariant.into()),
+        }
+    }
+}
+
+/// An `InventoryItem` announces an item available from a node.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InventoryItem {
+    /// The kind of the item.
+    pub kind: InventoryKind,
+    /// The id of the item.
+    pub id: Digest,
+}
+
+impl InventoryItem {
+    /// Creates a new `InventoryItem`.
+    pub fn new(kind: InventoryKind, id: Digest) -> InventoryItem {
+        InventoryItem {
+            kind: kind,
+            id: id,
+        }
+    }
+}
+
+impl Encode for InventoryItem {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write(&self.kind)?;
+        encoder.write_digest(self.id)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<InventoryItem> {
+        let kind = decoder.read()?;
+        let id = decoder.read_digest()?;
+
+        Ok(InventoryItem::new(kind, id))
+    }
+}
+
+/// A `GetBlockTransactions` requests the transactions of a block missing from the
+/// reconstruction of its `CompactBlock`.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct GetBlockTransactions {
+    /// The id of the block.
+    pub block_id: Digest,
+    /// The indexes of the transactions in the block, in ascending order.
+    pub indexes: Vec<u32>,
+}
+
+impl Encode for GetBlockTransactions {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.block_id)?;
+        encoder.write(&self.indexes)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<GetBlockTransactions> {
+        let get_block_transactions = GetBlockTransactions {
+            block_id: decoder.read_digest()?,
+            indexes: decoder.read()?,
+        };
+
+        Ok(get_block_transactions)
+    }
+}
+
+/// A `BlockTransactions` answers a `GetBlockTransactions`.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct BlockTransactions {
+    /// The id of the block.
+    pub block_id: Digest,
+    /// The requested transactions, in the order of their indexes.
+    pub transactions: Vec<Transaction>,
+}
+
+impl Encode for BlockTransactions {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(self.block_id)?;
+        encoder.write_list(&self.transactions)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<BlockTransactions> {
+        let block_transactions = BlockTransactions {
+            block_id: decoder.read_digest()?,
+            transactions: decoder.read_list()?,
+        };
+
+        Ok(block_transactions)
+    }
+}
+
+/// A `Message` is a message of the peer-to-peer protocol.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Message {
+    /// Opens the connection with a peer.
+    Handshake(Handshake),
+    /// Checks if a peer is alive, with a nonce to be echoed.
+    Ping(u64),
+    /// Answers a `Ping`, with its nonce.
+    Pong(u64),
+    /// Requests block headers.
+    GetHeaders(GetHeaders),
+    /// Sends block headers, sorted by height.
+    Headers(Vec<BlockHeader>),
+    /// Requests a block by id.
+    GetBlock(Digest),
+    /// Sends a block.
+    Block(Block),
+    /// Requests transactions by id.
+    GetTransactions(Vec<Digest>),
+    /// Sends a transaction.
+    Transaction(Transaction),
+    /// Requests data by id.
+    GetData(Vec<Digest>),
+    /// Sends data.
+    Data(Data),
+    /// Announces the items available from a node.
+    Inventory(Vec<InventoryItem>),
+    /// Answers a request with the items not available from a node.
+    NotFound(Vec<InventoryItem>),
+    /// Sends a block as the short ids of its transactions.
+    CompactBlock(CompactBlock),
+    /// Requests the transactions of a block by index.
+    GetBlockTransactions(GetBlockTransactions),
+    /// Sends the transactions of a block.
+    BlockTransactions(BlockTransactions),
+    /// Requests the filter of a block by block header id.
+    GetBlockFilter(Digest),
+    /// Sends the filter of a block.
+    BlockFilter(BlockFilter),
+}
+
+impl Message {
+    /// Returns the kind of the `Message`.
+    pub fn kind(&self) -> MessageKind {
+        match *self {
+            Message::Handshake(_) => MessageKind::Handshake,
+            Message::Ping(_) => MessageKind::Ping,
+            Message::Pong(_) => MessageKind::Pong,
+            Message::GetHeaders(_) => MessageKind::GetHeaders,
+            Message::Headers(_) => MessageKind::Headers,
+            Message::GetBlock(_) => MessageKind::GetBlock,
+            Message::Block(_) => MessageKind::Block,
+            Message::GetTransactions(_) => MessageKind::GetTransactions,
+            Message::Transaction(_) => MessageKind::Transaction,
+            Message::GetData(_) => MessageKind::GetData,
+            Message::Data(_) => MessageKind::Data,
+            Message::Inventory(_) => MessageKind::Inventory,
+            Message::NotFound(_) => MessageKind::NotFound,
+            Message::CompactBlock(_) => MessageKind::CompactBlock,
+            Message::GetBlockTransactions(_) => MessageKind::GetBlockTransactions,
+            Message::BlockTransactions(_) => MessageKind::BlockTransactions,
+            Message::GetBlockFilter(_) => MessageKind::GetBlockFilter,
+            Message::BlockFilter(_) => MessageKind::BlockFilter,
+        }
+    }
+
+    /// Encodes the payload of the `Message`.
+    fn payload_to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+
+        match *self {
+            Message::Handshake(ref handshake) => encode_to(handshake, &mut buf)?,
+            Message::Ping(ref nonce) |
+            Message::Pong(ref nonce) => encode_to(nonce, &mut buf)?,
+            Message::GetHeaders(ref get_headers) => encode_to(get_headers, &mut buf)?,
+            Message::Headers(ref block_headers) => encode_to(block_headers, &mut buf)?,
+            Message::GetBlock(ref id) |
+            Message::GetBlockFilter(ref id) => encode_to(id, &mut buf)?,
+            Message::Block(ref block) => encode_to(block, &mut buf)?,
+            Message::GetTransactions(ref ids) |
+            Message::GetData(ref ids) => encode_to(ids, &mut buf)?,
+            Message::Transaction(ref transaction) => encode_to(transaction, &mut buf)?,
+            Message::Data(ref data) => encode_to(data, &mut buf)?,
+            Message::Inventory(ref items) |
+            Message::NotFound(ref items) => encode_to(items, &mut buf)?,
+            Message::CompactBlock(ref compact_block) => encode_to(compact_block, &mut buf)?,
+            Message::GetBlockTransactions(ref get_block_transactions) => encode_to(get_block_transactions, &mut buf)?,
+            Message::BlockTransactions(ref block_transactions) => encode_to(block_transactions, &mut buf)?,
+            Message::BlockFilter(ref block_filter) => encode_to(block_filter, &mut buf)?,
+        }
+
+        Ok(buf)
+    }
+
+    /// Decodes the payload of a `Message` of a given kind from a reader, within the
+    /// `DecodeLimits` of the kind.
+    fn payload_from_reader<R: Read>(kind: MessageKind, mut reader: R) -> Result<Message> {
+        let limits = kind.decode_limits();
+
+        let message = match kind {
+            MessageKind::Handshake => Message::Handshake(decode_from(&mut reader, limits)?),
+            MessageKind::Ping => Message::Ping(decode_from(&mut reader, limits)?),
+            MessageKind::Pong => Message::Pong(decode_from(&mut reader, limits)?),
+            MessageKind::GetHeaders => Message::GetHeaders(decode_from(&mut reader, limits)?),
+            MessageKind::Headers => Message::Headers(decode_from(&mut reader, limits)?),
+            MessageKind::GetBlock => Message::GetBlock(decode_from(&mut reader, limits)?),
+            MessageKind::Block => Message::Block(decode_from(&mut reader, limits)?),
+            MessageKind::GetTransactions => Message::GetTransactions(decode_from(&mut reader, limits)?),
+            MessageKind::Transaction => Message::Transaction(decode_from(&mut reader, limits)?),
+            MessageKind::GetData => Message::GetData(decode_from(&mut reader, limits)?),
+            MessageKind::Data => Message::Data(decode_from(&mut reader, limits)?),
+            MessageKind::Inventory => Message::Inventory(decode_from(&mut reader, limits)?),
+            MessageKind::NotFound => Message::NotFound(decode_from(&mut reader, limits)?),
+            MessageKind::CompactBlock => Message::CompactBlock(decode_from(&mut reader, limits)?),
+            MessageKind::GetBlockTransactions => Message::GetBlockTransactions(decode_from(&mut reader, limits)?),
+            MessageKind::BlockTransactions => Message::BlockTransactions(decode_from(&mut reader, limits)?),
+            MessageKind::GetBlockFilter => Message::GetBlockFilter(decode_from(&mut reader, limits)?),
+            MessageKind::BlockFilter => Message::BlockFilter(decode_from(&mut reader, limits)?),
+        };
+
+        Ok(message)
+    }
+
+    /// Decodes the payload of a `Message` of a given kind, failing on trailing bytes.
+    fn payload_from_bytes(kind: MessageKind, b: &[u8]) -> Result<Message> {
+        let mut reader = b;
+        let message = Message::payload_from_reader(kind, &mut reader)?;
+
+        if !reader.is_empty() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        Ok(message)
+    }
+
+    /// Writes the `Message` to a writer.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.to_bytes()?)?;
+
+        Ok(())
+    }
+
+    /// Reads a `Message` from a reader.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Message> {
+        let mut header_buf = [0u8; MESSAGE_HEADER_LENGTH];
+        reader.read_exact(&mut header_buf)?;
+
+        let header = MessageHeader::from_bytes(&header_buf)?;
+
+        // the payload is decoded as it is read, so that its declared length is
+        // not allocated before
+        let mut payload = reader.take(u64::from(header.length));
+        let message = Message::payload_from_reader(header.kind, &mut payload)?;
+
+        if payload.limit() != 0 {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        message.validate()?;
+
+        Ok(message)
+    }
+}
+
+impl Validate for Message {
+    fn validate(&self) -> Result<()> {
+        let (length, max_length) = match *self {
+            Message::Handshake(ref handshake) => return handshake.version.validate(),
+            Message::GetHeaders(ref get_headers) => (get_headers.locator.len(), MAX_LOCATOR_LENGTH),
+            Message::Headers(ref block_headers) => (block_headers.len(), MAX_HEADERS_LENGTH),
+            Message::GetTransactions(ref ids) |
+            Message::GetData(ref ids) => (ids.len(), MAX_INVENTORY_LENGTH),
+            Message::Inventory(ref items) |
+            Message::NotFound(ref items) => (items.len(), MAX_INVENTORY_LENGTH),
+            Message::CompactBlock(ref compact_block) => (compact_block.short_ids.len(), MAX_INVENTORY_LENGTH),
+            Message::GetBlockTransactions(ref get_block_transactions) => (get_block_transactions.indexes.len(), MAX_INVENTORY_LENGTH),
+            Message::BlockTransactions(ref block_transactions) => (block_transactions.transactions.len(), MAX_INVENTORY_LENGTH),
+            _ => return Ok(()),
+        };
+
+        if length > max_length as usize {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl BinarySerialize for Message {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+
+        let payload = self.payload_to_bytes()?;
+
+        if payload.len() > MAX_MESSAGE_SIZE as usize {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        let header = MessageHeader::new(self.kind(), payload.len() as u32)?;
+
+        let mut buf = header.to_bytes()?;
+        buf.extend_from_slice(&payload);
+
+        Ok(buf)
+    }
+
+    fn from_bytes(b: &[u8]) -> Result<Message> {
+        if b.len() < MESSAGE_HEADER_LENGTH {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let header = MessageHeader::from_bytes(&b[..MESSAGE_HEADER_LENGTH])?;
+
+        if b.len() - MESSAGE_HEADER_LENGTH != header.length as usize {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let message = Message::payload_from_bytes(header.kind, &b[MESSAGE_HEADER_LENGTH..])?;
+        message.validate()?;
+
+        Ok(message)
+    }
+}
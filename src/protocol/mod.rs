@@ -0,0 +1,13 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `protocol` module provides the types and methods of the peer-to-peer protocol
+//! spoken between the nodes.
+
+pub mod message;
+
+pub use self::message::*;
@@ -13,3 +13,22 @@ use std::result::Result as StdResult;
 
 /// The `Result` alias type used in `libyobicash`.
 pub type Result<T> = StdResult<T, Error>;
+
+/// Trait for adding the field of a failure to the `Error` of a `Result`.
+pub trait ResultExt<T> {
+    /// Prepends a field to the path of the field which failed.
+    fn with_field(self, name: &'static str) -> Result<T>;
+
+    /// Prepends the item of a list field to the path of the field which failed.
+    fn with_index(self, name: &'static str, index: u32) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn with_field(self, name: &'static str) -> Result<T> {
+        self.map_err(|e| e.with_field(name))
+    }
+
+    fn with_index(self, name: &'static str, index: u32) -> Result<T> {
+        self.map_err(|e| e.with_index(name, index))
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `file` module provides the append-only file store type and methods.
+//!
+//! The store file is a sequence of records, each made of a kind byte, the 64 bytes
+//! id of the item, the big endian `u32` length of the item and the item bytes. The
+//! file is read once when opened to build the index of the records, and a truncated
+//! trailing record, left by an interrupted write, is discarded.
+
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+
+use error::ErrorKind;
+use result::Result;
+use traits::{Identify, Serialize};
+use crypto::Digest;
+use crypto::BinarySerialize as CryptoBinarySerialize;
+use models::output::Output;
+use models::data::Data;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+use store::Store;
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The length of a record header: kind, id and item length.
+const RECORD_HEADER_LENGTH: usize = 1 + 64 + 4;
+
+/// The kinds of the records of a `FileStore`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+enum RecordKind {
+    BlockHeader=0,
+    Block=1,
+    Transaction=2,
+    Output=3,
+    Data=4,
+}
+
+impl RecordKind {
+    /// Creates a `RecordKind` from its byte.
+    fn from_u8(n: u8) -> Result<RecordKind> {
+        match n {
+            0 => Ok(RecordKind::BlockHeader),
+            1 => Ok(RecordKind::Block),
+            2 => Ok(RecordKind::Transaction),
+            3 => Ok(RecordKind::Output),
+            4 => Ok(RecordKind::Data),
+            _ => Err(ErrorKind::InvalidVariant.into()),
+        }
+    }
+}
+
+/// The position of an item in the store file.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct RecordPosition {
+    /// The offset of the item bytes.
+    offset: u64,
+    /// The length of the item bytes.
+    length: u32,
+}
+
+/// A `FileStore` is a `Store` that appends its items to a file.
+#[derive(Debug)]
+pub struct FileStore {
+    /// The path of the store file.
+    path: PathBuf,
+    /// The store file, opened in append mode.
+    file: File,
+    /// The length of the store file.
+    length: u64,
+    /// The positions of the items by kind and id.
+    positions: BTreeMap<(RecordKind, Digest), RecordPosition>,
+    /// The block headers ids by height.
+    heights: BTreeMap<u32, Vec<Digest>>,
+    /// The block headers ids by previous id.
+    prev_ids: BTreeMap<Digest, Vec<Digest>>,
+    /// The block headers ids by block id.
+    block_ids: BTreeMap<Digest, Vec<Digest>>,
+}
+
+impl FileStore {
+    /// Opens a `FileStore`, creating its file if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<FileStore> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+
+        let mut store = FileStore {
+            path: path,
+            file: file,
+            length: 0,
+            positions: BTreeMap::new(),
+            heights: BTreeMap::new(),
+            prev_ids: BTreeMap::new(),
+            block_ids: BTreeMap::new(),
+        };
+
+        let mut offset = 0;
+
+        while buf.len() - offset >= RECORD_HEADER_LENGTH {
+            let kind = RecordKind::from_u8(buf[offset])?;
+            let id = Digest::from_bytes(&buf[offset + 1..offset + 65])?;
+            let length = BigEndian::read_u32(&buf[offset + 65..offset + RECORD_HEADER_LENGTH]);
+
+            let item_offset = offset + RECORD_HEADER_LENGTH;
+
+            if buf.len() - item_offset < length as usize {
+                break;
+            }
+
+            if kind == RecordKind::BlockHeader {
+                let item_buf = &buf[item_offset..item_offset + length as usize];
+                let block_header = BlockHeader::from_bytes(item_buf)?;
+                store.index_block_header(&block_header);
+            }
+
+            store.positions.insert((kind, id), RecordPosition {
+                offset: item_offset as u64,
+                length: length,
+            });
+
+            offset = item_offset + length as usize;
+        }
+
+        // discard an incomplete trailing record
+        if offset != buf.len() {
+            store.file.set_len(offset as u64)?;
+        }
+
+        store.length = offset as u64;
+
+        Ok(store)
+    }
+
+    /// Returns the path of the `FileStore` file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Verifies if an item is in the store.
+    fn has_item(&self, kind: RecordKind, id: Digest) -> bool {
+        self.positions.contains_key(&(kind, id))
+    }
+
+    /// Appends an item to the store file.
+    fn put_item(&mut self, kind: RecordKind, id: Digest, item: &[u8]) -> Result<()> {
+        if self.has_item(kind, id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        let mut buf = Vec::new();
+        buf.write_u8(kind as u8)?;
+        buf.write_all(&id.to_bytes()?)?;
+        buf.write_u32::<BigEndian>(item.len() as u32)?;
+        buf.write_all(item)?;
+
+        // truncate a partially written record
+        if let Err(err) = self.file.write_all(&buf).and_then(|_| self.file.flush()) {
+            self.file.set_len(self.length)?;
+            return Err(err.into());
+        }
+
+        self.positions.insert((kind, id), RecordPosition {
+            offset: self.length + RECORD_HEADER_LENGTH as u64,
+            length: item.len() as u32,
+        });
+
+        self.length += buf.len() as u64;
+
+        Ok(())
+    }
+
+    /// Reads an item from the store file.
+    fn get_item(&self, kind: RecordKind, id: Digest) -> Result<Vec<u8>> {
+        let position = match self.positions.get(&(kind, id)) {
+            Some(position) => *position,
+            None => return Err(ErrorKind::NotFound.into()),
+        };
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(position.offset))?;
+
+        let mut buf = vec![0u8; position.length as usize];
+        file.read_exact(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Adds a `BlockHeader` to the height, previous id and block id indexes.
+    fn index_block_header(&mut self, block_header: &BlockHeader) {
+        self.heights.entry(block_header.height)
+            .or_default()
+            .push(block_header.id);
+        self.prev_ids.entry(block_header.prev_id)
+            .or_default()
+            .push(block_header.id);
+        self.block_ids.entry(block_header.block_id)
+            .or_default()
+            .push(block_header.id);
+    }
+}
+
+impl Store for FileStore {
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<()> {
+        if block_header.id != block_header.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        self.put_item(RecordKind::BlockHeader, block_header.id, &block_header.to_bytes()?)?;
+        self.index_block_header(block_header);
+
+        Ok(())
+    }
+
+    fn get_block_header(&self, id: Digest) -> Result<BlockHeader> {
+        BlockHeader::from_bytes(&self.get_item(RecordKind::BlockHeader, id)?)
+    }
+
+    fn has_block_header(&self, id: Digest) -> Result<bool> {
+        Ok(self.has_item(RecordKind::BlockHeader, id))
+    }
+
+    fn get_block_headers_by_height(&self, height: u32) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+
+        if let Some(ids) = self.heights.get(&height) {
+            for id in ids {
+                block_headers.push(self.get_block_header(*id)?);
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    fn get_block_headers_by_prev_id(&self, prev_id: Digest) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+
+        if let Some(ids) = self.prev_ids.get(&prev_id) {
+            for id in ids {
+                block_headers.push(self.get_block_header(*id)?);
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    fn get_block_headers_by_block_id(&self, block_id: Digest) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+
+        if let Some(ids) = self.block_ids.get(&block_id) {
+            for id in ids {
+                block_headers.push(self.get_block_header(*id)?);
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    fn put_block(&mut self, block: &Block) -> Result<()> {
+        if block.id != block.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        self.put_item(RecordKind::Block, block.id, &block.to_bytes()?)
+    }
+
+    fn get_block(&self, id: Digest) -> Result<Block> {
+        Block::from_bytes(&self.get_item(RecordKind::Block, id)?)
+    }
+
+    fn has_block(&self, id: Digest) -> Result<bool> {
+        Ok(self.has_item(RecordKind::Block, id))
+    }
+
+    fn put_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+        if transaction.id != transaction.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        self.put_item(RecordKind::Transaction, transaction.id, &transaction.to_bytes()?)
+    }
+
+    fn get_transaction(&self, id: Digest) -> Result<Transaction> {
+        Transaction::from_bytes(&self.get_item(RecordKind::Transaction, id)?)
+    }
+
+    fn has_transaction(&self, id: Digest) -> Result<bool> {
+        Ok(self.has_item(RecordKind::Transaction, id))
+    }
+
+    fn put_output(&mut self, output: &Output) -> Result<()> {
+        if output.id != output.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        self.put_item(RecordKind::Output, output.id, &output.to_bytes()?)
+    }
+
+    fn get_output(&self, id: Digest) -> Result<Output> {
+        Output::from_bytes(&self.get_item(RecordKind::Output, id)?)
+    }
+
+    fn has_output(&self, id: Digest) -> Result<bool> {
+        Ok(self.has_item(RecordKind::Output, id))
+    }
+
+    fn put_data(&mut self, data: &Data) -> Result<()> {
+        if data.id != data.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        self.put_item(RecordKind::Data, data.id, &data.to_bytes()?)
+    }
+
+    fn get_data(&self, id: Digest) -> Result<Data> {
+        Data::from_bytes(&self.get_item(RecordKind::Data, id)?)
+    }
+
+    fn has_data(&self, id: Digest) -> Result<bool> {
+        Ok(self.has_item(RecordKind::Data, id))
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `memory` module provides the in-memory store type and methods.
+
+use error::ErrorKind;
+use result::Result;
+use traits::Identify;
+use crypto::Digest;
+use models::output::Output;
+use models::data::Data;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+use store::Store;
+
+use std::collections::BTreeMap;
+
+/// A `MemoryStore` is a `Store` that keeps its items in memory.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct MemoryStore {
+    /// The block headers by id.
+    block_headers: BTreeMap<Digest, BlockHeader>,
+    /// The block headers ids by height.
+    heights: BTreeMap<u32, Vec<Digest>>,
+    /// The block headers ids by previous id.
+    prev_ids: BTreeMap<Digest, Vec<Digest>>,
+    /// The block headers ids by block id.
+    block_ids: BTreeMap<Digest, Vec<Digest>>,
+    /// The blocks by id.
+    blocks: BTreeMap<Digest, Block>,
+    /// The transactions by id.
+    transactions: BTreeMap<Digest, Transaction>,
+    /// The outputs by id.
+    outputs: BTreeMap<Digest, Output>,
+    /// The data by id.
+    data: BTreeMap<Digest, Data>,
+}
+
+impl MemoryStore {
+    /// Creates a new empty `MemoryStore`.
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+}
+
+impl Store for MemoryStore {
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<()> {
+        let id = block_header.id;
+
+        if id != block_header.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        if self.block_headers.contains_key(&id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        self.heights.entry(block_header.height)
+            .or_default()
+            .push(id);
+        self.prev_ids.entry(block_header.prev_id)
+            .or_default()
+            .push(id);
+        self.block_ids.entry(block_header.block_id)
+            .or_default()
+            .push(id);
+        self.block_headers.insert(id, block_header.clone());
+
+        Ok(())
+    }
+
+    fn get_block_header(&self, id: Digest) -> Result<BlockHeader> {
+        match self.block_headers.get(&id) {
+            Some(block_header) => Ok(block_header.clone()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn has_block_header(&self, id: Digest) -> Result<bool> {
+        Ok(self.block_headers.contains_key(&id))
+    }
+
+    fn get_block_headers_by_height(&self, height: u32) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+
+        if let Some(ids) = self.heights.get(&height) {
+            for id in ids {
+                block_headers.push(self.get_block_header(*id)?);
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    fn get_block_headers_by_prev_id(&self, prev_id: Digest) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+
+        if let Some(ids) = self.prev_ids.get(&prev_id) {
+            for id in ids {
+                block_headers.push(self.get_block_header(*id)?);
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    fn get_block_headers_by_block_id(&self, block_id: Digest) -> Result<Vec<BlockHeader>> {
+        let mut block_headers = Vec::new();
+
+        if let Some(ids) = self.block_ids.get(&block_id) {
+            for id in ids {
+                block_headers.push(self.get_block_header(*id)?);
+            }
+        }
+
+        Ok(block_headers)
+    }
+
+    fn put_block(&mut self, block: &Block) -> Result<()> {
+        if block.id != block.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        if self.blocks.contains_key(&block.id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        self.blocks.insert(block.id, block.clone());
+
+        Ok(())
+    }
+
+    fn get_block(&self, id: Digest) -> Result<Block> {
+        match self.blocks.get(&id) {
+            Some(block) => Ok(block.clone()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn has_block(&self, id: Digest) -> Result<bool> {
+        Ok(self.blocks.contains_key(&id))
+    }
+
+    fn put_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+        if transaction.id != transaction.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        if self.transactions.contains_key(&transaction.id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        self.transactions.insert(transaction.id, transaction.clone());
+
+        Ok(())
+    }
+
+    fn get_transaction(&self, id: Digest) -> Result<Transaction> {
+        match self.transactions.get(&id) {
+            Some(transaction) => Ok(transaction.clone()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn has_transaction(&self, id: Digest) -> Result<bool> {
+        Ok(self.transactions.contains_key(&id))
+    }
+
+    fn put_output(&mut self, output: &Output) -> Result<()> {
+        if output.id != output.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        if self.outputs.contains_key(&output.id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        self.outputs.insert(output.id, output.clone());
+
+        Ok(())
+    }
+
+    fn get_output(&self, id: Digest) -> Result<Output> {
+        match self.outputs.get(&id) {
+            Some(output) => Ok(output.clone()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn has_output(&self, id: Digest) -> Result<bool> {
+        Ok(self.outputs.contains_key(&id))
+    }
+
+    fn put_data(&mut self, data: &Data) -> Result<()> {
+        if data.id != data.id()? {
+            return Err(ErrorKind::InvalidDigest.into());
+        }
+
+        if self.data.contains_key(&data.id) {
+            return Err(ErrorKind::AlreadyFound.into());
+        }
+
+        self.data.insert(data.id, data.clone());
+
+        Ok(())
+    }
+
+    fn get_data(&self, id: Digest) -> Result<Data> {
+        match self.data.get(&id) {
+            Some(data) => Ok(data.clone()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn has_data(&self, id: Digest) -> Result<bool> {
+        Ok(self.data.contains_key(&id))
+    }
+}
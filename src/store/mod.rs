@@ -0,0 +1,79 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `store` module provides the storage trait and its backends.
+
+use result::Result;
+use crypto::Digest;
+use models::output::Output;
+use models::data::Data;
+use models::transaction::Transaction;
+use models::block::Block;
+use models::block_header::BlockHeader;
+
+pub mod memory;
+pub mod file;
+
+pub use self::memory::*;
+pub use self::file::*;
+
+/// Trait for the stores of the Yobicash models. Items are keyed by their id.
+pub trait Store {
+    /// Puts a `BlockHeader` in the store.
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<()>;
+
+    /// Gets a `BlockHeader` from the store.
+    fn get_block_header(&self, id: Digest) -> Result<BlockHeader>;
+
+    /// Verifies if a `BlockHeader` is in the store.
+    fn has_block_header(&self, id: Digest) -> Result<bool>;
+
+    /// Gets the `BlockHeader`s with a given height.
+    fn get_block_headers_by_height(&self, height: u32) -> Result<Vec<BlockHeader>>;
+
+    /// Gets the `BlockHeader`s with a given previous id.
+    fn get_block_headers_by_prev_id(&self, prev_id: Digest) -> Result<Vec<BlockHeader>>;
+
+    /// Gets the `BlockHeader`s of a given block id.
+    fn get_block_headers_by_block_id(&self, block_id: Digest) -> Result<Vec<BlockHeader>>;
+
+    /// Puts a `Block` in the store.
+    fn put_block(&mut self, block: &Block) -> Result<()>;
+
+    /// Gets a `Block` from the store.
+    fn get_block(&self, id: Digest) -> Result<Block>;
+
+    /// Verifies if a `Block` is in the store.
+    fn has_block(&self, id: Digest) -> Result<bool>;
+
+    /// Puts a `Transaction` in the store.
+    fn put_transaction(&mut self, transaction: &Transaction) -> Result<()>;
+
+    /// Gets a `Transaction` from the store.
+    fn get_transaction(&self, id: Digest) -> Result<Transaction>;
+
+    /// Verifies if a `Transaction` is in the store.
+    fn has_transaction(&self, id: Digest) -> Result<bool>;
+
+    /// Puts an `Output` in the store.
+    fn put_output(&mut self, output: &Output) -> Result<()>;
+
+    /// Gets an `Output` from the store.
+    fn get_output(&self, id: Digest) -> Result<Output>;
+
+    /// Verifies if an `Output` is in the store.
+    fn has_output(&self, id: Digest) -> Result<bool>;
+
+    /// Puts a `Data` in the store.
+    fn put_data(&mut self, data: &Data) -> Result<()>;
+
+    /// Gets a `Data` from the store.
+    fn get_data(&self, id: Digest) -> Result<Data>;
+
+    /// Verifies if a `Data` is in the store.
+    fn has_data(&self, id: Digest) -> Result<bool>;
+}
@@ -10,6 +10,11 @@
 use serde;
 
 use result::Result;
+use utils::{Encoder, Decoder, DecodeLimits, JsonWriter, JsonReader, JsonSchema};
+use utils::encoding;
+use models::report::ValidationReport;
+
+use std::io::{Read, Write};
 
 /// Trait for types that can be validated.
 pub trait Validate {
@@ -17,6 +22,13 @@ pub trait Validate {
     fn validate(&self) -> Result<()>;
 }
 
+/// Trait for types that can be validated reporting all the violated rules.
+pub trait ValidateReport {
+    /// Validate the object, returning the `ValidationReport` of all the violated
+    /// rules instead of failing at the first one.
+    fn validate_report(&self) -> Result<ValidationReport>;
+}
+
 /// A trait used for identifying univocally an item.
 pub trait Identify<'a> {
     /// Type of the identifier.
@@ -53,6 +65,37 @@ pub trait BinarySerialize: Sized {
     fn from_bytes(b: &[u8]) -> Result<Self>;
 }
 
+/// Trait for types with a canonical binary encoding.
+pub trait Encode: Sized {
+    /// Encodes the object with an `Encoder`.
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()>;
+
+    /// Decodes the object with a `Decoder`.
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Self>;
+
+    /// Writes the versioned encoding of the object to a writer.
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        encoding::encode_to(self, writer)
+    }
+
+    /// Reads a versioned encoded object from a reader, within some `DecodeLimits`.
+    fn read_from<R: Read>(reader: &mut R, limits: DecodeLimits) -> Result<Self> {
+        encoding::decode_from(reader, limits)
+    }
+}
+
+/// Trait for types with a versioned JSON representation.
+pub trait Json: Sized {
+    /// Writes the fields of the object with a `JsonWriter`.
+    fn write_json(&self, writer: &mut JsonWriter) -> Result<()>;
+
+    /// Reads the fields of the object with a `JsonReader`.
+    fn read_json(reader: &mut JsonReader) -> Result<Self>;
+
+    /// Returns the `JsonSchema` of the object.
+    fn json_schema() -> JsonSchema;
+}
+
 /// Trait for object that can be serialized from and to hex.
 pub trait HexSerialize: Sized {
     /// Serialize to a binary.
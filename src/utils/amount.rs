@@ -7,17 +7,19 @@
 
 //! The `amount` module provides the amount type and methods.
 
-use rmp_serde as messagepack;
 use hex;
-use rug::Rational;
+use rug::{Integer, Rational};
 use rug::ops::Pow;
 
-use constants::GENESIS_AMOUNT;
+use constants::{GENESIS_AMOUNT, AMOUNT_UNIT};
 use error::ErrorKind;
 use result::Result;
-use traits::{BinarySerialize, HexSerialize};
+use traits::{BinarySerialize, HexSerialize, Encode};
+use utils::FixedAmount;
+use utils::encoding::{self, Encoder, Decoder};
 
 use std::fmt;
+use std::io::{Read, Write};
 use std::cmp::Eq;
 use std::convert::From;
 use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign};
@@ -61,19 +63,142 @@ impl Amount {
     pub fn from_string(s: &str) -> Result<Amount> {
         Ok(Amount(Rational::from_str_radix(s, 10)?))
     }
+
+    /// Creates an `Amount` from an `f32`, failing if it is not finite.
+    pub fn from_f32(n: f32) -> Result<Amount> {
+        match Rational::from_f32(n) {
+            Some(n) => Ok(Amount(n)),
+            None => Err(ErrorKind::InvalidFormat.into()),
+        }
+    }
+
+    /// Creates an `Amount` from an `f64`, failing if it is not finite.
+    pub fn from_f64(n: f64) -> Result<Amount> {
+        match Rational::from_f64(n) {
+            Some(n) => Ok(Amount(n)),
+            None => Err(ErrorKind::InvalidFormat.into()),
+        }
+    }
+
+    /// Converts the `Amount` to a decimal string without trailing zeros, if its
+    /// decimal representation is finite.
+    pub fn to_decimal(&self) -> Option<String> {
+        let denom = self.0.denom();
+        let mut scale = Integer::from(1);
+        let mut places = 0;
+
+        // the denominator divides a power of ten with less digits than its bits
+        while !scale.is_divisible(denom) {
+            if places > denom.significant_bits() {
+                return None;
+            }
+
+            scale *= 10;
+            places += 1;
+        }
+
+        let value = Integer::from(self.0.numer() * &scale) / denom;
+        let sign = if value < 0 { "-" } else { "" };
+        let mut digits = value.abs().to_string_radix(10);
+
+        if places == 0 {
+            return Some(format!("{}{}", sign, digits));
+        }
+
+        while digits.len() <= places as usize {
+            digits.insert(0, '0');
+        }
+
+        let point = digits.len() - places as usize;
+
+        Some(format!("{}{}.{}", sign, &digits[..point], &digits[point..]))
+    }
+
+    /// Converts the `Amount` to a `FixedAmount`, failing if it is not a whole number
+    /// of base units.
+    pub fn to_fixed(&self) -> Result<FixedAmount> {
+        let units = self.0.clone() * Rational::from(AMOUNT_UNIT);
+        let (numer, denom) = units.into_numer_denom();
+
+        if denom != 1 {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        Amount::units_to_fixed(numer)
+    }
+
+    /// Converts the `Amount` to a `FixedAmount`, rounding down to a whole number of
+    /// base units.
+    pub fn to_fixed_floor(&self) -> Result<FixedAmount> {
+        let units = self.0.clone() * Rational::from(AMOUNT_UNIT);
+        let (numer, denom) = units.into_numer_denom();
+
+        // the denominator is positive, so the truncation of a non negative amount
+        // is its floor
+        Amount::units_to_fixed(numer / denom)
+    }
+
+    /// Converts a number of base units to a `FixedAmount`.
+    fn units_to_fixed(units: Integer) -> Result<FixedAmount> {
+        match units.to_u64() {
+            Some(units) => FixedAmount::from_units(units),
+            None => Err(ErrorKind::OutOfBound.into()),
+        }
+    }
+
+    /// Creates an `Amount` from a decimal string.
+    /// e.g.: `-12.05`
+    pub fn from_decimal(s: &str) -> Result<Amount> {
+        let (sign, unsigned) = if s.starts_with('-') {
+            ("-", &s[1..])
+        } else {
+            ("", s)
+        };
+
+        let mut parts = unsigned.splitn(2, '.');
+        let integer = parts.next().unwrap_or("");
+        let fraction = parts.next().unwrap_or("");
+
+        if integer.is_empty() || (unsigned.contains('.') && fraction.is_empty()) {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        let numer = Integer::from_str_radix(&format!("{}{}{}", sign, integer, fraction), 10)?;
+        let denom = Integer::from(10).pow(fraction.len() as u32);
+
+        Ok(Amount(Rational::from((numer, denom))))
+    }
+}
+
+impl Encode for Amount {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_str(&self.to_string())
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Amount> {
+        let s = decoder.read_str()?;
+        let amount = Amount::from_string(&s)?;
+
+        // only the reduced decimal representation is canonical
+        if amount.to_string() != s {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        Ok(amount)
+    }
 }
 
 impl BinarySerialize for Amount {
     fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::encode::to_vec(self)?;
-
-        Ok(buf)
+        encoding::encode(self)
     }
 
     fn from_bytes(b: &[u8]) -> Result<Amount> {
-        let version = messagepack::decode::from_slice(b)?;
-
-        Ok(version)
+        encoding::decode(b)
     }
 }
 
@@ -117,18 +242,6 @@ impl From<u64> for Amount {
     }
 }
 
-impl From<f32> for Amount {
-    fn from(n: f32) -> Amount {
-        Amount(Rational::from_f32(n).unwrap())
-    }
-}
-
-impl From<f64> for Amount {
-    fn from(n: f64) -> Amount {
-        Amount(Rational::from_f64(n).unwrap())
-    }
-}
-
 impl From<(u32, u32)> for Amount {
     fn from(parts: (u32, u32)) -> Amount {
         Amount(Rational::from(parts))
@@ -0,0 +1,502 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `encoding` module provides the canonical binary encoding of the models, used
+//! both as the payload of the protocol messages and as the preimage of their ids.
+//!
+//! An encoded object starts with the `ENCODING_VERSION` byte, followed by its fields
+//! in declaration order:
+//!
+//! - `u8`, `u32`, `u64` and `i64` values are big-endian, fixed width.
+//! - byte strings, UTF-8 strings and lists are prefixed by their `u32` length, and
+//!   the items of the lists are encoded one after the other.
+//! - `Digest`s are 64 bytes, `ZKPWitness`es and `PublicKey`s 32 bytes and `ZKPProof`s
+//!   96 bytes, without prefix.
+//! - `Amount`s and `Memory`s are strings of their reduced decimal representation,
+//!   as in `21` or `3/2`.
+//! - `NetworkType`s are `u32` values.
+//! - `Vec`s of encodable values are lists.
+//!
+//! The ids of the models are the digests of their encoded fields, without the id and
+//! the version byte. Decoding fails on non-canonical values and trailing bytes.
+//!
+//! Models can be streamed to and from `std::io` writers and readers. The decoding
+//! reads within `DecodeLimits`, and buffers byte strings as they are read, so that a
+//! forged length prefix cannot trigger an unbounded allocation.
+
+use byteorder::{BigEndian, ByteOrder};
+
+use constants::{ENCODING_VERSION, MAX_DECODE_SIZE, MAX_DECODE_LENGTH};
+use error::ErrorKind;
+use result::Result;
+use traits::Encode;
+use crypto::{Digest, Memory, ZKPWitness, ZKPProof, PublicKey};
+use crypto::BinarySerialize as CryptoBinarySerialize;
+
+use std::io::{Read, Write, ErrorKind as IOErrorKind};
+
+/// The size of an encoded `Digest`.
+pub const DIGEST_SIZE: usize = 64;
+
+/// The size of an encoded `ZKPWitness` or `PublicKey`.
+pub const POINT_SIZE: usize = 32;
+
+/// The size of an encoded `ZKPProof`.
+pub const PROOF_SIZE: usize = 96;
+
+/// The size of an encoded `Scalar`.
+pub const SCALAR_SIZE: usize = 32;
+
+/// An `Encoder` writes the canonical encoding of values to a writer.
+pub struct Encoder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Creates a new `Encoder` writing to a writer.
+    pub fn new(writer: W) -> Encoder<W> {
+        Encoder {
+            writer: writer,
+        }
+    }
+
+    /// Returns the writer of the `Encoder`.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes bytes without length prefix.
+    pub fn write_fixed(&mut self, b: &[u8]) -> Result<()> {
+        self.writer.write_all(b)?;
+
+        Ok(())
+    }
+
+    /// Writes a `u8`.
+    pub fn write_u8(&mut self, n: u8) -> Result<()> {
+        self.write_fixed(&[n])
+    }
+
+    /// Writes a `u32`.
+    pub fn write_u32(&mut self, n: u32) -> Result<()> {
+        let mut buf = [0u8; 4];
+        BigEndian::write_u32(&mut buf, n);
+
+        self.write_fixed(&buf)
+    }
+
+    /// Writes a `u64`.
+    pub fn write_u64(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0u8; 8];
+        BigEndian::write_u64(&mut buf, n);
+
+        self.write_fixed(&buf)
+    }
+
+    /// Writes an `i64`.
+    pub fn write_i64(&mut self, n: i64) -> Result<()> {
+        let mut buf = [0u8; 8];
+        BigEndian::write_i64(&mut buf, n);
+
+        self.write_fixed(&buf)
+    }
+
+    /// Writes a length prefix.
+    pub fn write_length(&mut self, length: usize) -> Result<()> {
+        if length > u32::max_value() as usize {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        self.write_u32(length as u32)
+    }
+
+    /// Writes bytes with their length prefix.
+    pub fn write_bytes(&mut self, b: &[u8]) -> Result<()> {
+        self.write_length(b.len())?;
+        self.write_fixed(b)
+    }
+
+    /// Writes a string with its length prefix.
+    pub fn write_str(&mut self, s: &str) -> Result<()> {
+        self.write_bytes(s.as_bytes())
+    }
+
+    /// Writes a `Digest`.
+    pub fn write_digest(&mut self, digest: Digest) -> Result<()> {
+        self.write_fixed(&digest.to_bytes()?)
+    }
+
+    /// Writes a `ZKPWitness`.
+    pub fn write_witness(&mut self, witness: ZKPWitness) -> Result<()> {
+        self.write_fixed(&witness.to_bytes()?)
+    }
+
+    /// Writes a `ZKPProof`.
+    pub fn write_proof(&mut self, proof: ZKPProof) -> Result<()> {
+        self.write_fixed(&proof.to_bytes()?)
+    }
+
+    /// Writes a `PublicKey`.
+    pub fn write_public_key(&mut self, public_key: PublicKey) -> Result<()> {
+        self.write_fixed(&public_key.to_bytes()?)
+    }
+
+    /// Writes a `Memory`.
+    pub fn write_memory(&mut self, memory: &Memory) -> Result<()> {
+        self.write_str(&memory.to_string())
+    }
+
+    /// Writes an encodable value.
+    pub fn write<T: Encode>(&mut self, value: &T) -> Result<()> {
+        value.encode(self)
+    }
+
+    /// Writes a list of encodable values with its length prefix.
+    pub fn write_list<T: Encode>(&mut self, values: &[T]) -> Result<()> {
+        self.write_length(values.len())?;
+
+        for value in values {
+            value.encode(self)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a list of `Digest`s with its length prefix.
+    pub fn write_digests(&mut self, digests: &[Digest]) -> Result<()> {
+        self.write_length(digests.len())?;
+
+        for digest in digests {
+            self.write_digest(*digest)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The `DecodeLimits` bound the resources used by a `Decoder`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct DecodeLimits {
+    /// The maximum number of bytes read.
+    pub max_size: u64,
+    /// The maximum number of items of a list.
+    pub max_length: u32,
+}
+
+impl DecodeLimits {
+    /// Creates new `DecodeLimits`.
+    pub fn new(max_size: u64, max_length: u32) -> DecodeLimits {
+        DecodeLimits {
+            max_size: max_size,
+            max_length: max_length,
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> DecodeLimits {
+        DecodeLimits::new(MAX_DECODE_SIZE, MAX_DECODE_LENGTH)
+    }
+}
+
+/// A `Decoder` reads canonically encoded values from a reader.
+pub struct Decoder<R: Read> {
+    reader: R,
+    limits: DecodeLimits,
+    size: u64,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Creates a new `Decoder` reading from a reader, within the default
+    /// `DecodeLimits`.
+    pub fn new(reader: R) -> Decoder<R> {
+        Decoder::with_limits(reader, DecodeLimits::default())
+    }
+
+    /// Creates a new `Decoder` reading from a reader, within some `DecodeLimits`.
+    pub fn with_limits(reader: R, limits: DecodeLimits) -> Decoder<R> {
+        Decoder {
+            reader: reader,
+            limits: limits,
+            size: 0,
+        }
+    }
+
+    /// Returns the reader of the `Decoder`.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Returns the number of bytes read.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Accounts for a number of bytes to read, failing if they exceed the maximum size.
+    fn consume(&mut self, length: usize) -> Result<()> {
+        let size = self.size + length as u64;
+
+        if size > self.limits.max_size {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        self.size = size;
+
+        Ok(())
+    }
+
+    /// Fills a buffer, failing if the reader ends before.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.consume(buf.len())?;
+
+        match self.reader.read_exact(buf) {
+            Ok(()) => Ok(()),
+            Err(ref e) if e.kind() == IOErrorKind::UnexpectedEof => Err(ErrorKind::InvalidLength.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Reads bytes without length prefix.
+    pub fn read_fixed(&mut self, length: usize) -> Result<Vec<u8>> {
+        self.consume(length)?;
+
+        // the buffer grows with the bytes actually read
+        let mut buf = Vec::new();
+        (&mut self.reader).take(length as u64).read_to_end(&mut buf)?;
+
+        if buf.len() != length {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        Ok(buf)
+    }
+
+    /// Reads a `u8`.
+    pub fn read_u8(&mut self) -> Result<u8> {
+        let mut buf = [0u8; 1];
+        self.read_exact(&mut buf)?;
+
+        Ok(buf[0])
+    }
+
+    /// Reads a `u32`.
+    pub fn read_u32(&mut self) -> Result<u32> {
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf)?;
+
+        Ok(BigEndian::read_u32(&buf))
+    }
+
+    /// Reads a `u64`.
+    pub fn read_u64(&mut self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        self.read_exact(&mut buf)?;
+
+        Ok(BigEndian::read_u64(&buf))
+    }
+
+    /// Reads an `i64`.
+    pub fn read_i64(&mut self) -> Result<i64> {
+        let mut buf = [0u8; 8];
+        self.read_exact(&mut buf)?;
+
+        Ok(BigEndian::read_i64(&buf))
+    }
+
+    /// Reads a length prefix.
+    pub fn read_length(&mut self) -> Result<usize> {
+        Ok(self.read_u32()? as usize)
+    }
+
+    /// Reads bytes with their length prefix.
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>> {
+        let length = self.read_length()?;
+
+        self.read_fixed(length)
+    }
+
+    /// Reads a string with its length prefix.
+    pub fn read_str(&mut self) -> Result<String> {
+        Ok(String::from_utf8(self.read_bytes()?)?)
+    }
+
+    /// Reads a `Digest`.
+    pub fn read_digest(&mut self) -> Result<Digest> {
+        Ok(Digest::from_bytes(&self.read_fixed(DIGEST_SIZE)?)?)
+    }
+
+    /// Reads a `ZKPWitness`.
+    pub fn read_witness(&mut self) -> Result<ZKPWitness> {
+        Ok(ZKPWitness::from_bytes(&self.read_fixed(POINT_SIZE)?)?)
+    }
+
+    /// Reads a `ZKPProof`.
+    pub fn read_proof(&mut self) -> Result<ZKPProof> {
+        Ok(ZKPProof::from_bytes(&self.read_fixed(PROOF_SIZE)?)?)
+    }
+
+    /// Reads a `PublicKey`.
+    pub fn read_public_key(&mut self) -> Result<PublicKey> {
+        Ok(PublicKey::from_bytes(&self.read_fixed(POINT_SIZE)?)?)
+    }
+
+    /// Reads a `Memory`.
+    pub fn read_memory(&mut self) -> Result<Memory> {
+        let s = self.read_str()?;
+        let memory = Memory::from_string(&s)?;
+
+        // only the decimal representation without sign and leading zeros is canonical
+        if memory.to_string() != s {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        Ok(memory)
+    }
+
+    /// Reads an encodable value.
+    pub fn read<T: Encode>(&mut self) -> Result<T> {
+        T::decode(self)
+    }
+
+    /// Reads the length prefix of a list, failing if it exceeds the maximum length.
+    pub fn read_list_length(&mut self) -> Result<usize> {
+        let length = self.read_u32()?;
+
+        if length > self.limits.max_length {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        Ok(length as usize)
+    }
+
+    /// Reads a list of encodable values with its length prefix.
+    pub fn read_list<T: Encode>(&mut self) -> Result<Vec<T>> {
+        let length = self.read_list_length()?;
+        let mut values = Vec::new();
+
+        for _ in 0..length {
+            values.push(T::decode(self)?);
+        }
+
+        Ok(values)
+    }
+
+    /// Reads a list of `Digest`s with its length prefix.
+    pub fn read_digests(&mut self) -> Result<Vec<Digest>> {
+        let length = self.read_list_length()?;
+        let mut digests = Vec::new();
+
+        for _ in 0..length {
+            digests.push(self.read_digest()?);
+        }
+
+        Ok(digests)
+    }
+
+    /// Verifies that the reader has no bytes left.
+    pub fn finish(&mut self) -> Result<()> {
+        let mut buf = [0u8; 1];
+
+        if self.reader.read(&mut buf)? != 0 {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Encodes a value to a writer, prefixed by the `ENCODING_VERSION`.
+pub fn encode_to<T: Encode, W: Write>(value: &T, writer: &mut W) -> Result<()> {
+    let mut encoder = Encoder::new(writer);
+
+    encoder.write_u8(ENCODING_VERSION)?;
+    value.encode(&mut encoder)
+}
+
+/// Decodes a value prefixed by the `ENCODING_VERSION` from a reader, within some
+/// `DecodeLimits`. The reader is left after the value.
+pub fn decode_from<T: Encode, R: Read>(reader: &mut R, limits: DecodeLimits) -> Result<T> {
+    let mut decoder = Decoder::with_limits(reader, limits);
+
+    if decoder.read_u8()? != ENCODING_VERSION {
+        return Err(ErrorKind::NotSupported.into());
+    }
+
+    T::decode(&mut decoder)
+}
+
+/// Encodes a value to a binary, prefixed by the `ENCODING_VERSION`.
+pub fn encode<T: Encode>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    encode_to(value, &mut buf)?;
+
+    Ok(buf)
+}
+
+/// Decodes a value from a binary, prefixed by the `ENCODING_VERSION`, within the
+/// default `DecodeLimits`.
+pub fn decode<T: Encode>(b: &[u8]) -> Result<T> {
+    let mut reader = b;
+    let value = decode_from(&mut reader, DecodeLimits::default())?;
+
+    if !reader.is_empty() {
+        return Err(ErrorKind::InvalidLength.into());
+    }
+
+    Ok(value)
+}
+
+/// Returns the digest of the encoding of a value without version, written by a
+/// function.
+pub fn digest<F>(f: F) -> Result<Digest>
+    where F: FnOnce(&mut Encoder<Vec<u8>>) -> Result<()>
+{
+    let mut encoder = Encoder::new(Vec::new());
+    f(&mut encoder)?;
+
+    Ok(Digest::hash(&encoder.into_inner()))
+}
+
+impl Encode for u32 {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_u32(*self)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<u32> {
+        decoder.read_u32()
+    }
+}
+
+impl Encode for u64 {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_u64(*self)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<u64> {
+        decoder.read_u64()
+    }
+}
+
+impl Encode for Digest {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_digest(*self)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Digest> {
+        decoder.read_digest()
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_list(self)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Vec<T>> {
+        decoder.read_list()
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `fixed_amount` module provides the fixed-point amount type and methods.
+//!
+//! A `FixedAmount` is an integer number of base units, with `AMOUNT_UNIT` base units
+//! to a coin, so that the amounts have exactly `AMOUNT_DECIMALS` decimal places and
+//! every implementation computes the same values. The amounts are never above
+//! `MAX_SUPPLY`, and the arithmetic fails with `OutOfBound` instead of overflowing
+//! or going below zero.
+
+use hex;
+
+use constants::{AMOUNT_DECIMALS, AMOUNT_UNIT, AMOUNT_SYMBOL, MAX_SUPPLY};
+use error::ErrorKind;
+use result::Result;
+use traits::{BinarySerialize, HexSerialize, Encode};
+use utils::Amount;
+use utils::encoding::{self, Encoder, Decoder};
+
+use std::fmt;
+use std::io::{Read, Write};
+
+/// A `FixedAmount` is an amount in base units, the smallest fraction of a coin.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug, Serialize, Deserialize)]
+pub struct FixedAmount(u64);
+
+impl FixedAmount {
+    /// Returns the zero `FixedAmount`.
+    pub fn zero() -> FixedAmount {
+        FixedAmount(0)
+    }
+
+    /// Returns the maximum `FixedAmount`, the maximum supply.
+    pub fn max_value() -> FixedAmount {
+        FixedAmount(MAX_SUPPLY)
+    }
+
+    /// Creates a `FixedAmount` from a number of base units.
+    pub fn from_units(units: u64) -> Result<FixedAmount> {
+        if units > MAX_SUPPLY {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        Ok(FixedAmount(units))
+    }
+
+    /// Creates a `FixedAmount` from a number of coins.
+    pub fn from_coins(coins: u64) -> Result<FixedAmount> {
+        match coins.checked_mul(AMOUNT_UNIT) {
+            Some(units) => FixedAmount::from_units(units),
+            None => Err(ErrorKind::OutOfBound.into()),
+        }
+    }
+
+    /// Returns the number of base units.
+    pub fn units(&self) -> u64 {
+        self.0
+    }
+
+    /// Verifies if the `FixedAmount` is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Adds a `FixedAmount`, failing above the maximum supply.
+    pub fn checked_add(&self, other: FixedAmount) -> Result<FixedAmount> {
+        match self.0.checked_add(other.0) {
+            Some(units) => FixedAmount::from_units(units),
+            None => Err(ErrorKind::OutOfBound.into()),
+        }
+    }
+
+    /// Subtracts a `FixedAmount`, failing below zero.
+    pub fn checked_sub(&self, other: FixedAmount) -> Result<FixedAmount> {
+        match self.0.checked_sub(other.0) {
+            Some(units) => Ok(FixedAmount(units)),
+            None => Err(ErrorKind::OutOfBound.into()),
+        }
+    }
+
+    /// Multiplies by an integer, failing above the maximum supply.
+    pub fn checked_mul(&self, n: u64) -> Result<FixedAmount> {
+        match self.0.checked_mul(n) {
+            Some(units) => FixedAmount::from_units(units),
+            None => Err(ErrorKind::OutOfBound.into()),
+        }
+    }
+
+    /// Divides by an integer, rounding down.
+    pub fn checked_div(&self, n: u64) -> Result<FixedAmount> {
+        match self.0.checked_div(n) {
+            Some(units) => Ok(FixedAmount(units)),
+            None => Err(ErrorKind::OutOfBound.into()),
+        }
+    }
+
+    /// Sums a list of `FixedAmount`s, failing above the maximum supply.
+    pub fn checked_sum(amounts: &[FixedAmount]) -> Result<FixedAmount> {
+        let mut sum = FixedAmount::zero();
+
+        for amount in amounts {
+            sum = sum.checked_add(*amount)?;
+        }
+
+        Ok(sum)
+    }
+
+    /// Converts the `FixedAmount` to a decimal string of coins without trailing zeros.
+    /// e.g.: `1.25`
+    pub fn to_decimal(&self) -> String {
+        let coins = self.0 / AMOUNT_UNIT;
+        let units = self.0 % AMOUNT_UNIT;
+
+        if units == 0 {
+            return coins.to_string();
+        }
+
+        let fraction = format!("{:01$}", units, AMOUNT_DECIMALS as usize);
+
+        format!("{}.{}", coins, fraction.trim_end_matches('0'))
+    }
+
+    /// Creates a `FixedAmount` from a decimal string of coins, optionally followed
+    /// by the coin symbol, with at most `AMOUNT_DECIMALS` decimal places.
+    /// e.g.: `1.25 YBC`
+    pub fn parse(s: &str) -> Result<FixedAmount> {
+        let mut parts = s.split(' ');
+        let decimal = parts.next().unwrap_or("");
+
+        match parts.next() {
+            Some(symbol) if symbol == AMOUNT_SYMBOL => {},
+            Some(_) => return Err(ErrorKind::InvalidFormat.into()),
+            None => {},
+        }
+
+        if parts.next().is_some() {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        let mut parts = decimal.splitn(2, '.');
+        let coins = parts.next().unwrap_or("");
+        let fraction = parts.next();
+
+        if coins.is_empty() || !coins.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+
+        let mut units = 0;
+
+        if let Some(fraction) = fraction {
+            if fraction.is_empty() || fraction.len() > AMOUNT_DECIMALS as usize {
+                return Err(ErrorKind::InvalidFormat.into());
+            }
+
+            if !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return Err(ErrorKind::InvalidFormat.into());
+            }
+
+            let padding = AMOUNT_DECIMALS as usize - fraction.len();
+            units = u64::from_str_radix(&format!("{}{}", fraction, "0".repeat(padding)), 10)?;
+        }
+
+        let coins = match u64::from_str_radix(coins, 10) {
+            Ok(coins) => coins,
+            Err(_) => return Err(ErrorKind::OutOfBound.into()),
+        };
+
+        FixedAmount::from_coins(coins)?.checked_add(FixedAmount(units))
+    }
+}
+
+impl Encode for FixedAmount {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_u64(self.0)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<FixedAmount> {
+        FixedAmount::from_units(decoder.read_u64()?)
+    }
+}
+
+impl BinarySerialize for FixedAmount {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
+    }
+
+    fn from_bytes(b: &[u8]) -> Result<FixedAmount> {
+        encoding::decode(b)
+    }
+}
+
+impl HexSerialize for FixedAmount {
+    fn from_hex(s: &str) -> Result<FixedAmount> {
+        if s.is_empty() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        FixedAmount::from_bytes(&hex::decode(s)?)
+    }
+
+    fn to_hex(&self) -> Result<String> {
+        Ok(hex::encode(&self.to_bytes()?))
+    }
+}
+
+impl fmt::Display for FixedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.to_decimal(), AMOUNT_SYMBOL)
+    }
+}
+
+impl From<FixedAmount> for Amount {
+    fn from(amount: FixedAmount) -> Amount {
+        Amount::from((amount.units(), AMOUNT_UNIT))
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `gcs` module provides the Golomb-coded set type and methods, a compact
+//! probabilistic set used to build block filters.
+//!
+//! The distinct items are hashed with a key to the range `[0, length * GCS_M)` and
+//! sorted, and the differences between consecutive values are Golomb-Rice coded with
+//! `GCS_P` bits of remainder. The false positive rate of a query is about `1 / GCS_M`.
+
+use byteorder::{BigEndian, ByteOrder};
+
+use constants::{GCS_P, GCS_M};
+use error::ErrorKind;
+use result::Result;
+use traits::Encode;
+use utils::encoding::{Encoder, Decoder};
+use crypto::Digest;
+use crypto::BinarySerialize as CryptoBinarySerialize;
+
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+
+/// Hashes an item with a key to the range `[0, range)`.
+fn hash_to_range(key: Digest, item: &[u8], range: u64) -> Result<u64> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&key.to_bytes()?)?;
+    buf.write_all(item)?;
+
+    let digest = Digest::hash(&buf).to_bytes()?;
+    let value = BigEndian::read_u64(&digest[..8]);
+
+    Ok(((u128::from(value) * u128::from(range)) >> 64) as u64)
+}
+
+/// Writes bits to a buffer, from the most significant bit of each byte.
+struct BitWriter {
+    buf: Vec<u8>,
+    length: usize,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            buf: Vec::new(),
+            length: 0,
+        }
+    }
+
+    fn write_bit(&mut self, bit: bool) {
+        if self.length % 8 == 0 {
+            self.buf.push(0);
+        }
+
+        if bit {
+            let last = self.buf.len() - 1;
+            self.buf[last] |= 0x80 >> (self.length % 8);
+        }
+
+        self.length += 1;
+    }
+
+    fn write_bits(&mut self, value: u64, length: u8) {
+        for i in (0..length).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+}
+
+/// Reads the bits written by a `BitWriter`.
+struct BitReader<'a> {
+    buf: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(buf: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            buf: buf,
+            position: 0,
+        }
+    }
+
+    fn read_bit(&mut self) -> Result<bool> {
+        let byte = match self.buf.get(self.position / 8) {
+            Some(byte) => *byte,
+            None => return Err(ErrorKind::InvalidLength.into()),
+        };
+
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+
+        Ok(bit)
+    }
+
+    fn read_bits(&mut self, length: u8) -> Result<u64> {
+        let mut value = 0;
+
+        for _ in 0..length {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+
+        Ok(value)
+    }
+}
+
+/// A `GolombCodedSet` is a compact set of hashed items, queried with false positives
+/// but without false negatives.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Serialize, Deserialize)]
+pub struct GolombCodedSet {
+    /// The number of distinct items.
+    pub length: u32,
+    /// The Golomb-Rice coded differences of the sorted hashed items.
+    pub data: Vec<u8>,
+}
+
+impl GolombCodedSet {
+    /// Creates a new `GolombCodedSet` from a list of items, hashed with a key.
+    pub fn new(key: Digest, items: &[Vec<u8>]) -> Result<GolombCodedSet> {
+        let items: BTreeSet<&Vec<u8>> = items.iter().collect();
+        let range = items.len() as u64 * GCS_M;
+
+        let mut values = Vec::new();
+
+        for item in &items {
+            values.push(hash_to_range(key, item, range)?);
+        }
+
+        values.sort();
+
+        let mut writer = BitWriter::new();
+        let mut last = 0;
+
+        for value in &values {
+            let delta = value - last;
+            last = *value;
+
+            for _ in 0..(delta >> GCS_P) {
+                writer.write_bit(true);
+            }
+
+            writer.write_bit(false);
+            writer.write_bits(delta, GCS_P);
+        }
+
+        let gcs = GolombCodedSet {
+            length: values.len() as u32,
+            data: writer.buf,
+        };
+
+        Ok(gcs)
+    }
+
+    /// Decodes the sorted hashed items.
+    fn values(&self) -> Result<Vec<u64>> {
+        let mut reader = BitReader::new(&self.data);
+        let mut values = Vec::new();
+        let mut last = 0u64;
+
+        for _ in 0..self.length {
+            let mut quotient = 0;
+
+            while reader.read_bit()? {
+                quotient += 1;
+            }
+
+            let remainder = reader.read_bits(GCS_P)?;
+
+            last = match last.checked_add((quotient << GCS_P) | remainder) {
+                Some(value) => value,
+                None => return Err(ErrorKind::OutOfBound.into()),
+            };
+
+            values.push(last);
+        }
+
+        Ok(values)
+    }
+
+    /// Verifies if an item may be in the `GolombCodedSet` built with a key.
+    pub fn contains(&self, key: Digest, item: &[u8]) -> Result<bool> {
+        self.contains_any(key, &[item.to_vec()])
+    }
+
+    /// Verifies if any of a list of items may be in the `GolombCodedSet` built with
+    /// a key.
+    pub fn contains_any(&self, key: Digest, items: &[Vec<u8>]) -> Result<bool> {
+        if self.length == 0 || items.is_empty() {
+            return Ok(false);
+        }
+
+        let range = u64::from(self.length) * GCS_M;
+
+        let mut queries = BTreeSet::new();
+
+        for item in items {
+            queries.insert(hash_to_range(key, item, range)?);
+        }
+
+        let values = self.values()?;
+
+        Ok(values.iter().any(|value| queries.contains(value)))
+    }
+}
+
+impl Encode for GolombCodedSet {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_u32(self.length)?;
+        encoder.write_bytes(&self.data)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<GolombCodedSet> {
+        let gcs = GolombCodedSet {
+            length: decoder.read_u32()?,
+            data: decoder.read_bytes()?,
+        };
+
+        Ok(gcs)
+    }
+}
@@ -0,0 +1,589 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `json` module provides the versioned JSON representation shared by the models.
+//!
+//! A model is a JSON object with a `json_version` field, set to `JSON_VERSION`, and
+//! one field for each of its fields, named the same. Nested models carry their own
+//! `json_version`. The fields are represented as follows:
+//!
+//! - `u32`: a number.
+//! - `u64`: a decimal string, as it can exceed the safe integers of JSON.
+//! - bytes, `Digest`, `ZKPWitness`, `ZKPProof`, `PublicKey`, `Scalar`: lowercase hex.
+//! - `Amount`: a decimal string, e.g. `"12.05"`, or a `"numerator/denominator"`
+//!   fraction if the amount has no finite decimal representation.
+//! - `Memory`: a decimal string.
+//! - `Timestamp`: an object with the `rfc3339` date time string and the `unix`
+//!   seconds, which must agree.
+//! - `Version`: a semver string, e.g. `"1.2.3-alpha"`.
+//! - `NetworkType`: `"mainnet"`, `"testnet"` or `"regtest"`.
+//! - lists: arrays.
+//!
+//! Parsing fails on unknown fields, and the errors have the path of the field which
+//! failed. The types of the fields are described by the `definitions` of the JSON
+//! Schema returned by `models::json_schema`.
+
+use hex;
+use serde_json as json;
+use serde_json::{Map, Value};
+
+use constants::JSON_VERSION;
+use error::ErrorKind;
+use result::{Result, ResultExt};
+use traits::Json;
+use utils::{Amount, Timestamp, Version, NetworkType};
+use utils::encoding::{DIGEST_SIZE, POINT_SIZE, PROOF_SIZE, SCALAR_SIZE};
+use crypto::{Digest, Memory, Scalar, ZKPWitness, ZKPProof, PublicKey};
+use crypto::HexSerialize as CryptoHexSerialize;
+
+/// A `JsonWriter` writes the JSON representation of a model.
+pub struct JsonWriter {
+    object: Map<String, Value>,
+}
+
+impl JsonWriter {
+    /// Creates a new `JsonWriter`, with the `json_version` field.
+    pub fn new() -> JsonWriter {
+        let mut object = Map::new();
+        object.insert("json_version".to_string(), Value::from(JSON_VERSION));
+
+        JsonWriter {
+            object: object,
+        }
+    }
+
+    /// Returns the JSON object written.
+    pub fn into_value(self) -> Value {
+        Value::Object(self.object)
+    }
+
+    /// Writes a field.
+    fn write_value(&mut self, name: &str, value: Value) -> Result<()> {
+        self.object.insert(name.to_string(), value);
+
+        Ok(())
+    }
+
+    /// Writes a cryptographic value in hex.
+    fn write_hex<T: CryptoHexSerialize>(&mut self, name: &str, value: &T) -> Result<()> {
+        let s = value.to_hex()?;
+        self.write_value(name, Value::from(s))
+    }
+
+    /// Writes a `u32`.
+    pub fn write_u32(&mut self, name: &str, n: u32) -> Result<()> {
+        self.write_value(name, Value::from(n))
+    }
+
+    /// Writes a `u64`.
+    pub fn write_u64(&mut self, name: &str, n: u64) -> Result<()> {
+        self.write_value(name, Value::from(n.to_string()))
+    }
+
+    /// Writes bytes.
+    pub fn write_bytes(&mut self, name: &str, b: &[u8]) -> Result<()> {
+        self.write_value(name, Value::from(hex::encode(b)))
+    }
+
+    /// Writes a `Digest`.
+    pub fn write_digest(&mut self, name: &str, digest: Digest) -> Result<()> {
+        self.write_hex(name, &digest)
+    }
+
+    /// Writes a `ZKPWitness`.
+    pub fn write_witness(&mut self, name: &str, witness: ZKPWitness) -> Result<()> {
+        self.write_hex(name, &witness)
+    }
+
+    /// Writes a `ZKPProof`.
+    pub fn write_proof(&mut self, name: &str, proof: ZKPProof) -> Result<()> {
+        self.write_hex(name, &proof)
+    }
+
+    /// Writes a `PublicKey`.
+    pub fn write_public_key(&mut self, name: &str, public_key: PublicKey) -> Result<()> {
+        self.write_hex(name, &public_key)
+    }
+
+    /// Writes a `Scalar`.
+    pub fn write_scalar(&mut self, name: &str, scalar: Scalar) -> Result<()> {
+        self.write_hex(name, &scalar)
+    }
+
+    /// Writes an `Amount`.
+    pub fn write_amount(&mut self, name: &str, amount: &Amount) -> Result<()> {
+        let s = match amount.to_decimal() {
+            Some(s) => s,
+            None => amount.to_string(),
+        };
+
+        self.write_value(name, Value::from(s))
+    }
+
+    /// Writes a `Memory`.
+    pub fn write_memory(&mut self, name: &str, memory: &Memory) -> Result<()> {
+        self.write_value(name, Value::from(memory.to_string()))
+    }
+
+    /// Writes a `Timestamp`.
+    pub fn write_timestamp(&mut self, name: &str, timestamp: Timestamp) -> Result<()> {
+        let value = json!({
+            "rfc3339": timestamp.to_rfc3339()?,
+            "unix": timestamp.to_unix(),
+        });
+
+        self.write_value(name, value)
+    }
+
+    /// Writes a `Version`.
+    pub fn write_version(&mut self, name: &str, version: &Version) -> Result<()> {
+        self.write_value(name, Value::from(version.to_string()))
+    }
+
+    /// Writes a `NetworkType`.
+    pub fn write_network_type(&mut self, name: &str, network_type: NetworkType) -> Result<()> {
+        self.write_value(name, Value::from(network_type.to_string()))
+    }
+
+    /// Writes a model.
+    pub fn write<T: Json>(&mut self, name: &str, value: &T) -> Result<()> {
+        let value = to_value(value)?;
+        self.write_value(name, value)
+    }
+
+    /// Writes a list of models.
+    pub fn write_list<T: Json>(&mut self, name: &str, values: &[T]) -> Result<()> {
+        let mut list = Vec::new();
+
+        for value in values {
+            list.push(to_value(value)?);
+        }
+
+        self.write_value(name, Value::Array(list))
+    }
+
+    /// Writes a list of `Digest`s.
+    pub fn write_digests(&mut self, name: &str, digests: &[Digest]) -> Result<()> {
+        let mut list = Vec::new();
+
+        for digest in digests {
+            list.push(Value::from(digest.to_hex()?));
+        }
+
+        self.write_value(name, Value::Array(list))
+    }
+}
+
+impl Default for JsonWriter {
+    fn default() -> JsonWriter {
+        JsonWriter::new()
+    }
+}
+
+/// Parses a string field value.
+fn parse_str(value: &Value) -> Result<&str> {
+    match value.as_str() {
+        Some(s) => Ok(s),
+        None => Err(ErrorKind::InvalidFormat.into()),
+    }
+}
+
+/// Parses a cryptographic value from its canonical hex.
+fn parse_hex<T: CryptoHexSerialize>(value: &Value) -> Result<T> {
+    let s = parse_str(value)?;
+    let parsed = T::from_hex(s)?;
+
+    if parsed.to_hex()? != s {
+        return Err(ErrorKind::InvalidFormat.into());
+    }
+
+    Ok(parsed)
+}
+
+/// A `JsonReader` reads the JSON representation of a model.
+pub struct JsonReader<'a> {
+    object: &'a Map<String, Value>,
+    names: Vec<&'static str>,
+}
+
+impl<'a> JsonReader<'a> {
+    /// Creates a new `JsonReader` reading from a JSON object, failing if its
+    /// `json_version` is not supported.
+    pub fn new(value: &'a Value) -> Result<JsonReader<'a>> {
+        let object = match value.as_object() {
+            Some(object) => object,
+            None => return Err(ErrorKind::InvalidFormat.into()),
+        };
+
+        let mut reader = JsonReader {
+            object: object,
+            names: Vec::new(),
+        };
+
+        let version = reader.read_u32("json_version")?;
+
+        if version != JSON_VERSION {
+            return Err(ErrorKind::NotSupported.with_field("json_version").with_values(JSON_VERSION, version));
+        }
+
+        Ok(reader)
+    }
+
+    /// Fails if the object has fields which have not been read.
+    pub fn finish(self) -> Result<()> {
+        for name in self.object.keys() {
+            if !self.names.contains(&name.as_str()) {
+                return Err(ErrorKind::InvalidFormat.with_values("a known field", name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a field, marking it as read.
+    fn get(&mut self, name: &'static str) -> Result<&'a Value> {
+        self.names.push(name);
+
+        match self.object.get(name) {
+            Some(value) => Ok(value),
+            None => Err(ErrorKind::NotFound.with_field(name)),
+        }
+    }
+
+    /// Reads a field, with the field path on failure.
+    fn read_value<T, F>(&mut self, name: &'static str, parse: F) -> Result<T>
+        where F: FnOnce(&'a Value) -> Result<T>
+    {
+        let value = self.get(name)?;
+
+        parse(value).with_field(name)
+    }
+
+    /// Reads a list field, with the field path of the item on failure.
+    fn read_items<T, F>(&mut self, name: &'static str, parse: F) -> Result<Vec<T>>
+        where F: Fn(&'a Value) -> Result<T>
+    {
+        let list = match self.get(name)?.as_array() {
+            Some(list) => list,
+            None => return Err(ErrorKind::InvalidFormat.with_field(name)),
+        };
+
+        let mut values = Vec::new();
+
+        for (index, value) in list.iter().enumerate() {
+            values.push(parse(value).map_err(|e| e.with_index(name, index as u32))?);
+        }
+
+        Ok(values)
+    }
+
+    /// Reads a `u32`.
+    pub fn read_u32(&mut self, name: &'static str) -> Result<u32> {
+        self.read_value(name, |value| {
+            match value.as_u64() {
+                Some(n) if n <= u64::from(u32::max_value()) => Ok(n as u32),
+                _ => Err(ErrorKind::InvalidFormat.into()),
+            }
+        })
+    }
+
+    /// Reads a `u64`.
+    pub fn read_u64(&mut self, name: &'static str) -> Result<u64> {
+        self.read_value(name, |value| {
+            let s = parse_str(value)?;
+            let n: u64 = s.parse()?;
+
+            if n.to_string() != s {
+                return Err(ErrorKind::InvalidFormat.into());
+            }
+
+            Ok(n)
+        })
+    }
+
+    /// Reads bytes.
+    pub fn read_bytes(&mut self, name: &'static str) -> Result<Vec<u8>> {
+        self.read_value(name, |value| {
+            let s = parse_str(value)?;
+            let b = hex::decode(s)?;
+
+            if hex::encode(&b) != s {
+                return Err(ErrorKind::InvalidFormat.into());
+            }
+
+            Ok(b)
+        })
+    }
+
+    /// Reads a `Digest`.
+    pub fn read_digest(&mut self, name: &'static str) -> Result<Digest> {
+        self.read_value(name, parse_hex)
+    }
+
+    /// Reads a `ZKPWitness`.
+    pub fn read_witness(&mut self, name: &'static str) -> Result<ZKPWitness> {
+        self.read_value(name, parse_hex)
+    }
+
+    /// Reads a `ZKPProof`.
+    pub fn read_proof(&mut self, name: &'static str) -> Result<ZKPProof> {
+        self.read_value(name, parse_hex)
+    }
+
+    /// Reads a `PublicKey`.
+    pub fn read_public_key(&mut self, name: &'static str) -> Result<PublicKey> {
+        self.read_value(name, parse_hex)
+    }
+
+    /// Reads a `Scalar`.
+    pub fn read_scalar(&mut self, name: &'static str) -> Result<Scalar> {
+        self.read_value(name, parse_hex)
+    }
+
+    /// Reads an `Amount`, rejecting any representation but the one written by
+    /// `write_amount`.
+    pub fn read_amount(&mut self, name: &'static str) -> Result<Amount> {
+        self.read_value(name, |value| {
+            let s = parse_str(value)?;
+
+            let amount = if s.contains('/') {
+                Amount::from_string(s)?
+            } else {
+                Amount::from_decimal(s)?
+            };
+
+            let canonical = match amount.to_decimal() {
+                Some(decimal) => decimal,
+                None => amount.to_string(),
+            };
+
+            if canonical != s {
+                return Err(ErrorKind::InvalidFormat.into());
+            }
+
+            Ok(amount)
+        })
+    }
+
+    /// Reads a `Memory`.
+    pub fn read_memory(&mut self, name: &'static str) -> Result<Memory> {
+        self.read_value(name, |value| {
+            let s = parse_str(value)?;
+            let memory = Memory::from_string(s)?;
+
+            if memory.to_string() != s {
+                return Err(ErrorKind::InvalidFormat.into());
+            }
+
+            Ok(memory)
+        })
+    }
+
+    /// Reads a `Timestamp`.
+    pub fn read_timestamp(&mut self, name: &'static str) -> Result<Timestamp> {
+        self.read_value(name, |value| {
+            let mut reader = JsonReader {
+                object: match value.as_object() {
+                    Some(object) => object,
+                    None => return Err(ErrorKind::InvalidFormat.into()),
+                },
+                names: Vec::new(),
+            };
+
+            let rfc3339 = reader.read_value("rfc3339", |value| Timestamp::parse(parse_str(value)?))?;
+            let unix = reader.read_value("unix", |value| {
+                match value.as_i64() {
+                    Some(secs) => Ok(Timestamp::from_unix(secs)),
+                    None => Err(ErrorKind::InvalidFormat.into()),
+                }
+            })?;
+            reader.finish()?;
+
+            if rfc3339 != unix {
+                return Err(ErrorKind::InvalidTime.with_values(rfc3339, unix));
+            }
+
+            Ok(unix)
+        })
+    }
+
+    /// Reads a `Version`.
+    pub fn read_version(&mut self, name: &'static str) -> Result<Version> {
+        self.read_value(name, |value| Version::parse(parse_str(value)?))
+    }
+
+    /// Reads a `NetworkType`.
+    pub fn read_network_type(&mut self, name: &'static str) -> Result<NetworkType> {
+        self.read_value(name, |value| NetworkType::parse(parse_str(value)?))
+    }
+
+    /// Reads a model.
+    pub fn read<T: Json>(&mut self, name: &'static str) -> Result<T> {
+        self.read_value(name, from_value)
+    }
+
+    /// Reads a list of models.
+    pub fn read_list<T: Json>(&mut self, name: &'static str) -> Result<Vec<T>> {
+        self.read_items(name, from_value)
+    }
+
+    /// Reads a list of `Digest`s.
+    pub fn read_digests(&mut self, name: &'static str) -> Result<Vec<Digest>> {
+        self.read_items(name, parse_hex)
+    }
+}
+
+/// A `JsonSchema` builds the JSON Schema of the representation of a model.
+pub struct JsonSchema {
+    description: String,
+    properties: Map<String, Value>,
+    required: Vec<Value>,
+}
+
+impl JsonSchema {
+    /// Creates a new `JsonSchema` of a model, with the `json_version` field.
+    pub fn new(description: &str) -> JsonSchema {
+        let schema = JsonSchema {
+            description: description.to_string(),
+            properties: Map::new(),
+            required: Vec::new(),
+        };
+
+        schema.field("json_version", json!({ "const": JSON_VERSION }))
+    }
+
+    /// Adds a required field.
+    pub fn field(mut self, name: &str, schema: Value) -> JsonSchema {
+        self.properties.insert(name.to_string(), schema);
+        self.required.push(Value::from(name));
+        self
+    }
+
+    /// Adds a required field of a type of the definitions.
+    pub fn reference(self, name: &str, definition: &str) -> JsonSchema {
+        self.field(name, json!({ "$ref": format!("#/definitions/{}", definition) }))
+    }
+
+    /// Adds a required list field of a type of the definitions.
+    pub fn list(self, name: &str, definition: &str) -> JsonSchema {
+        let items = json!({ "$ref": format!("#/definitions/{}", definition) });
+        self.field(name, json!({ "type": "array", "items": items }))
+    }
+
+    /// Returns the JSON Schema.
+    pub fn into_value(self) -> Value {
+        json!({
+            "description": self.description,
+            "type": "object",
+            "properties": self.properties,
+            "required": self.required,
+            "additionalProperties": false,
+        })
+    }
+}
+
+/// Returns the JSON Schema of a hex string of a number of bytes.
+fn hex_schema(description: &str, size: usize) -> Value {
+    json!({
+        "description": description,
+        "type": "string",
+        "pattern": format!("^[0-9a-f]{{{}}}$", size * 2),
+    })
+}
+
+/// Returns the JSON Schema definitions of the field types.
+pub fn definitions() -> Map<String, Value> {
+    let mut definitions = Map::new();
+
+    definitions.insert("U32".to_string(), json!({
+        "type": "integer",
+        "minimum": 0,
+        "maximum": u32::max_value(),
+    }));
+
+    definitions.insert("U64".to_string(), json!({
+        "description": "A u64 in decimal.",
+        "type": "string",
+        "pattern": "^(0|[1-9][0-9]*)$",
+    }));
+
+    definitions.insert("Bytes".to_string(), json!({
+        "description": "Bytes in lowercase hex.",
+        "type": "string",
+        "pattern": "^([0-9a-f]{2})*$",
+    }));
+
+    definitions.insert("Digest".to_string(), hex_schema("A digest in lowercase hex.", DIGEST_SIZE));
+    definitions.insert("Point".to_string(), hex_schema("A witness or public key in lowercase hex.", POINT_SIZE));
+    definitions.insert("Proof".to_string(), hex_schema("A proof in lowercase hex.", PROOF_SIZE));
+    definitions.insert("Scalar".to_string(), hex_schema("A scalar in lowercase hex.", SCALAR_SIZE));
+
+    definitions.insert("Amount".to_string(), json!({
+        "description": "An amount in decimal, or a fraction if it has no finite decimal representation.",
+        "type": "string",
+        "pattern": "^-?(0|[1-9][0-9]*)(\\.[0-9]*[1-9])?$|^-?[1-9][0-9]*/[1-9][0-9]*$",
+    }));
+
+    definitions.insert("Memory".to_string(), json!({
+        "description": "A memory in decimal.",
+        "type": "string",
+        "pattern": "^(0|[1-9][0-9]*)$",
+    }));
+
+    definitions.insert("Timestamp".to_string(), json!({
+        "description": "A UTC time in rfc3339 format and in seconds since the Epoch time.",
+        "type": "object",
+        "properties": {
+            "rfc3339": { "type": "string", "format": "date-time" },
+            "unix": { "type": "integer" },
+        },
+        "required": ["rfc3339", "unix"],
+        "additionalProperties": false,
+    }));
+
+    definitions.insert("Version".to_string(), json!({
+        "description": "A semver version.",
+        "type": "string",
+        "pattern": "^(0|[1-9][0-9]*)\\.(0|[1-9][0-9]*)\\.(0|[1-9][0-9]*)(-[0-9A-Za-z.-]+)?(\\+[0-9A-Za-z.-]+)?$",
+    }));
+
+    definitions.insert("NetworkType".to_string(), json!({
+        "type": "string",
+        "enum": ["mainnet", "testnet", "regtest"],
+    }));
+
+    definitions
+}
+
+/// Converts a model to its JSON representation.
+pub fn to_value<T: Json>(value: &T) -> Result<Value> {
+    let mut writer = JsonWriter::new();
+    value.write_json(&mut writer)?;
+
+    Ok(writer.into_value())
+}
+
+/// Creates a model from its JSON representation.
+pub fn from_value<T: Json>(value: &Value) -> Result<T> {
+    let mut reader = JsonReader::new(value)?;
+    let value = T::read_json(&mut reader)?;
+    reader.finish()?;
+
+    Ok(value)
+}
+
+/// Converts a model to a JSON string.
+pub fn to_string<T: Json>(value: &T) -> Result<String> {
+    Ok(json::to_string(&to_value(value)?)?)
+}
+
+/// Creates a model from a JSON string.
+pub fn from_str<T: Json>(s: &str) -> Result<T> {
+    let value: Value = json::from_str(s)?;
+
+    from_value(&value)
+}
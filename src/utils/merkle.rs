@@ -0,0 +1,250 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `merkle` module provides the Merkle tree types and methods used to commit to
+//! the transactions of a block.
+//!
+//! Leaves and nodes are hashed with different prefixes, so that a node can never be
+//! passed off as a leaf. When a level has an odd number of nodes, the last one is
+//! promoted to the upper level as is, instead of being paired with itself.
+
+use rmp_serde as messagepack;
+use hex;
+
+use error::ErrorKind;
+use result::Result;
+use traits::{Validate, BinarySerialize, HexSerialize};
+use crypto::Digest;
+use crypto::BinarySerialize as CryptoBinarySerialize;
+
+use std::io::Write;
+
+/// The prefix used when hashing a leaf.
+const LEAF_PREFIX: u8 = 0;
+
+/// The prefix used when hashing a node.
+const NODE_PREFIX: u8 = 1;
+
+/// Hashes a leaf of the tree.
+fn hash_leaf(leaf: Digest) -> Result<Digest> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&[LEAF_PREFIX])?;
+    buf.write_all(&leaf.to_bytes()?)?;
+
+    Ok(Digest::hash(&buf))
+}
+
+/// Hashes two sibling nodes of the tree.
+fn hash_node(left: Digest, right: Digest) -> Result<Digest> {
+    let mut buf = Vec::new();
+
+    buf.write_all(&[NODE_PREFIX])?;
+    buf.write_all(&left.to_bytes()?)?;
+    buf.write_all(&right.to_bytes()?)?;
+
+    Ok(Digest::hash(&buf))
+}
+
+/// Returns the Merkle root of a list of digests.
+pub fn merkle_root(leaves: &[Digest]) -> Result<Digest> {
+    Ok(MerkleTree::new(leaves)?.root())
+}
+
+/// A `MerkleTree` is a binary hash tree built over a list of digests.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MerkleTree {
+    /// The tree levels, from the hashed leaves to the root.
+    levels: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    /// Creates a new `MerkleTree`.
+    pub fn new(leaves: &[Digest]) -> Result<MerkleTree> {
+        if leaves.is_empty() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let mut level = Vec::new();
+        for leaf in leaves {
+            level.push(hash_leaf(*leaf)?);
+        }
+
+        let mut levels = vec![level];
+
+        while levels[levels.len() - 1].len() > 1 {
+            let mut next_level = Vec::new();
+
+            for pair in levels[levels.len() - 1].chunks(2) {
+                if pair.len() == 2 {
+                    next_level.push(hash_node(pair[0], pair[1])?);
+                } else {
+                    next_level.push(pair[0]);
+                }
+            }
+
+            levels.push(next_level);
+        }
+
+        Ok(MerkleTree { levels: levels })
+    }
+
+    /// Returns the number of leaves of the `MerkleTree`.
+    pub fn leaves_length(&self) -> u32 {
+        self.levels[0].len() as u32
+    }
+
+    /// Returns the root of the `MerkleTree`.
+    pub fn root(&self) -> Digest {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Returns the `MerkleProof` of a leaf of the `MerkleTree`.
+    pub fn proof(&self, leaf: Digest) -> Result<MerkleProof> {
+        let leaf_hash = hash_leaf(leaf)?;
+
+        let index_res: Result<usize> = self.levels[0].iter()
+            .position(|l| *l == leaf_hash)
+            .ok_or_else(|| ErrorKind::NotFound.into());
+        let index = index_res?;
+
+        let mut path = Vec::new();
+        let mut position = index;
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+
+            if sibling < level.len() {
+                path.push(level[sibling]);
+            }
+
+            position /= 2;
+        }
+
+        let proof = MerkleProof {
+            leaf: leaf,
+            index: index as u32,
+            leaves_length: self.leaves_length(),
+            path: path,
+        };
+
+        Ok(proof)
+    }
+}
+
+/// A `MerkleProof` is a compact proof that a leaf is included in a `MerkleTree`.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// The proven leaf.
+    pub leaf: Digest,
+    /// The index of the leaf.
+    pub index: u32,
+    /// The number of leaves of the tree.
+    pub leaves_length: u32,
+    /// The sibling nodes from the leaf to the root.
+    pub path: Vec<Digest>,
+}
+
+impl MerkleProof {
+    /// Returns the length of the path expected for the `MerkleProof`.
+    fn expected_path_length(&self) -> usize {
+        let mut length = 0;
+        let mut position = self.index as usize;
+        let mut level_length = self.leaves_length as usize;
+
+        while level_length > 1 {
+            if position ^ 1 < level_length {
+                length += 1;
+            }
+
+            position /= 2;
+            level_length = level_length / 2 + level_length % 2;
+        }
+
+        length
+    }
+
+    /// Computes the root of the `MerkleProof`.
+    pub fn root(&self) -> Result<Digest> {
+        self.validate()?;
+
+        let mut node = hash_leaf(self.leaf)?;
+        let mut path = self.path.iter();
+        let mut position = self.index as usize;
+        let mut level_length = self.leaves_length as usize;
+
+        while level_length > 1 {
+            let sibling = position ^ 1;
+
+            if sibling < level_length {
+                let sibling_node = *path.next().unwrap();
+
+                node = if position & 1 == 0 {
+                    hash_node(node, sibling_node)?
+                } else {
+                    hash_node(sibling_node, node)?
+                };
+            }
+
+            position /= 2;
+            level_length = level_length / 2 + level_length % 2;
+        }
+
+        Ok(node)
+    }
+
+    /// Verifies the `MerkleProof` against a Merkle root.
+    pub fn verify(&self, root: Digest) -> Result<bool> {
+        Ok(self.root()? == root)
+    }
+}
+
+impl Validate for MerkleProof {
+    fn validate(&self) -> Result<()> {
+        if self.leaves_length == 0 {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        if self.index >= self.leaves_length {
+            return Err(ErrorKind::OutOfBound.into());
+        }
+
+        if self.path.len() != self.expected_path_length() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl BinarySerialize for MerkleProof {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        let buf = messagepack::encode::to_vec(self)?;
+
+        Ok(buf)
+    }
+
+    fn from_bytes(b: &[u8]) -> Result<MerkleProof> {
+        let proof = messagepack::decode::from_slice(b)?;
+
+        Ok(proof)
+    }
+}
+
+impl HexSerialize for MerkleProof {
+    fn from_hex(s: &str) -> Result<MerkleProof> {
+        if s.is_empty() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        MerkleProof::from_bytes(&hex::decode(s)?)
+    }
+
+    fn to_hex(&self) -> Result<String> {
+        Ok(hex::encode(&self.to_bytes()?))
+    }
+}
@@ -11,8 +11,18 @@ pub mod version;
 pub mod network_type;
 pub mod timestamp;
 pub mod amount;
+pub mod fixed_amount;
+pub mod merkle;
+pub mod gcs;
+pub mod encoding;
+pub mod json;
 
 pub use self::version::*;
 pub use self::network_type::*;
 pub use self::timestamp::*;
 pub use self::amount::*;
+pub use self::fixed_amount::*;
+pub use self::merkle::*;
+pub use self::gcs::*;
+pub use self::encoding::*;
+pub use self::json::{JsonWriter, JsonReader, JsonSchema};
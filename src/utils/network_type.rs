@@ -14,7 +14,11 @@ use hex;
 use constants::{MAINPORT, TESTPORT, REGTESTPORT};
 use error::ErrorKind;
 use result::Result;
-use traits::{BinarySerialize, HexSerialize};
+use traits::{BinarySerialize, HexSerialize, Encode};
+use utils::encoding::{Encoder, Decoder};
+
+use std::fmt;
+use std::io::{Read, Write};
 
 /// A `NetworkType` is the set of possible types of network in Yobicash.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -33,6 +37,27 @@ impl NetworkType {
             NetworkType::RegTest => REGTESTPORT,
         }
     }
+
+    /// Creates a `NetworkType` from its name.
+    /// e.g.: `testnet`
+    pub fn parse(s: &str) -> Result<NetworkType> {
+        match s {
+            "mainnet" => Ok(NetworkType::MainNet),
+            "testnet" => Ok(NetworkType::TestNet),
+            "regtest" => Ok(NetworkType::RegTest),
+            _ => Err(ErrorKind::UnknownNetwork.into()),
+        }
+    }
+}
+
+impl fmt::Display for NetworkType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NetworkType::MainNet => write!(f, "mainnet"),
+            NetworkType::TestNet => write!(f, "testnet"),
+            NetworkType::RegTest => write!(f, "regtest"),
+        }
+    }
 }
 
 impl Default for NetworkType {
@@ -67,6 +92,16 @@ impl BinarySerialize for NetworkType {
     }
 }
 
+impl Encode for NetworkType {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_fixed(&self.to_bytes()?)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<NetworkType> {
+        NetworkType::from_bytes(&decoder.read_fixed(4)?)
+    }
+}
+
 impl HexSerialize for NetworkType {
     fn from_hex(s: &str) -> Result<NetworkType> {
         if s.is_empty() {
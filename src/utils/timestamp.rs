@@ -7,16 +7,17 @@
 
 //! The `timestamp` module provides the timestamp types and methods.
 
-use chrono::{DateTime, TimeZone, Utc};
-use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use chrono::{DateTime, TimeZone, Utc, SecondsFormat};
 use hex;
 
 use constants::{MIN_DATETIME, MAX_TIMENOISE};
 use error::ErrorKind;
 use result::Result;
-use traits::{BinarySerialize, HexSerialize, Validate};
+use traits::{BinarySerialize, HexSerialize, Validate, Encode};
+use utils::encoding::{self, Encoder, Decoder};
 
 use std::fmt;
+use std::io::{Read, Write};
 
 /// A `Timestamp` is an integer representing the number of seconds elapsed since
 /// the `Epoch` time (1970-01-01:00:00:00.0000...).
@@ -81,6 +82,25 @@ impl Timestamp {
         format!("{:?}", self.0)
     }
 
+    /// Creates a `Timestamp` from the number of seconds since the `Epoch` time.
+    pub fn from_unix(secs: i64) -> Timestamp {
+        Timestamp(secs)
+    }
+
+    /// Returns the number of seconds since the `Epoch` time.
+    pub fn to_unix(&self) -> i64 {
+        self.0
+    }
+
+    /// Converts the `Timestamp` to a UTC date time string in rfc3339 format.
+    /// e.g.: `2018-01-18T00:00:00Z`
+    pub fn to_rfc3339(&self) -> Result<String> {
+        match Utc.timestamp_opt(self.0, 0).single() {
+            Some(dt) => Ok(dt.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            None => Err(ErrorKind::InvalidTime.into()),
+        }
+    }
+
     /// Returns the current time timestamp.
     pub fn now() -> Timestamp {
         Timestamp(Utc::now().timestamp())
@@ -97,23 +117,23 @@ impl Timestamp {
     }
 }
 
-impl BinarySerialize for Timestamp {
-    fn to_bytes(&self) -> Result<Vec<u8>> {
-        let mut buf = Vec::new();
+impl Encode for Timestamp {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_i64(self.0)
+    }
 
-        buf.write_i64::<BigEndian>(self.0)?;
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Timestamp> {
+        Ok(Timestamp(decoder.read_i64()?))
+    }
+}
 
-        Ok(buf)
+impl BinarySerialize for Timestamp {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(self)
     }
 
     fn from_bytes(b: &[u8]) -> Result<Timestamp> {
-        let len = b.len();
-        if len != 8 {
-            return Err(ErrorKind::InvalidLength.into());
-        }
-
-        let _timestamp = BigEndian::read_i64(b);
-        Ok(Timestamp(_timestamp))
+        encoding::decode(b)
     }
 }
 
@@ -7,16 +7,17 @@
 
 //! The `version` module provides the version types and methods.
 
-use rmp_serde as messagepack;
 use hex;
 use regex::{Regex, Captures};
 
 use constants::VERSION;
 use error::ErrorKind;
 use result::Result;
-use traits::{Validate, BinarySerialize, HexSerialize};
+use traits::{Validate, BinarySerialize, HexSerialize, Encode};
+use utils::encoding::{self, Encoder, Decoder};
 
 use std::fmt;
+use std::io::{Read, Write};
 use std::str::FromStr;
 
 /// A `Version` is a semver formatted version.
@@ -133,17 +134,35 @@ impl Version {
     }
 }
 
+impl Encode for Version {
+    fn encode<W: Write>(&self, encoder: &mut Encoder<W>) -> Result<()> {
+        encoder.write_u32(self.major)?;
+        encoder.write_u32(self.minor)?;
+        encoder.write_u32(self.patch)?;
+        encoder.write_str(&self.release)?;
+        encoder.write_str(&self.buildmeta)
+    }
+
+    fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Version> {
+        let version = Version {
+            major: decoder.read_u32()?,
+            minor: decoder.read_u32()?,
+            patch: decoder.read_u32()?,
+            release: decoder.read_str()?,
+            buildmeta: decoder.read_str()?,
+        };
+
+        Ok(version)
+    }
+}
+
 impl BinarySerialize for Version {
     fn to_bytes(&self) -> Result<Vec<u8>> {
-        let buf = messagepack::encode::to_vec(self)?;
-
-        Ok(buf)
+        encoding::encode(self)
     }
 
     fn from_bytes(b: &[u8]) -> Result<Version> {
-        let version = messagepack::decode::from_slice(b)?;
-
-        Ok(version)
+        encoding::decode(b)
     }
 }
 
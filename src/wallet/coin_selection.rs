@@ -0,0 +1,274 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `coin_selection` module provides the types and methods used to select the
+//! coins spent by a transaction.
+
+use error::ErrorKind;
+use result::Result;
+use utils::{Amount, NetworkType};
+use crypto::{Random, ZKPWitness};
+use models::output::Output;
+use models::coin::Coin;
+use models::transaction::Transaction;
+
+/// Maximum number of branches explored by the branch-and-bound strategy.
+const MAX_BRANCH_AND_BOUND_TRIES: u32 = 100_000;
+
+/// A `FeePolicy` prices a transaction by the number of its inputs and outputs.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct FeePolicy {
+    /// The fee paid by every transaction.
+    pub base_fee: Amount,
+    /// The fee paid for every input.
+    pub input_fee: Amount,
+    /// The fee paid for every output.
+    pub output_fee: Amount,
+}
+
+impl FeePolicy {
+    /// Creates a new `FeePolicy`.
+    pub fn new(base_fee: &Amount, input_fee: &Amount, output_fee: &Amount) -> FeePolicy {
+        FeePolicy {
+            base_fee: base_fee.clone(),
+            input_fee: input_fee.clone(),
+            output_fee: output_fee.clone(),
+        }
+    }
+
+    /// Creates a `FeePolicy` paying the same fee regardless of the transaction.
+    pub fn fixed(fee: &Amount) -> FeePolicy {
+        FeePolicy::new(fee, &Amount::zero(), &Amount::zero())
+    }
+
+    /// Returns the fee of a transaction with a given number of inputs and outputs.
+    pub fn fee(&self, inputs_length: u32, outputs_length: u32) -> Amount {
+        &self.base_fee +
+            &(&self.input_fee * &Amount::from(inputs_length)) +
+            &(&self.output_fee * &Amount::from(outputs_length))
+    }
+}
+
+/// The strategies used to select coins.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum SelectionStrategy {
+    /// Spends the largest coins first, minimizing the number of inputs.
+    LargestFirst,
+    /// Searches a set of coins paying the outputs and the fee exactly, without
+    /// change, falling back to `LargestFirst` when there is none.
+    BranchAndBound,
+    /// Spends coins in random order, making the spent coins harder to link.
+    Random,
+}
+
+impl Default for SelectionStrategy {
+    fn default() -> SelectionStrategy {
+        SelectionStrategy::LargestFirst
+    }
+}
+
+/// A `Selection` is the set of coins paying a set of outputs, with the change.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Selection {
+    /// The selected coins.
+    pub coins: Vec<Coin>,
+    /// The change amount, if any.
+    pub change: Option<Amount>,
+    /// The fee paid by the selection.
+    pub fee: Amount,
+}
+
+impl Selection {
+    /// Returns the total amount of the selected coins.
+    pub fn coins_amount(&self) -> Amount {
+        let mut amount = Amount::new();
+
+        for coin in &self.coins {
+            amount += &coin.amount;
+        }
+
+        amount
+    }
+
+    /// Builds the `Transaction` spending the selection to a set of outputs
+    /// and to the change. The change witness is requested only if there is change.
+    pub fn build_transaction<F>(&self,
+                                network_type: NetworkType,
+                                outputs: &[Output],
+                                change_witness: F) -> Result<Transaction>
+        where F: FnOnce() -> Result<ZKPWitness>
+    {
+        let mut outputs = outputs.to_vec();
+
+        if let Some(ref change) = self.change {
+            outputs.push(Output::new(change, change_witness()?)?);
+        }
+
+        Transaction::new(network_type, &self.coins, &outputs, &[], &self.fee)
+    }
+}
+
+/// Selects the coins paying a set of outputs and the fee required by a `FeePolicy`,
+/// with the change amount, if any.
+pub fn select_coins(strategy: SelectionStrategy,
+                    coins: &[Coin],
+                    outputs: &[Output],
+                    policy: &FeePolicy) -> Result<Selection> {
+    if outputs.is_empty() {
+        return Err(ErrorKind::InvalidLength.into());
+    }
+
+    let mut outputs_amount = Amount::new();
+
+    for output in outputs {
+        outputs_amount += &output.amount;
+    }
+
+    let outputs_length = outputs.len() as u32;
+
+    if strategy == SelectionStrategy::BranchAndBound {
+        if let Some(coins) = select_exact(coins, &outputs_amount, outputs_length, policy) {
+            let fee = policy.fee(coins.len() as u32, outputs_length);
+
+            return Ok(Selection {
+                coins: coins,
+                change: None,
+                fee: fee,
+            });
+        }
+    }
+
+    let mut coins = coins.to_vec();
+
+    if strategy == SelectionStrategy::Random {
+        shuffle(&mut coins)?;
+    } else {
+        coins.sort_by(|a, b| b.amount.cmp(&a.amount));
+    }
+
+    let mut selected = Vec::new();
+    let mut selected_amount = Amount::new();
+
+    for coin in coins {
+        if selected_amount >= &outputs_amount + &policy.fee(selected.len() as u32, outputs_length) {
+            break;
+        }
+
+        selected_amount += &coin.amount;
+        selected.push(coin);
+    }
+
+    let inputs_length = selected.len() as u32;
+
+    if selected_amount < &outputs_amount + &policy.fee(inputs_length, outputs_length) {
+        return Err(ErrorKind::InsufficientFunds.into());
+    }
+
+    // the change pays for its own output, when it is not worth it the surplus
+    // goes to the fee
+    let fee_with_change = policy.fee(inputs_length, outputs_length + 1);
+    let spent_with_change = &outputs_amount + &fee_with_change;
+
+    if selected_amount > spent_with_change {
+        let change_amount = &selected_amount - &spent_with_change;
+
+        Ok(Selection {
+            coins: selected,
+            change: Some(change_amount),
+            fee: fee_with_change,
+        })
+    } else {
+        Ok(Selection {
+            coins: selected,
+            change: None,
+            fee: selected_amount - &outputs_amount,
+        })
+    }
+}
+
+/// Searches a set of coins paying exactly a given amount and its fee, using the
+/// effective values of the coins, that is their amounts minus the fee of their input.
+fn select_exact(coins: &[Coin],
+                outputs_amount: &Amount,
+                outputs_length: u32,
+                policy: &FeePolicy) -> Option<Vec<Coin>> {
+    let mut candidates: Vec<(Amount, &Coin)> = coins.iter()
+        .map(|coin| (&coin.amount - &policy.input_fee, coin))
+        .filter(|candidate| candidate.0 > Amount::zero())
+        .collect();
+
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let values: Vec<Amount> = candidates.iter().map(|c| c.0.clone()).collect();
+
+    // the sums of the values from every position to the end
+    let mut remainders = vec![Amount::zero(); values.len() + 1];
+
+    for i in (0..values.len()).rev() {
+        remainders[i] = &remainders[i + 1] + &values[i];
+    }
+
+    let target = outputs_amount + &policy.fee(0, outputs_length);
+
+    let selected = search_exact(&values, &remainders, &target, MAX_BRANCH_AND_BOUND_TRIES)?;
+
+    Some(selected.iter().map(|i| candidates[*i].1.clone()).collect())
+}
+
+/// Explores depth-first the sets of values summing to the target, including
+/// or excluding each value in turn, returning the positions of the values found.
+/// The search keeps its branches on an explicit stack, as its depth grows with
+/// the number of values.
+fn search_exact(values: &[Amount],
+                remainders: &[Amount],
+                target: &Amount,
+                mut tries: u32) -> Option<Vec<usize>> {
+    let mut selected = Vec::new();
+
+    // the branches to explore, as their position, sum and selected length
+    let mut branches = vec![(0, Amount::zero(), 0)];
+
+    while let Some((position, sum, selected_length)) = branches.pop() {
+        if tries == 0 {
+            return None;
+        }
+
+        tries -= 1;
+
+        selected.truncate(selected_length);
+
+        if &sum == target {
+            return Some(selected);
+        }
+
+        if &sum > target ||
+            position == values.len() ||
+            &(&sum + &remainders[position]) < target {
+            continue;
+        }
+
+        // the branch including the value is explored first
+        let included_sum = &sum + &values[position];
+
+        branches.push((position + 1, sum, selected_length));
+        branches.push((position + 1, included_sum, selected_length + 1));
+
+        selected.push(position);
+    }
+
+    None
+}
+
+/// Shuffles a set of coins.
+fn shuffle(coins: &mut [Coin]) -> Result<()> {
+    for i in (1..coins.len()).rev() {
+        let j = Random::u32_range(0..(i as u32 + 1))? as usize;
+        coins.swap(i, j);
+    }
+
+    Ok(())
+}
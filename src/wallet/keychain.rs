@@ -0,0 +1,133 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `keychain` module provides the keychain type and methods, used to derive
+//! and store the keys of a wallet.
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use error::ErrorKind;
+use result::Result;
+use crypto::{Random, Scalar, ZKPWitness};
+use crypto::BinarySerialize as CryptoBinarySerialize;
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// The length of a random keychain seed.
+pub const SEED_LENGTH: u32 = 32;
+
+/// A `WalletKey` is the instance used to redeem the outputs sent to its witness.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct WalletKey {
+    /// The derivation index of the key.
+    pub index: u32,
+    /// The secret instance of the key.
+    pub instance: Scalar,
+    /// The witness of the key.
+    pub witness: ZKPWitness,
+}
+
+/// A `Keychain` derives deterministically the keys of a wallet from a seed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Keychain {
+    /// The seed of the keychain.
+    seed: Vec<u8>,
+    /// The derived keys, by index.
+    keys: Vec<WalletKey>,
+    /// The indexes of the derived keys, by binary witness.
+    indexes: BTreeMap<Vec<u8>, u32>,
+}
+
+impl Keychain {
+    /// Creates a new `Keychain` from a seed.
+    pub fn new(seed: &[u8]) -> Result<Keychain> {
+        if seed.is_empty() {
+            return Err(ErrorKind::InvalidLength.into());
+        }
+
+        let keychain = Keychain {
+            seed: seed.to_vec(),
+            keys: Vec::new(),
+            indexes: BTreeMap::new(),
+        };
+
+        Ok(keychain)
+    }
+
+    /// Creates a new `Keychain` from a random seed.
+    pub fn random() -> Keychain {
+        Keychain::new(&Random::bytes(SEED_LENGTH)).unwrap()
+    }
+
+    /// Restores a `Keychain` from its seed and the number of keys derived from it.
+    pub fn restore(seed: &[u8], length: u32) -> Result<Keychain> {
+        let mut keychain = Keychain::new(seed)?;
+
+        for _ in 0..length {
+            keychain.derive()?;
+        }
+
+        Ok(keychain)
+    }
+
+    /// Returns the seed of the `Keychain`.
+    pub fn seed(&self) -> &[u8] {
+        &self.seed
+    }
+
+    /// Returns the number of derived keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Verifies if no key has been derived.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the derived keys.
+    pub fn keys(&self) -> &[WalletKey] {
+        &self.keys
+    }
+
+    /// Derives the next key of the `Keychain`.
+    pub fn derive(&mut self) -> Result<WalletKey> {
+        let index = self.keys.len() as u32;
+
+        let mut buf = Vec::new();
+        buf.write_all(&self.seed)?;
+        buf.write_u32::<BigEndian>(index)?;
+
+        let instance = Scalar::from_hash(&buf);
+        let witness = ZKPWitness::new(instance)?;
+
+        let key = WalletKey {
+            index: index,
+            instance: instance,
+            witness: witness,
+        };
+
+        self.indexes.insert(witness.to_bytes()?, index);
+        self.keys.push(key);
+
+        Ok(key)
+    }
+
+    /// Returns the key of a witness, if any.
+    pub fn get(&self, witness: ZKPWitness) -> Result<Option<WalletKey>> {
+        let key = self.indexes.get(&witness.to_bytes()?)
+            .map(|index| self.keys[*index as usize]);
+
+        Ok(key)
+    }
+
+    /// Verifies if the `Keychain` owns a witness.
+    pub fn contains(&self, witness: ZKPWitness) -> Result<bool> {
+        Ok(self.indexes.contains_key(&witness.to_bytes()?))
+    }
+}
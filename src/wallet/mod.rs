@@ -0,0 +1,175 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! The `wallet` module provides the wallet type and methods, used to keep track
+//! of the spendable coins of a user and to build transactions from them.
+
+pub mod keychain;
+pub mod coin_selection;
+
+pub use self::keychain::*;
+pub use self::coin_selection::*;
+
+use error::ErrorKind;
+use result::Result;
+use traits::Validate;
+use utils::{Amount, NetworkType};
+use crypto::{Digest, ZKPWitness};
+use models::output::Output;
+use models::coin::Coin;
+use models::transaction::Transaction;
+use models::block_filter::BlockFilter;
+
+use std::collections::BTreeMap;
+
+/// A `Wallet` keeps the coins redeemable with the keys of its `Keychain`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Wallet {
+    /// The network type of the wallet.
+    pub network_type: NetworkType,
+    /// The strategy used to select the coins to spend.
+    pub strategy: SelectionStrategy,
+    /// The keychain of the wallet.
+    keychain: Keychain,
+    /// The spendable coins, by id.
+    coins: BTreeMap<Digest, Coin>,
+}
+
+impl Wallet {
+    /// Creates a new `Wallet` from a `Keychain`.
+    pub fn new(network_type: NetworkType, keychain: Keychain) -> Wallet {
+        Wallet {
+            network_type: network_type,
+            strategy: SelectionStrategy::default(),
+            keychain: keychain,
+            coins: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a new `Wallet` with a random `Keychain`.
+    pub fn random(network_type: NetworkType) -> Wallet {
+        Wallet::new(network_type, Keychain::random())
+    }
+
+    /// Returns the `Keychain` of the `Wallet`.
+    pub fn keychain(&self) -> &Keychain {
+        &self.keychain
+    }
+
+    /// Derives a new witness to receive outputs.
+    pub fn new_witness(&mut self) -> Result<ZKPWitness> {
+        Ok(self.keychain.derive()?.witness)
+    }
+
+    /// Returns the spendable coins.
+    pub fn coins(&self) -> Vec<Coin> {
+        self.coins.values().cloned().collect()
+    }
+
+    /// Returns a spendable coin, if any.
+    pub fn get_coin(&self, id: Digest) -> Option<&Coin> {
+        self.coins.get(&id)
+    }
+
+    /// Verifies if a coin is spendable.
+    pub fn contains_coin(&self, id: Digest) -> bool {
+        self.coins.contains_key(&id)
+    }
+
+    /// Returns the total amount of the spendable coins.
+    pub fn balance(&self) -> Amount {
+        let mut balance = Amount::new();
+
+        for coin in self.coins.values() {
+            balance += &coin.amount;
+        }
+
+        balance
+    }
+
+    /// Adds an `Output` to the spendable coins if it is owned by the `Wallet`.
+    /// Returns if the output was added.
+    pub fn scan_output(&mut self, output: &Output) -> Result<bool> {
+        output.validate()?;
+
+        if self.coins.contains_key(&output.id) {
+            return Ok(false);
+        }
+
+        let key = match self.keychain.get(output.witness)? {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+
+        let coin = Coin::new(output, key.instance)?;
+        self.coins.insert(coin.id, coin);
+
+        Ok(true)
+    }
+
+    /// Updates the spendable coins with a `Transaction`, removing the coins it spends
+    /// and adding the outputs owned by the `Wallet`. Returns the number of added coins.
+    pub fn scan_transaction(&mut self, transaction: &Transaction) -> Result<u32> {
+        transaction.validate()?;
+
+        if transaction.network_type != self.network_type {
+            return Err(ErrorKind::InvalidNetwork.into());
+        }
+
+        for input in &transaction.inputs {
+            self.coins.remove(&input.id);
+        }
+
+        let mut count = 0;
+
+        for output in &transaction.outputs {
+            if self.scan_output(output)? {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Verifies if the block of a `BlockFilter` may pay to a witness of the `Wallet`
+    /// or spend one of its coins, and so has to be downloaded and scanned.
+    pub fn match_filter(&self, block_filter: &BlockFilter) -> Result<bool> {
+        let witnesses: Vec<ZKPWitness> = self.keychain.keys()
+            .iter()
+            .map(|key| key.witness)
+            .collect();
+
+        let ids: Vec<Digest> = self.coins.keys().cloned().collect();
+
+        block_filter.match_any(&witnesses, &ids)
+    }
+
+    /// Builds a `Transaction` sending amounts to a set of witnesses and paying the
+    /// fee required by a `FeePolicy`. The coins are selected with the `Wallet` strategy
+    /// and the change, if any, is sent to a new witness of the `Wallet`. The spent coins are
+    /// removed from the `Wallet` when the `Transaction` is scanned.
+    pub fn build_transaction(&mut self,
+                             targets: &[(ZKPWitness, Amount)],
+                             policy: &FeePolicy) -> Result<Transaction> {
+        let mut outputs = Vec::new();
+
+        for &(witness, ref amount) in targets {
+            if amount <= &Amount::zero() {
+                return Err(ErrorKind::OutOfBound.into());
+            }
+
+            outputs.push(Output::new(amount, witness)?);
+        }
+
+        let selection = select_coins(self.strategy,
+                                     &self.coins(),
+                                     &outputs,
+                                     policy)?;
+
+        selection.build_transaction(self.network_type, &outputs, || self.new_witness())
+    }
+}
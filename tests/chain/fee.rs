@@ -0,0 +1,136 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `fee` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::chain::fee::*;
+use fixtures::new_transaction;
+
+fn new_block(fees: &[f64]) -> (Block, Vec<Transaction>, Amount) {
+    let transactions: Vec<Transaction> = fees.iter().map(|fee| new_transaction(NetworkType::RegTest, *fee, &[])).collect();
+    let block = Block::new(NetworkType::RegTest, &transactions).unwrap();
+
+    let min_fee_rate = transactions.iter()
+        .map(|tx| get_fee_rate(tx).unwrap())
+        .min()
+        .unwrap();
+
+    (block, transactions, min_fee_rate)
+}
+
+#[test]
+fn get_fee_rate_succ() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+
+    let weight = get_transaction_weight(&transaction).unwrap();
+    assert_eq!(weight, transaction.size().unwrap());
+
+    let fee_rate = get_fee_rate(&transaction).unwrap();
+    assert_eq!(fee_rate * &Amount::from(weight), Amount::from_f64(1.0).unwrap())
+}
+
+#[test]
+fn minimum_fee_verify_transaction_succ() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    let minimum_fee = MinimumFee::default();
+
+    assert!(minimum_fee.verify_transaction(&transaction).unwrap())
+}
+
+#[test]
+fn minimum_fee_verify_transaction_fail() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    let minimum_fee = MinimumFee::new(&Amount::from(1u32));
+
+    assert!(!minimum_fee.verify_transaction(&transaction).unwrap())
+}
+
+#[test]
+fn fee_estimator_add_block_succ() {
+    let (block, transactions, _) = new_block(&[1.0, 2.0]);
+    let mut estimator = FeeEstimator::default();
+    estimator.max_blocks = 1;
+
+    let res = estimator.add_block(&block, &transactions);
+    assert!(res.is_ok());
+
+    estimator.add_block(&block, &transactions).unwrap();
+    assert_eq!(estimator.len(), 1)
+}
+
+#[test]
+fn fee_estimator_add_block_fail() {
+    let (block, mut transactions, _) = new_block(&[1.0, 2.0]);
+    let mut estimator = FeeEstimator::default();
+
+    transactions.reverse();
+
+    let res = estimator.add_block(&block, &transactions);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidID);
+
+    let res = estimator.add_block(&block, &transactions[..1]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength)
+}
+
+#[test]
+fn fee_estimator_revert_block_succ() {
+    let (block, transactions, _) = new_block(&[1.0, 2.0]);
+    let mut estimator = FeeEstimator::default();
+    estimator.add_block(&block, &transactions).unwrap();
+
+    let res = estimator.revert_block(&block);
+    assert!(res.is_ok());
+    assert!(estimator.is_empty())
+}
+
+#[test]
+fn fee_estimator_revert_block_fail() {
+    let (block_a, transactions_a, _) = new_block(&[1.0, 2.0]);
+    let (block_b, _, _) = new_block(&[1.0, 2.0]);
+    let mut estimator = FeeEstimator::default();
+    estimator.add_block(&block_a, &transactions_a).unwrap();
+
+    let res = estimator.revert_block(&block_b);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidChain)
+}
+
+#[test]
+fn fee_estimator_estimate_succ() {
+    let (block_a, transactions_a, fee_rate_a) = new_block(&[1.0, 2.0]);
+    let (block_b, transactions_b, fee_rate_b) = new_block(&[3.0, 4.0]);
+    let (block_c, transactions_c, _) = new_block(&[5.0, 6.0]);
+
+    let mut estimator = FeeEstimator::default();
+
+    let estimate = estimator.estimate(1).unwrap();
+    assert_eq!(estimate.high, estimator.minimum_fee.fee_per_byte);
+
+    estimator.add_block(&block_a, &transactions_a).unwrap();
+    estimator.add_block(&block_b, &transactions_b).unwrap();
+    estimator.add_block(&block_c, &transactions_c).unwrap();
+
+    let estimate = estimator.estimate(1).unwrap();
+    assert_eq!(estimate.low, fee_rate_a);
+    assert_eq!(estimate.medium, fee_rate_b);
+    assert_eq!(estimate.high, fee_rate_b);
+
+    let estimate = estimator.estimate(3).unwrap();
+    assert_eq!(estimate.low, fee_rate_a);
+    assert_eq!(estimate.high, fee_rate_a)
+}
+
+#[test]
+fn fee_estimator_estimate_fail() {
+    let estimator = FeeEstimator::default();
+
+    let res = estimator.estimate(0);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound)
+}
@@ -0,0 +1,282 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `header_tree` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::NetworkType;
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::mining::miner::{Miner, MiningJob};
+use libyobicash::chain::header_tree::{HeaderTree, get_work};
+use fixtures::new_block;
+
+use std::thread;
+use std::time::Duration;
+
+// mines a block header after the previous block header timestamp
+fn new_block_header(prev_block_header: &BlockHeader, witness: ZKPWitness) -> BlockHeader {
+    thread::sleep(Duration::from_millis(1_100));
+
+    let (block, transactions) = new_block(NetworkType::RegTest, 1);
+    BlockHeader::new(&block, &transactions, prev_block_header, witness).unwrap()
+}
+
+// mines a block header declaring more difficulty than its retarget
+fn new_invalid_block_header(prev_block_header: &BlockHeader, witness: ZKPWitness) -> BlockHeader {
+    thread::sleep(Duration::from_millis(1_100));
+
+    let (block, transactions) = new_block(NetworkType::RegTest, 1);
+    let mut job = MiningJob::new(&block, &transactions, prev_block_header, witness).unwrap();
+    job.block_header.pow_difficulty += 2;
+    Miner::new(2).unwrap().mine(&mut job, |_| {}).unwrap().unwrap()
+}
+
+#[test]
+fn header_tree_add_genesis_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    let reorg = tree.add_block_header(&genesis).unwrap().unwrap();
+
+    assert_eq!(reorg.attached, vec![genesis.clone()]);
+    assert_eq!(tree.genesis(), Some(&genesis));
+    assert_eq!(tree.tip(), Some(&genesis));
+    assert_eq!(tree.tip_work(), Some(&get_work(&genesis)))
+}
+
+#[test]
+fn header_tree_add_genesis_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let other_genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+
+    let mut tree = HeaderTree::new(NetworkType::TestNet);
+    let res = tree.add_block_header(&genesis);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidNetwork);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+
+    let res = tree.add_block_header(&other_genesis);
+    assert!(res.is_err())
+}
+
+#[test]
+fn header_tree_add_block_header_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header = new_block_header(&genesis, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+
+    let reorg = tree.add_block_header(&block_header).unwrap().unwrap();
+    assert!(reorg.is_extension());
+    assert_eq!(reorg.attached, vec![block_header.clone()]);
+
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.height(), Some(1));
+    assert!(tree.is_best_chain(genesis.id));
+    assert!(tree.is_best_chain(block_header.id))
+}
+
+#[test]
+fn header_tree_add_block_header_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header = new_block_header(&genesis, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+    tree.add_block_header(&block_header).unwrap();
+
+    let res = tree.add_block_header(&block_header);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::AlreadyFound);
+
+    let mut invalid_block_header = new_block_header(&genesis, witness);
+    invalid_block_header.pow_nonce += 1;
+
+    let res = tree.add_block_header(&invalid_block_header);
+    assert!(res.is_err())
+}
+
+#[test]
+fn header_tree_add_orphan_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header_a = new_block_header(&genesis, witness);
+    let block_header_b = new_block_header(&block_header_a, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+
+    let reorg = tree.add_block_header(&block_header_b).unwrap();
+    assert!(reorg.is_none());
+    assert!(tree.is_orphan(block_header_b.id));
+
+    let reorg = tree.add_block_header(&block_header_a).unwrap().unwrap();
+    assert_eq!(reorg.attached, vec![block_header_a, block_header_b.clone()]);
+
+    assert_eq!(tree.orphans_len(), 0);
+    assert_eq!(tree.tip(), Some(&block_header_b))
+}
+
+#[test]
+fn header_tree_add_orphan_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header_a = new_block_header(&genesis, witness);
+    let block_header_b = new_block_header(&block_header_a, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.max_orphans = 0;
+    tree.add_block_header(&genesis).unwrap();
+
+    let res = tree.add_block_header(&block_header_b);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert_eq!(tree.orphans_len(), 0);
+
+    // the descendants of an orphan that does not connect are discarded
+    let block_header_c = new_invalid_block_header(&block_header_a, witness);
+    let block_header_d = new_block_header(&block_header_c, witness);
+
+    tree.max_orphans = 2;
+    tree.add_block_header(&block_header_d).unwrap();
+    tree.add_block_header(&block_header_c).unwrap();
+    assert_eq!(tree.orphans_len(), 2);
+
+    tree.add_block_header(&block_header_a).unwrap();
+    assert_eq!(tree.orphans_len(), 0);
+    assert!(!tree.contains(block_header_c.id));
+    assert!(!tree.contains(block_header_d.id));
+
+    // as are the descendants of a block header that does not connect
+    let block_header_e = new_invalid_block_header(&genesis, witness);
+    let block_header_f = new_block_header(&block_header_e, witness);
+
+    tree.add_block_header(&block_header_f).unwrap();
+
+    let res = tree.add_block_header(&block_header_e);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidChain);
+    assert_eq!(tree.orphans_len(), 0)
+}
+
+#[test]
+fn header_tree_evict_orphan_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header_a = new_block_header(&genesis, witness);
+    let block_header_b = new_block_header(&block_header_a, witness);
+    let block_header_c = new_block_header(&block_header_b, witness);
+    let block_header_d = new_block_header(&block_header_c, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.max_orphans = 2;
+    tree.add_block_header(&genesis).unwrap();
+
+    tree.add_block_header(&block_header_c).unwrap();
+    tree.add_block_header(&block_header_d).unwrap();
+    assert_eq!(tree.orphans_len(), 2);
+
+    // either the last orphan is evicted, or its parent is evicted with it
+    tree.add_block_header(&block_header_b).unwrap();
+    assert!(tree.is_orphan(block_header_b.id));
+    assert!(!tree.is_orphan(block_header_d.id));
+
+    if tree.is_orphan(block_header_c.id) {
+        assert_eq!(tree.orphans_len(), 2);
+    } else {
+        assert_eq!(tree.orphans_len(), 1);
+    }
+}
+
+#[test]
+fn header_tree_reorg_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header_a = new_block_header(&genesis, witness);
+    let block_header_b = new_block_header(&genesis, witness);
+    let block_header_c = new_block_header(&block_header_b, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+    tree.add_block_header(&block_header_a).unwrap();
+
+    let reorg = tree.add_block_header(&block_header_b).unwrap();
+    assert!(reorg.is_none());
+    assert_eq!(tree.tip(), Some(&block_header_a));
+
+    let reorg = tree.add_block_header(&block_header_c).unwrap().unwrap();
+    assert!(!reorg.is_extension());
+    assert_eq!(reorg.detached, vec![block_header_a.clone()]);
+    assert_eq!(reorg.attached, vec![block_header_b, block_header_c.clone()]);
+
+    assert_eq!(tree.tip(), Some(&block_header_c));
+    assert!(!tree.is_best_chain(block_header_a.id))
+}
+
+#[test]
+fn header_tree_reorg_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header_a = new_block_header(&genesis, witness);
+
+    // a fork declaring more difficulty than its retarget, to claim more work
+    let block_header_b = new_invalid_block_header(&genesis, witness);
+    assert!(get_work(&block_header_b) > get_work(&block_header_a));
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+    tree.add_block_header(&block_header_a).unwrap();
+
+    let res = tree.add_block_header(&block_header_b);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidChain);
+    assert_eq!(tree.tip(), Some(&block_header_a));
+    assert!(!tree.contains(block_header_b.id));
+
+    // a fork with a timestamp not above the median of its ancestors
+    let mut block_header_c = new_block_header(&genesis, witness);
+    block_header_c.timestamp = genesis.timestamp;
+    let mut job = MiningJob { block_header: block_header_c, next_nonce: 0 };
+    let block_header_c = Miner::new(2).unwrap().mine(&mut job, |_| {}).unwrap().unwrap();
+
+    let res = tree.add_block_header(&block_header_c);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidTimestamp);
+    assert_eq!(tree.tip(), Some(&block_header_a))
+}
+
+#[test]
+fn header_tree_ancestor_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header_a = new_block_header(&genesis, witness);
+    let block_header_b = new_block_header(&block_header_a, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+    tree.add_block_header(&block_header_a).unwrap();
+    tree.add_block_header(&block_header_b).unwrap();
+
+    assert_eq!(tree.ancestor(block_header_b.id, 0), Some(&genesis));
+    assert_eq!(tree.ancestor(block_header_b.id, 1), Some(&block_header_a))
+}
+
+#[test]
+fn header_tree_ancestor_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header = new_block_header(&genesis, witness);
+
+    let mut tree = HeaderTree::new(NetworkType::RegTest);
+    tree.add_block_header(&genesis).unwrap();
+    tree.add_block_header(&block_header).unwrap();
+
+    assert!(tree.ancestor(genesis.id, 1).is_none());
+    assert!(tree.ancestor(block_header.id, 2).is_none())
+}
@@ -0,0 +1,143 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `light_client` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::NetworkType;
+use libyobicash::crypto::{Digest, Scalar, ZKPWitness};
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::chain::light_client::LightClient;
+use fixtures::new_transaction;
+
+use std::thread;
+use std::time::Duration;
+
+// mines a block header with the block of some transactions, after the previous
+// block header timestamp
+fn new_block_header(transactions: &[Transaction], prev_block_header: &BlockHeader, witness: ZKPWitness) -> (BlockHeader, Block) {
+    thread::sleep(Duration::from_millis(1_100));
+
+    let block = Block::new(NetworkType::RegTest, transactions).unwrap();
+    let block_header = BlockHeader::new(&block, transactions, prev_block_header, witness).unwrap();
+
+    (block_header, block)
+}
+
+#[test]
+fn light_client_add_block_header_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let (block_header_a, _) = new_block_header(&[new_transaction(NetworkType::RegTest, 1.0, &[])], &genesis, witness);
+    let (block_header_b, _) = new_block_header(&[new_transaction(NetworkType::RegTest, 1.0, &[])], &block_header_a, witness);
+
+    let mut light_client = LightClient::new(&genesis).unwrap();
+    assert_eq!(light_client.height(), 0);
+
+    let reorg = light_client.add_block_header(&block_header_a).unwrap().unwrap();
+    assert!(reorg.is_extension());
+
+    light_client.add_block_header(&block_header_b).unwrap();
+    assert_eq!(light_client.tip(), &block_header_b);
+
+    assert_eq!(light_client.confirmations(genesis.id), 3);
+    assert_eq!(light_client.confirmations(block_header_a.id), 2);
+    assert_eq!(light_client.confirmations(block_header_b.id), 1);
+    assert_eq!(light_client.confirmations(Digest::hash(b"unknown")), 0)
+}
+
+#[test]
+fn light_client_add_block_header_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let (block_header_a, _) = new_block_header(&[new_transaction(NetworkType::RegTest, 1.0, &[])], &genesis, witness);
+    let (block_header_b, _) = new_block_header(&[new_transaction(NetworkType::RegTest, 1.0, &[])], &block_header_a, witness);
+
+    let mut light_client = LightClient::new(&genesis).unwrap();
+
+    // the previous block header is unknown
+    let res = light_client.add_block_header(&block_header_b);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound);
+
+    // the PoW does not match the block header
+    let mut invalid_pow = block_header_a.clone();
+    invalid_pow.pow_nonce += 1;
+    let res = light_client.add_block_header(&invalid_pow);
+    assert!(res.is_err());
+
+    light_client.add_block_header(&block_header_a).unwrap();
+
+    let res = light_client.add_block_header(&block_header_a);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::AlreadyFound);
+    assert_eq!(light_client.height(), 1)
+}
+
+#[test]
+fn light_client_verify_transaction_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let transactions = vec![new_transaction(NetworkType::RegTest, 1.0, &[]), new_transaction(NetworkType::RegTest, 1.0, &[]), new_transaction(NetworkType::RegTest, 1.0, &[])];
+    let (block_header_a, block) = new_block_header(&transactions, &genesis, witness);
+    let (block_header_b, _) = new_block_header(&[new_transaction(NetworkType::RegTest, 1.0, &[])], &block_header_a, witness);
+
+    let mut light_client = LightClient::new(&genesis).unwrap();
+    light_client.add_block_header(&block_header_a).unwrap();
+
+    let transaction = &transactions[1];
+    let proof = block.transaction_proof(transaction.id).unwrap();
+
+    let res = light_client.verify_transaction(block_header_a.id, transaction, &proof);
+    assert_eq!(res.unwrap(), 1);
+
+    light_client.add_block_header(&block_header_b).unwrap();
+
+    let res = light_client.verify_transaction(block_header_a.id, transaction, &proof);
+    assert_eq!(res.unwrap(), 2);
+
+    let output = &transaction.outputs[0];
+    let res = light_client.verify_output(block_header_a.id, output, transaction, &proof);
+    assert_eq!(res.unwrap(), 2);
+
+    let res = light_client.verify_coinbase_output(block_header_b.id, &block_header_b.coinbase_output);
+    assert_eq!(res.unwrap(), 1)
+}
+
+#[test]
+fn light_client_verify_transaction_fail() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let transactions = vec![new_transaction(NetworkType::RegTest, 1.0, &[]), new_transaction(NetworkType::RegTest, 1.0, &[])];
+    let (block_header, block) = new_block_header(&transactions, &genesis, witness);
+
+    let mut light_client = LightClient::new(&genesis).unwrap();
+
+    let transaction = &transactions[0];
+    let proof = block.transaction_proof(transaction.id).unwrap();
+
+    // the block header is unknown
+    let res = light_client.verify_transaction(block_header.id, transaction, &proof);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound);
+
+    light_client.add_block_header(&block_header).unwrap();
+
+    // the proof is of another transaction
+    let res = light_client.verify_transaction(block_header.id, &transactions[1], &proof);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidTransaction);
+
+    // the proof is against another block
+    let res = light_client.verify_transaction(genesis.id, transaction, &proof);
+    assert!(res.is_err());
+
+    let other_transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    let res = light_client.verify_output(block_header.id, &other_transaction.outputs[0], transaction, &proof);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound);
+
+    let res = light_client.verify_coinbase_output(block_header.id, &transaction.outputs[0]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound)
+}
@@ -0,0 +1,224 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `mempool` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::{NetworkType, Amount, Timestamp};
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::output::Output;
+use libyobicash::models::coin::Coin;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::chain::state::ChainState;
+use libyobicash::chain::fee::{MinimumFee, get_transaction_weight};
+use libyobicash::chain::mempool::Mempool;
+use fixtures::{regtest_genesis, spend};
+
+/// Returns a chain state with four spendable outputs of 5 coins.
+fn chain_state() -> (ChainState, Vec<(Output, Scalar)>) {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let coin = Coin::new(&genesis_tx.outputs[0], instance).unwrap();
+
+    let mut outputs = Vec::new();
+    let mut instances = Vec::new();
+
+    for _ in 0..4 {
+        let instance = Scalar::random();
+        let witness = ZKPWitness::new(instance).unwrap();
+        outputs.push(Output::new(&Amount::from(5u32), witness).unwrap());
+        instances.push(instance);
+    }
+
+    let fee = Amount::from(1u32);
+    let tx = Transaction::new(NetworkType::RegTest, &[coin], &outputs, &[], &fee).unwrap();
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = BlockHeader::new(&block, &[tx.clone()], &genesis_header, witness).unwrap();
+    state.apply_block(&block_header, &block, &[tx]).unwrap();
+
+    (state, outputs.into_iter().zip(instances.into_iter()).collect())
+}
+
+#[test]
+fn mempool_add_transaction_succ() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx = spend(&outputs[0].0, outputs[0].1, 1.0);
+
+    let res = mempool.add_transaction(&tx, &state);
+    assert!(res.is_ok());
+
+    assert!(mempool.contains(tx.id));
+    assert_eq!(mempool.spender(outputs[0].0.id), Some(tx.id));
+    assert_eq!(mempool.weight(), get_transaction_weight(&tx).unwrap())
+}
+
+#[test]
+fn mempool_add_transaction_fail() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx_a = spend(&outputs[0].0, outputs[0].1, 1.0);
+    let tx_b = spend(&outputs[0].0, outputs[0].1, 2.0);
+    mempool.add_transaction(&tx_a, &state).unwrap();
+
+    let res = mempool.add_transaction(&tx_a, &state);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::AlreadyFound);
+
+    let res = mempool.add_transaction(&tx_b, &state);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::DoubleSpend);
+
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let output = Output::new(&Amount::from(5u32), witness).unwrap();
+    let tx_c = spend(&output, instance, 1.0);
+
+    let res = mempool.add_transaction(&tx_c, &state);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound)
+}
+
+#[test]
+fn mempool_add_transaction_fee_fail() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+    mempool.minimum_fee = MinimumFee::new(&Amount::from(1u32));
+
+    let tx = spend(&outputs[0].0, outputs[0].1, 1.0);
+
+    let res = mempool.add_transaction(&tx, &state);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidFee)
+}
+
+#[test]
+fn mempool_evict_succ() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx_a = spend(&outputs[0].0, outputs[0].1, 1.0);
+    let tx_b = spend(&outputs[1].0, outputs[1].1, 2.0);
+    let tx_c = spend(&outputs[2].0, outputs[2].1, 0.5);
+
+    // room for the heaviest of the transactions, but not for two of them
+    mempool.max_weight = [&tx_a, &tx_b, &tx_c].iter()
+        .map(|tx| get_transaction_weight(tx).unwrap())
+        .max()
+        .unwrap() + 10;
+    mempool.add_transaction(&tx_a, &state).unwrap();
+
+    let res = mempool.add_transaction(&tx_b, &state);
+    assert!(res.is_ok());
+    assert!(!mempool.contains(tx_a.id));
+    assert!(mempool.contains(tx_b.id));
+
+    let res = mempool.add_transaction(&tx_c, &state);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert!(!mempool.contains(tx_c.id));
+    assert_eq!(mempool.len(), 1)
+}
+
+#[test]
+fn mempool_evict_fail() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx_a = spend(&outputs[0].0, outputs[0].1, 1.0);
+    let tx_b = spend(&outputs[1].0, outputs[1].1, 2.0);
+    let tx_c = spend(&outputs[2].0, outputs[2].1, 1.5);
+
+    mempool.add_transaction(&tx_a, &state).unwrap();
+    mempool.add_transaction(&tx_b, &state).unwrap();
+
+    // tx_c fits only by evicting tx_b too, which has a higher fee rate
+    mempool.max_weight = get_transaction_weight(&tx_c).unwrap() + 10;
+
+    let res = mempool.add_transaction(&tx_c, &state);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert!(mempool.contains(tx_a.id));
+    assert!(mempool.contains(tx_b.id));
+    assert!(!mempool.contains(tx_c.id));
+    assert_eq!(mempool.len(), 2)
+}
+
+#[test]
+fn mempool_expire_succ() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx = spend(&outputs[0].0, outputs[0].1, 1.0);
+    mempool.add_transaction(&tx, &state).unwrap();
+
+    let expired = mempool.expire(Timestamp::now());
+    assert!(expired.is_empty());
+
+    mempool.expiry_time = 0;
+
+    let expired = mempool.expire(Timestamp::now());
+    assert_eq!(expired, vec![tx]);
+    assert!(mempool.is_empty());
+    assert!(mempool.spender(outputs[0].0.id).is_none())
+}
+
+#[test]
+fn mempool_remove_block_transactions_succ() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx_a = spend(&outputs[0].0, outputs[0].1, 1.0);
+    let tx_b = spend(&outputs[1].0, outputs[1].1, 1.0);
+    let tx_c = spend(&outputs[1].0, outputs[1].1, 2.0);
+    mempool.add_transaction(&tx_a, &state).unwrap();
+    mempool.add_transaction(&tx_b, &state).unwrap();
+
+    let conflicts = mempool.remove_block_transactions(&[tx_a, tx_c]);
+    assert_eq!(conflicts, vec![tx_b]);
+    assert!(mempool.is_empty());
+    assert_eq!(mempool.weight(), 0)
+}
+
+#[test]
+fn mempool_block_template_succ() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx_a = spend(&outputs[0].0, outputs[0].1, 1.0);
+    let tx_b = spend(&outputs[1].0, outputs[1].1, 3.0);
+    let tx_c = spend(&outputs[2].0, outputs[2].1, 2.0);
+    mempool.add_transaction(&tx_a, &state).unwrap();
+    mempool.add_transaction(&tx_b, &state).unwrap();
+    mempool.add_transaction(&tx_c, &state).unwrap();
+
+    let template = mempool.block_template(u32::max_value());
+    assert_eq!(template, vec![tx_b.clone(), tx_c.clone(), tx_a]);
+
+    let max_size = tx_b.size().unwrap() + tx_c.size().unwrap() + 10;
+    let template = mempool.block_template(max_size);
+    assert_eq!(template, vec![tx_b, tx_c]);
+
+    let res = Block::new(NetworkType::RegTest, &template);
+    assert!(res.is_ok())
+}
+
+#[test]
+fn mempool_block_template_fail() {
+    let (state, outputs) = chain_state();
+    let mut mempool = Mempool::new(NetworkType::RegTest);
+
+    let tx = spend(&outputs[0].0, outputs[0].1, 1.0);
+    mempool.add_transaction(&tx, &state).unwrap();
+
+    let template = mempool.block_template(tx.size().unwrap() - 1);
+    assert!(template.is_empty())
+}
@@ -0,0 +1,14 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `chain` module tests.
+
+mod state;
+mod header_tree;
+mod fee;
+mod mempool;
+mod light_client;
@@ -0,0 +1,320 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `state` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::Identify;
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::output::Output;
+use libyobicash::models::coin::Coin;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::models::monetary_policy::get_max_supply_height;
+use libyobicash::chain::state::{ChainState, UnspentOutput};
+use fixtures::{regtest_genesis, spend};
+
+#[test]
+fn unspent_output_is_mature_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let output = Output::new(&Amount::from(1u32), witness).unwrap();
+
+    let unspent = UnspentOutput {
+        output: output,
+        height: 10,
+        is_coinbase: true,
+    };
+
+    assert!(unspent.is_mature(20, 10));
+    assert!(!unspent.is_mature(19, 10));
+    assert!(!unspent.is_mature(u32::max_value() - 1, u32::max_value()))
+}
+
+#[test]
+fn chain_state_new_succ() {
+    let state = ChainState::new(NetworkType::RegTest);
+
+    assert!(state.is_empty());
+    assert!(state.tip().is_none())
+}
+
+#[test]
+fn chain_state_apply_genesis_succ() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    assert_eq!(state.len(), 1);
+    assert_eq!(state.height(), Some(0));
+    assert!(state.contains(genesis_tx.outputs[0].id))
+}
+
+#[test]
+fn chain_state_apply_genesis_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::TestNet);
+
+    let res = state.apply_block(&genesis_header, &genesis_block, &[genesis_tx]);
+    assert!(res.is_err())
+}
+
+#[test]
+fn chain_state_apply_block_succ() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let tx = spend(&genesis_tx.outputs[0], instance, 1.0);
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = BlockHeader::new(&block, &[tx.clone()], &genesis_header, witness).unwrap();
+
+    let res = state.apply_block(&block_header, &block, &[tx.clone()]);
+    assert!(res.is_ok());
+
+    assert!(!state.contains(genesis_tx.outputs[0].id));
+    assert!(state.contains(tx.outputs[0].id));
+    assert!(state.get(block_header.coinbase_output.id).unwrap().is_coinbase);
+    assert_eq!(state.height(), Some(1))
+}
+
+#[test]
+fn chain_state_apply_block_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let other_instance = Scalar::random();
+    let other_witness = ZKPWitness::new(other_instance).unwrap();
+    let other_output = Output::new(&Amount::from_f64(10.0).unwrap(), other_witness).unwrap();
+
+    let tx = spend(&other_output, other_instance, 1.0);
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = BlockHeader::new(&block, &[tx.clone()], &genesis_header, witness).unwrap();
+
+    let res = state.apply_block(&block_header, &block, &[tx]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound);
+
+    assert_eq!(state.len(), 1);
+    assert_eq!(state.height(), Some(0))
+}
+
+#[test]
+fn chain_state_verify_transaction_inflation_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    // the genesis output is spent to an output of all but 1.0, declaring no outputs
+    // amount and all of it as fee
+    let mut tx = spend(&genesis_tx.outputs[0], instance, 1.0);
+    tx.outputs_amount = Amount::zero();
+    tx.fee = genesis_tx.outputs[0].amount.clone();
+    tx.id = tx.id().unwrap();
+
+    let err = state.verify_transaction(&tx, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBalance);
+    assert_eq!(err.details().path(), "outputs_amount");
+
+    // no block can include it, so the coinbase cannot claim its fee
+    let res = Block::new(NetworkType::RegTest, &[tx]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidBalance);
+    assert_eq!(state.len(), 1)
+}
+
+#[test]
+fn chain_state_apply_block_double_spend_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let tx_a = spend(&genesis_tx.outputs[0], instance, 1.0);
+    let tx_b = spend(&genesis_tx.outputs[0], instance, 2.0);
+    let txs = vec![tx_a, tx_b];
+    let block = Block::new(NetworkType::RegTest, &txs).unwrap();
+    let block_header = BlockHeader::new(&block, &txs, &genesis_header, witness).unwrap();
+
+    let res = state.apply_block(&block_header, &block, &txs);
+    let err = res.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DoubleSpend);
+    assert_eq!(err.details().path(), "transactions[1].inputs[0]");
+
+    assert!(state.contains(genesis_tx.outputs[0].id));
+    assert_eq!(state.len(), 1)
+}
+
+#[test]
+fn chain_state_apply_block_immature_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let tx = spend(&genesis_tx.outputs[0], instance, 1.0);
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = BlockHeader::new(&block, &[tx.clone()], &genesis_header, witness).unwrap();
+
+    let res = state.apply_block(&block_header, &block, &[tx]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::ImmatureCoinbase);
+
+    assert!(state.contains(genesis_tx.outputs[0].id))
+}
+
+#[test]
+fn chain_state_apply_block_same_coinbase_succ() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let max_supply_height = get_max_supply_height().unwrap();
+    let mut block_header = genesis_header;
+    let mut output = genesis_tx.outputs[0].clone();
+    let mut output_instance = instance;
+
+    // after the maximum supply the coinbase pays only the fees, so two blocks
+    // paying the same fees to the same witness have the same coinbase output
+    while block_header.height < max_supply_height + 2 {
+        // the first fee keeps the outputs from having the genesis amount
+        let fee = if block_header.height > 0 && block_header.height < max_supply_height { 0.0 } else { 1.0 };
+
+        let coin = Coin::new(&output, output_instance).unwrap();
+        let fee = Amount::from_f64(fee).unwrap();
+        output_instance = Scalar::random();
+        output = Output::new(&(&output.amount - &fee), ZKPWitness::new(output_instance).unwrap()).unwrap();
+
+        let tx = Transaction::new(NetworkType::RegTest, &[coin], &[output.clone()], &[], &fee).unwrap();
+        let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+        block_header = BlockHeader::new(&block, &[tx.clone()], &block_header, witness).unwrap();
+
+        state.apply_block(&block_header, &block, &[tx]).unwrap();
+    }
+
+    let coinbase_output = block_header.coinbase_output.clone();
+    assert_eq!(coinbase_output.amount, Amount::one());
+    assert_eq!(state.unspent_outputs().iter().filter(|unspent| unspent.output == coinbase_output).count(), 2);
+
+    // the identical coinbase outputs are spent one at a time
+    for remaining in &[1, 0] {
+        let tx = spend(&coinbase_output, instance, 0.5);
+        let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+        block_header = BlockHeader::new(&block, &[tx.clone()], &block_header, witness).unwrap();
+        state.apply_block(&block_header, &block, &[tx]).unwrap();
+
+        assert_eq!(state.unspent_outputs().iter().filter(|unspent| unspent.output == coinbase_output).count(), *remaining);
+    }
+
+    // reverting the block restores the spent coinbase output
+    state.revert_block(&block_header).unwrap();
+    assert!(state.contains(coinbase_output.id))
+}
+
+#[test]
+fn chain_state_revert_block_succ() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let genesis_state = state.clone();
+
+    let tx = spend(&genesis_tx.outputs[0], instance, 1.0);
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = BlockHeader::new(&block, &[tx.clone()], &genesis_header, witness).unwrap();
+    state.apply_block(&block_header, &block, &[tx]).unwrap();
+
+    let res = state.revert_block(&block_header);
+    assert!(res.is_ok());
+
+    assert_eq!(state, genesis_state)
+}
+
+#[test]
+fn chain_state_revert_block_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let tx = spend(&genesis_tx.outputs[0], instance, 1.0);
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = BlockHeader::new(&block, &[tx.clone()], &genesis_header, witness).unwrap();
+    state.apply_block(&block_header, &block, &[tx]).unwrap();
+
+    let res = state.revert_block(&genesis_header);
+    assert!(res.is_err())
+}
+
+#[test]
+fn chain_state_verify_transaction_succ() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let tx = spend(&genesis_tx.outputs[0], instance, 1.0);
+
+    let res = state.verify_transaction(&tx, 1);
+    assert!(res.is_ok())
+}
+
+#[test]
+fn chain_state_verify_transaction_fail() {
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let (genesis_header, genesis_block, genesis_tx) = regtest_genesis(witness);
+
+    let mut state = ChainState::new(NetworkType::RegTest);
+    state.maturity_time = 0;
+    state.apply_block(&genesis_header, &genesis_block, &[genesis_tx.clone()]).unwrap();
+
+    let other_instance = Scalar::random();
+    let other_witness = ZKPWitness::new(other_instance).unwrap();
+    let other_output = Output::new(&Amount::from_f64(10.0).unwrap(), other_witness).unwrap();
+
+    let tx = spend(&other_output, other_instance, 1.0);
+
+    let res = state.verify_transaction(&tx, 1);
+    assert!(res.is_err())
+}
@@ -0,0 +1,159 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `error` module tests.
+
+extern crate failure;
+extern crate libyobicash;
+
+use libyobicash::error::{Error, ErrorKind};
+use libyobicash::result::{Result, ResultExt};
+use libyobicash::traits::{Identify, Validate};
+use libyobicash::utils::{Version, NetworkType, Timestamp, Amount};
+use libyobicash::crypto::{Random, Digest, Scalar, SecretKey, ZKPWitness};
+use libyobicash::models::data::Data;
+use libyobicash::models::output::Output;
+use libyobicash::models::coin::Coin;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::mining::miner::{Miner, MiningJob};
+
+use failure::Fail;
+
+fn fail_at_index(index: u32) -> Result<()> {
+    Err(ErrorKind::InvalidLength.with_field("outputs").with_values(1, 2))
+        .with_index("transactions", index)
+}
+
+#[test]
+fn error_details_succ() {
+    let err = fail_at_index(3).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    assert_eq!(err.details().path(), "transactions[3].outputs");
+    assert_eq!(err.details().expected, Some("1".to_string()));
+    assert_eq!(err.details().actual, Some("2".to_string()));
+    assert_eq!(err.to_string(), "Invalid length at transactions[3].outputs (expected 1, actual 2)");
+
+    // the innermost values are kept
+    let err = err.with_values(3, 4);
+    assert_eq!(err.details().expected, Some("1".to_string()))
+}
+
+#[test]
+fn error_details_fail() {
+    let err = Error::from(ErrorKind::NotFound);
+
+    assert!(err.details().is_empty());
+    assert_eq!(err.details().path(), "");
+    assert_eq!(err.to_string(), "Not found");
+    assert!(err.cause().is_none())
+}
+
+#[test]
+fn error_data_fail() {
+    let sk_a = SecretKey::random();
+    let pk_b = SecretKey::random().to_public();
+    let mut data = Data::new(sk_a, pk_b, 10, &Random::bytes(10)).unwrap();
+    data.cyphertext.push(0);
+    data.id = data.id().unwrap();
+
+    let err = data.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    assert_eq!(err.details().path(), "cyphertext");
+    assert_eq!(err.details().expected, Some("16".to_string()));
+    assert_eq!(err.details().actual, Some("17".to_string()))
+}
+
+#[test]
+fn error_genesis_fail() {
+    let mut block_header = BlockHeader::new_testnet_genesis().unwrap();
+    block_header.timestamp = Timestamp::now();
+
+    let err = block_header.is_genesis().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidTimestamp);
+    assert_eq!(err.details().path(), "timestamp");
+    assert_eq!(err.details().expected, Some(Timestamp::min_value().to_string()))
+}
+
+#[test]
+fn error_coinbase_fail() {
+    let mut transaction = Transaction::new_testnet_genesis().unwrap();
+    transaction.outputs_length = 2;
+
+    let err = transaction.is_coinbase().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    assert_eq!(err.details().path(), "outputs_length");
+    assert_eq!(err.details().expected, Some("1".to_string()));
+    assert_eq!(err.details().actual, Some("2".to_string()));
+
+    let mut transaction = Transaction::new_testnet_genesis().unwrap();
+    transaction.network_type = NetworkType::RegTest;
+
+    let err = transaction.is_genesis().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidNetwork);
+    assert_eq!(err.details().path(), "network_type");
+    assert_eq!(err.details().expected, Some(NetworkType::TestNet.to_string()));
+    assert_eq!(err.details().actual, Some(NetworkType::RegTest.to_string()))
+}
+
+#[test]
+fn error_block_fail() {
+    let transaction = Transaction::new_testnet_genesis().unwrap();
+
+    let err = Block::new(NetworkType::MainNet, &[transaction.clone()]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidNetwork);
+    assert_eq!(err.details().path(), "transactions[0].network_type");
+    assert_eq!(err.details().expected, Some(NetworkType::MainNet.to_string()));
+
+    let err = Block::new(NetworkType::TestNet, &[transaction.clone(), transaction]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicatesFound);
+    assert_eq!(err.details().path(), "transactions");
+
+    let err = Block::new_genesis(&Version::current().unwrap(), NetworkType::RegTest, None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidNetwork);
+    assert_eq!(err.details().path(), "network_type")
+}
+
+#[test]
+fn error_block_header_fail() {
+    // a testnet block header at height 1 not following the testnet genesis
+    let prev_block_header = BlockHeader::new_testnet_genesis().unwrap();
+    let prev_id = Digest::hash(b"prev_id");
+
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+    let fee = Amount::from_f64(1.0).unwrap();
+
+    let mut transactions = Vec::new();
+    for amount in &[10.0, 20.0] {
+        let coin = Coin::new(&Output::new(&Amount::from_f64(*amount).unwrap(), witness).unwrap(), instance).unwrap();
+        let output = Output::new(&Amount::from_f64(amount - 1.0).unwrap(), witness).unwrap();
+        transactions.push(Transaction::new(NetworkType::TestNet, &[coin], &[output], &[], &fee).unwrap());
+    }
+
+    let block = Block::new(NetworkType::TestNet, &transactions).unwrap();
+
+    let mut job = MiningJob::new(&block, &transactions, &prev_block_header, witness).unwrap();
+    job.block_header.prev_id = prev_id;
+    let block_header = Miner::new(1).unwrap().mine(&mut job, |_| {}).unwrap().unwrap();
+
+    let err = block_header.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidNetwork);
+    assert_eq!(err.details().path(), "prev_id");
+    assert_eq!(err.details().actual, Some(prev_id.to_string()))
+}
+
+#[test]
+fn error_source_succ() {
+    let err = Version::from_string("99999999999.0.0").unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::DeserializationFailure);
+    assert!(err.cause().is_some());
+    assert!(err.to_string().starts_with("Deserialization failure: "))
+}
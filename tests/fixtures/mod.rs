@@ -0,0 +1,98 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash tests fixtures.
+
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::{Random, Scalar, ZKPWitness, SecretKey};
+use libyobicash::models::output::Output;
+use libyobicash::models::data::Data;
+use libyobicash::models::coin::Coin;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+
+/// Returns an `Output` of a random witness.
+pub fn new_output(amount: f64) -> Output {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+
+    Output::new(&Amount::from_f64(amount).unwrap(), witness).unwrap()
+}
+
+/// Returns a `Data` of random plaintext and keys.
+pub fn new_data() -> Data {
+    let sk_a = SecretKey::random();
+    let pk_b = SecretKey::random().to_public();
+    let plain = Random::bytes(10);
+
+    Data::new(sk_a, pk_b, 10, &plain).unwrap()
+}
+
+/// Returns a `Transaction` spending a coin of 10 into an output and a fee.
+pub fn new_transaction(network_type: NetworkType, fee: f64, data: &[Data]) -> Transaction {
+    let in_instance = Scalar::random();
+    let in_witness = ZKPWitness::new(in_instance).unwrap();
+    let in_output = Output::new(&Amount::from_f64(10.0).unwrap(), in_witness).unwrap();
+    let in_coin = Coin::new(&in_output, in_instance).unwrap();
+
+    let out_output = new_output(10.0 - fee);
+
+    Transaction::new(network_type, &[in_coin], &[out_output], data, &Amount::from_f64(fee).unwrap()).unwrap()
+}
+
+/// Returns the regtest genesis `BlockHeader`, `Block` and `Transaction` of a witness.
+pub fn regtest_genesis(witness: ZKPWitness) -> (BlockHeader, Block, Transaction) {
+    // the regtest genesis depends on the current time
+    loop {
+        let block_header = BlockHeader::new_regtest_genesis(witness).unwrap();
+        let block = Block::new_regtest_genesis(witness).unwrap();
+        let transaction = Transaction::new_regtest_genesis(witness).unwrap();
+
+        if block_header.block_id == block.id &&
+            block.transactions_ids[0] == transaction.id {
+            return (block_header, block, transaction);
+        }
+    }
+}
+
+/// Returns a regtest `Transaction` spending an output into a new output and a fee.
+pub fn spend(output: &Output, instance: Scalar, fee: f64) -> Transaction {
+    let coin = Coin::new(output, instance).unwrap();
+
+    let fee = Amount::from_f64(fee).unwrap();
+    let out_amount = &output.amount - &fee;
+    let out_witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let out_output = Output::new(&out_amount, out_witness).unwrap();
+
+    Transaction::new(NetworkType::RegTest, &[coin], &[out_output], &[], &fee).unwrap()
+}
+
+/// Returns a `Block` of transactions with a fee of 2 and the transactions.
+pub fn new_block(network_type: NetworkType, length: usize) -> (Block, Vec<Transaction>) {
+    let transactions: Vec<Transaction> = (0..length)
+        .map(|_| new_transaction(network_type, 2.0, &[]))
+        .collect();
+    let block = Block::new(network_type, &transactions).unwrap();
+
+    (block, transactions)
+}
+
+/// Returns a mined `BlockHeader` of a `Block` and its transactions, following the
+/// genesis block header of the block network.
+pub fn new_block_header(block: &Block, transactions: &[Transaction]) -> BlockHeader {
+    let genesis = match block.network_type {
+        NetworkType::MainNet => BlockHeader::new_mainnet_genesis().unwrap(),
+        NetworkType::TestNet => BlockHeader::new_testnet_genesis().unwrap(),
+        NetworkType::RegTest => {
+            let witness = ZKPWitness::new(Scalar::random()).unwrap();
+            BlockHeader::new_regtest_genesis(witness).unwrap()
+        },
+    };
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+
+    BlockHeader::new(block, transactions, &genesis, witness).unwrap()
+}
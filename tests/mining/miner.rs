@@ -0,0 +1,111 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `miner` module tests.
+
+use libyobicash::utils::NetworkType;
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::mining::miner::{Miner, MiningJob};
+use fixtures::new_block;
+
+use std::time::Duration;
+
+#[test]
+fn miner_new_succ() {
+    let res = Miner::new(4);
+    assert!(res.is_ok())
+}
+
+#[test]
+fn miner_new_fail() {
+    let res = Miner::new(0);
+    assert!(res.is_err())
+}
+
+#[test]
+fn miner_mine_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let (block, transactions) = new_block(NetworkType::RegTest, 1);
+
+    let miner = Miner::new(4).unwrap();
+    let mut job = MiningJob::new(&block, &transactions, &genesis, witness).unwrap();
+
+    let block_header = miner.mine(&mut job, |_| {}).unwrap().unwrap();
+    assert!(block_header.verify(&block, &transactions, Some(&genesis)).unwrap())
+}
+
+#[test]
+fn miner_mine_cancel_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let (block, transactions) = new_block(NetworkType::RegTest, 1);
+
+    // a single thread always finds the lowest nonce
+    let mut miner = Miner::new(1).unwrap();
+    miner.progress_interval = Duration::from_millis(1);
+
+    let mut job = MiningJob::new(&block, &transactions, &genesis, witness).unwrap();
+    // a difficulty high enough for the mining to be cancelled several times
+    job.block_header.pow_difficulty = 10;
+    let expected = miner.mine(&mut job.clone(), |_| {}).unwrap().unwrap();
+
+    // a cancel issued before the mining starts is not lost
+    miner.handle().cancel();
+
+    let res = miner.mine(&mut job, |_| {}).unwrap();
+    assert!(res.is_none());
+    assert!(!miner.handle().is_cancelled());
+    assert_eq!(job.next_nonce, 0);
+
+    let handle = miner.handle();
+
+    loop {
+        let res = miner.mine(&mut job, |progress| {
+            if progress.hashes > 0 {
+                handle.cancel();
+            }
+        }).unwrap();
+        assert!(!miner.handle().is_cancelled());
+
+        // the cancelled job is resumed without skipping any nonce
+        if let Some(block_header) = res {
+            assert_eq!(block_header, expected);
+            break;
+        }
+    }
+}
+
+#[test]
+fn miner_mine_progress_succ() {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let (block, transactions) = new_block(NetworkType::RegTest, 1);
+
+    let mut miner = Miner::new(2).unwrap();
+    miner.progress_interval = Duration::from_millis(10);
+
+    let mut job = MiningJob::new(&block, &transactions, &genesis, witness).unwrap();
+    // a difficulty high enough to never be met during the test
+    job.block_header.pow_difficulty = 60;
+
+    let handle = miner.handle();
+    let mut reports = Vec::new();
+
+    let res = miner.mine(&mut job, |progress| {
+        reports.push(*progress);
+
+        if progress.hashes > 0 {
+            handle.cancel();
+        }
+    }).unwrap();
+
+    assert!(res.is_none());
+    assert!(!reports.is_empty());
+    assert!(job.next_nonce > 0)
+}
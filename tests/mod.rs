@@ -9,8 +9,17 @@
 
 extern crate rug;
 extern crate byteorder;
+extern crate futures;
+extern crate serde_json;
 extern crate libyobicash;
 
 
+mod fixtures;
 mod utils;
 mod models;
+mod chain;
+mod store;
+mod wallet;
+mod mining;
+mod protocol;
+mod node;
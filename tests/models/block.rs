@@ -18,13 +18,13 @@ use libyobicash::models::block::Block;
 
 #[test]
 fn block_new_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -37,7 +37,7 @@ fn block_new_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -54,13 +54,13 @@ fn block_new_succ() {
 
 #[test]
 fn block_new_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -73,7 +73,7 @@ fn block_new_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -149,18 +149,18 @@ fn block_new_mainnet_genesis_fail() {
 fn block_validate_succ() {
     let network_type = NetworkType::RegTest;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -168,18 +168,18 @@ fn block_validate_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -197,13 +197,13 @@ fn block_validate_succ() {
 
 #[test]
 fn block_validate_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -216,7 +216,7 @@ fn block_validate_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -236,13 +236,13 @@ fn block_validate_fail() {
 
 #[test]
 fn block_to_json_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -255,7 +255,7 @@ fn block_to_json_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -275,13 +275,13 @@ fn block_to_json_succ() {
 
 #[test]
 fn block_to_json_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -294,7 +294,7 @@ fn block_to_json_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -315,13 +315,13 @@ fn block_to_json_fail() {
 
 #[test]
 fn block_to_bytes_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -334,7 +334,7 @@ fn block_to_bytes_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -354,13 +354,13 @@ fn block_to_bytes_succ() {
 
 #[test]
 fn block_to_bytes_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -373,7 +373,7 @@ fn block_to_bytes_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -394,13 +394,13 @@ fn block_to_bytes_fail() {
 
 #[test]
 fn block_to_hex_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -413,7 +413,7 @@ fn block_to_hex_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -433,13 +433,13 @@ fn block_to_hex_succ() {
 
 #[test]
 fn block_to_hex_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -452,7 +452,7 @@ fn block_to_hex_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -470,3 +470,64 @@ fn block_to_hex_fail() {
     let res = Block::from_hex(&block_str);
     assert!(res.is_err())
 }
+
+#[test]
+fn block_transaction_proof_succ() {
+    let in_amount = Amount::from_f64(10.0).unwrap();
+    let in_instance = Scalar::random();
+    let in_witness = ZKPWitness::new(in_instance).unwrap();
+    let in_output = Output::new(&in_amount, in_witness).unwrap();
+    let in_coin = Coin::new(&in_output, in_instance).unwrap();
+
+    let out_amount = Amount::from_f64(8.0).unwrap();
+    let out_instance = Scalar::random();
+    let out_witness = ZKPWitness::new(out_instance).unwrap();
+    let out_output = Output::new(&out_amount, out_witness).unwrap();
+    
+    let fee = Amount::from_f64(2.0).unwrap();
+    
+    let coins = vec![in_coin];
+    let outputs = vec![out_output];
+    let ds = vec![];
+    
+    let network_type = NetworkType::default();
+
+    let tx = Transaction::new(network_type, &coins, &outputs, &ds, &fee).unwrap();
+    let txs = vec![tx.clone()];
+
+    let block = Block::new(network_type, &txs).unwrap();
+
+    let proof = block.transaction_proof(tx.id).unwrap();
+    let verified = proof.verify(block.transactions_root().unwrap()).unwrap();
+    assert!(verified)
+}
+
+#[test]
+fn block_transaction_proof_fail() {
+    let in_amount = Amount::from_f64(10.0).unwrap();
+    let in_instance = Scalar::random();
+    let in_witness = ZKPWitness::new(in_instance).unwrap();
+    let in_output = Output::new(&in_amount, in_witness).unwrap();
+    let in_coin = Coin::new(&in_output, in_instance).unwrap();
+
+    let out_amount = Amount::from_f64(8.0).unwrap();
+    let out_instance = Scalar::random();
+    let out_witness = ZKPWitness::new(out_instance).unwrap();
+    let out_output = Output::new(&out_amount, out_witness).unwrap();
+    
+    let fee = Amount::from_f64(2.0).unwrap();
+    
+    let coins = vec![in_coin];
+    let outputs = vec![out_output];
+    let ds = vec![];
+    
+    let network_type = NetworkType::default();
+
+    let tx = Transaction::new(network_type, &coins, &outputs, &ds, &fee).unwrap();
+    let txs = vec![tx];
+
+    let block = Block::new(network_type, &txs).unwrap();
+
+    let res = block.transaction_proof(Digest::default());
+    assert!(res.is_err())
+}
@@ -0,0 +1,80 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `block_filter` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::NetworkType;
+use libyobicash::crypto::{Digest, Scalar, ZKPWitness};
+use libyobicash::models::block_filter::BlockFilter;
+use fixtures::{new_block, new_block_header};
+
+#[test]
+fn block_filter_new_succ() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let block_header = new_block_header(&block, &transactions);
+
+    let res = BlockFilter::new(&block_header, &block, &transactions);
+    assert!(res.is_ok());
+
+    let block_filter = res.unwrap();
+    assert_eq!(block_filter.block_id, block.id);
+    assert_eq!(block_filter.filter.length, 5)
+}
+
+#[test]
+fn block_filter_new_fail() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let block_header = new_block_header(&block, &transactions);
+
+    let res = BlockFilter::new(&block_header, &block, &transactions[..1]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+    let swapped = vec![transactions[1].clone(), transactions[0].clone()];
+    let res = BlockFilter::new(&block_header, &block, &swapped);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidTransaction);
+
+    let (other_block, other_transactions) = new_block(NetworkType::TestNet, 2);
+    let res = BlockFilter::new(&block_header, &other_block, &other_transactions);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidBlock)
+}
+
+#[test]
+fn block_filter_match_succ() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let block_header = new_block_header(&block, &transactions);
+    let block_filter = BlockFilter::new(&block_header, &block, &transactions).unwrap();
+
+    let witness = transactions[1].outputs[0].witness;
+    let id = transactions[0].inputs[0].id;
+
+    assert!(block_filter.match_witness(witness).unwrap());
+    assert!(block_filter.match_input(id).unwrap());
+
+    let coinbase_witness = block_header.coinbase_output.witness;
+    assert!(block_filter.match_witness(coinbase_witness).unwrap());
+
+    let other_witness = ZKPWitness::new(Scalar::random()).unwrap();
+    assert!(block_filter.match_any(&[other_witness, witness], &[]).unwrap());
+    assert!(block_filter.match_any(&[], &[Digest::hash(b"other"), id]).unwrap())
+}
+
+#[test]
+fn block_filter_match_fail() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let block_header = new_block_header(&block, &transactions);
+    let block_filter = BlockFilter::new(&block_header, &block, &transactions).unwrap();
+
+    let other_witness = ZKPWitness::new(Scalar::random()).unwrap();
+    assert!(!block_filter.match_witness(other_witness).unwrap());
+    assert!(!block_filter.match_input(Digest::hash(b"other")).unwrap());
+    assert!(!block_filter.match_any(&[], &[]).unwrap());
+
+    // the output ids are not in the filter, only their witnesses
+    let output_id = transactions[0].outputs[0].id;
+    assert!(!block_filter.match_input(output_id).unwrap())
+}
@@ -7,16 +7,23 @@
 
 //! Libyobicash `block_header` module tests.
 
-use libyobicash::constants::TESTWITNESS;
-use libyobicash::traits::{Validate, Serialize};
-use libyobicash::utils::{NetworkType, Amount};
-use libyobicash::crypto::{Digest, Scalar, ZKPWitness};
+use libyobicash::constants::{TESTWITNESS, CONFIRMATION_TIME, RETARGET_TIME};
+use libyobicash::constants::{MEDIAN_TIME_SPAN, MAX_TIMENOISE};
+use libyobicash::constants::{GENESIS_DIFFICULTY, GENESIS_MEMORY};
+use libyobicash::traits::{Identify, Validate, Serialize};
+use libyobicash::utils::{NetworkType, Amount, Timestamp};
+use libyobicash::crypto::{Digest, Scalar, ZKPWitness, Memory};
 use libyobicash::crypto::HexSerialize as CryptoHexSerialize;
 use libyobicash::models::output::Output;
 use libyobicash::models::coin::Coin;
 use libyobicash::models::transaction::Transaction;
 use libyobicash::models::block::Block;
 use libyobicash::models::block_header::BlockHeader;
+use libyobicash::models::block_header::{get_difficulty, get_memory, get_retarget_timestamp, get_pow_work};
+use libyobicash::models::block_header::get_median_time_past;
+use libyobicash::models::monetary_policy::get_subsidy;
+use libyobicash::mining::miner::{Miner, MiningJob};
+use fixtures::new_transaction;
 
 #[test]
 fn block_header_new_succ() {
@@ -24,18 +31,18 @@ fn block_header_new_succ() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -43,18 +50,18 @@ fn block_header_new_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -68,7 +75,7 @@ fn block_header_new_succ() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let res = BlockHeader::new(&block, &prev_block_header, witness);
+    let res = BlockHeader::new(&block, &txs, &prev_block_header, witness);
     assert!(res.is_ok())
 }
 
@@ -78,18 +85,18 @@ fn block_header_new_fail() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -97,18 +104,18 @@ fn block_header_new_fail() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -121,7 +128,7 @@ fn block_header_new_fail() {
     let block = Block::new(network_type, &txs).unwrap();
     let witness = ZKPWitness::from_hex(TESTWITNESS).unwrap();
 
-    let res = BlockHeader::new(&block, &prev_block_header, witness);
+    let res = BlockHeader::new(&block, &txs, &prev_block_header, witness);
     assert!(res.is_err())
 }
 
@@ -188,18 +195,18 @@ fn block_header_validate_succ() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -207,18 +214,18 @@ fn block_header_validate_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -232,7 +239,7 @@ fn block_header_validate_succ() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
 
     let res = block_header.validate();
     assert!(res.is_ok())
@@ -244,18 +251,18 @@ fn block_header_validate_fail() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -263,18 +270,18 @@ fn block_header_validate_fail() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -288,7 +295,7 @@ fn block_header_validate_fail() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let mut block_header = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let mut block_header = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     block_header.prev_id = Digest::default();
 
     let res = block_header.validate();
@@ -301,18 +308,18 @@ fn block_header_verify_succ() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -320,18 +327,18 @@ fn block_header_verify_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -345,9 +352,13 @@ fn block_header_verify_succ() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
 
-    let verified = block_header.verify(&block, Some(&prev_block_header)).unwrap();
+    // the coinbase collects the fees of the transactions
+    let coinbase_amount = Amount::from(get_subsidy(1).unwrap()) + Amount::from_f64(4.0).unwrap();
+    assert_eq!(block_header.coinbase_amount, coinbase_amount);
+
+    let verified = block_header.verify(&block, &txs, Some(&prev_block_header)).unwrap();
     assert!(verified)
 }
 
@@ -357,18 +368,18 @@ fn block_header_verify_fail() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -376,18 +387,18 @@ fn block_header_verify_fail() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -401,14 +412,34 @@ fn block_header_verify_fail() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header = BlockHeader::new(&block_a, &prev_block_header, witness).unwrap();
+    let block_header = BlockHeader::new(&block_a, &txs_a, &prev_block_header, witness).unwrap();
 
     let txs_b = vec![tx_b, tx_a];
 
     let block_b = Block::new(network_type, &txs_b).unwrap();
 
-    let verified = block_header.verify(&block_b, Some(&prev_block_header)).unwrap();
-    assert!(!verified)
+    let verified = block_header.verify(&block_b, &txs_b, Some(&prev_block_header)).unwrap();
+    assert!(!verified);
+
+    let res = block_header.verify(&block_a, &txs_a[..1], Some(&prev_block_header));
+    assert!(res.is_err());
+
+    // another block with other fees is not the block of the header
+    let txs_c = vec![new_transaction(network_type, 3.0, &[]), new_transaction(network_type, 3.0, &[])];
+    let block_c = Block::new(network_type, &txs_c).unwrap();
+
+    let verified = block_header.verify(&block_c, &txs_c, Some(&prev_block_header)).unwrap();
+    assert!(!verified);
+
+    // the coinbase claims more than the fees of the transactions
+    let mut job = MiningJob::new(&block_a, &txs_a, &prev_block_header, witness).unwrap();
+    let coinbase_amount = &job.block_header.coinbase_amount + &Amount::from_f64(1.0).unwrap();
+    job.block_header.coinbase_output = Output::new(&coinbase_amount, witness).unwrap();
+    job.block_header.coinbase_amount = coinbase_amount;
+
+    let block_header = Miner::new(1).unwrap().mine(&mut job, |_| {}).unwrap().unwrap();
+    let err = block_header.verify(&block_a, &txs_a, Some(&prev_block_header)).unwrap_err();
+    assert_eq!(err.details().path(), "coinbase_amount")
 }
 
 #[test]
@@ -417,18 +448,18 @@ fn block_header_to_json_succ() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -436,18 +467,18 @@ fn block_header_to_json_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -461,7 +492,7 @@ fn block_header_to_json_succ() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header_a = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header_a = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     let block_header_str = block_header_a.to_json().unwrap();
     let block_header_b = BlockHeader::from_json(&block_header_str).unwrap();
     
@@ -474,18 +505,18 @@ fn block_header_to_json_fail() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -493,18 +524,18 @@ fn block_header_to_json_fail() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -518,7 +549,7 @@ fn block_header_to_json_fail() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header_a = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header_a = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     let mut block_header_str = block_header_a.to_json().unwrap();
     block_header_str.pop();
 
@@ -532,18 +563,18 @@ fn block_header_to_bytes_succ() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -551,18 +582,18 @@ fn block_header_to_bytes_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -576,7 +607,7 @@ fn block_header_to_bytes_succ() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header_a = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header_a = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     let block_header_buf = block_header_a.to_bytes().unwrap();
     let block_header_b = BlockHeader::from_bytes(&block_header_buf).unwrap();
     
@@ -589,18 +620,18 @@ fn block_header_to_bytes_fail() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -608,18 +639,18 @@ fn block_header_to_bytes_fail() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -633,7 +664,7 @@ fn block_header_to_bytes_fail() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header_a = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header_a = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     let mut block_header_buf = block_header_a.to_bytes().unwrap();
     block_header_buf[0] ^= block_header_buf[0];
     
@@ -647,18 +678,18 @@ fn block_header_to_hex_succ() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -666,18 +697,18 @@ fn block_header_to_hex_succ() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -691,7 +722,7 @@ fn block_header_to_hex_succ() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header_a = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header_a = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     let block_header_str = block_header_a.to_hex().unwrap();
     let block_header_b = BlockHeader::from_hex(&block_header_str).unwrap();
     
@@ -704,18 +735,18 @@ fn block_header_to_hex_fail() {
 
     let network_type = NetworkType::TestNet;
 
-    let in_amount_a = Amount::from(10.0);
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
     let in_instance_a = Scalar::random();
     let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
     let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
     let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
 
-    let out_amount_a = Amount::from(8.0);
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
     let out_instance_a = Scalar::random();
     let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
     let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
     
-    let fee_a = Amount::from(2.0);
+    let fee_a = Amount::from_f64(2.0).unwrap();
     
     let coins_a = vec![in_coin_a];
     let outputs_a = vec![out_output_a];
@@ -723,18 +754,18 @@ fn block_header_to_hex_fail() {
 
     let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
 
-    let in_amount_b = Amount::from(10.0);
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
     let in_instance_b = Scalar::random();
     let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
     let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
     let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
 
-    let out_amount_b = Amount::from(8.0);
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
     let out_instance_b = Scalar::random();
     let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
     let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
     
-    let fee_b = Amount::from(2.0);
+    let fee_b = Amount::from_f64(2.0).unwrap();
     
     let coins_b = vec![in_coin_b];
     let outputs_b = vec![out_output_b];
@@ -748,10 +779,379 @@ fn block_header_to_hex_fail() {
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
-    let block_header_a = BlockHeader::new(&block, &prev_block_header, witness).unwrap();
+    let block_header_a = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
     let mut block_header_str = block_header_a.to_hex().unwrap();
     block_header_str.pop();
 
     let res = BlockHeader::from_hex(&block_header_str);
     assert!(res.is_err())
 }
+
+#[test]
+fn block_header_verify_transaction_succ() {
+    let prev_block_header = BlockHeader::new_testnet_genesis().unwrap();
+
+    let network_type = NetworkType::TestNet;
+
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
+    let in_instance_a = Scalar::random();
+    let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
+    let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
+    let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
+
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
+    let out_instance_a = Scalar::random();
+    let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
+    let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
+    
+    let fee_a = Amount::from_f64(2.0).unwrap();
+    
+    let coins_a = vec![in_coin_a];
+    let outputs_a = vec![out_output_a];
+    let ds_a = vec![];
+
+    let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
+
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
+    let in_instance_b = Scalar::random();
+    let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
+    let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
+    let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
+
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
+    let out_instance_b = Scalar::random();
+    let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
+    let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
+    
+    let fee_b = Amount::from_f64(2.0).unwrap();
+    
+    let coins_b = vec![in_coin_b];
+    let outputs_b = vec![out_output_b];
+    let ds_b = vec![];
+
+    let tx_b = Transaction::new(network_type, &coins_b, &outputs_b, &ds_b, &fee_b).unwrap();
+
+    let txs = vec![tx_a, tx_b.clone()];
+
+    let block = Block::new(network_type, &txs).unwrap();
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+
+    let block_header = BlockHeader::new(&block, &txs, &prev_block_header, witness).unwrap();
+
+    let proof = block.transaction_proof(tx_b.id).unwrap();
+
+    let verified = block_header.verify_transaction(&proof).unwrap();
+    assert!(verified)
+}
+
+#[test]
+fn block_header_verify_transaction_fail() {
+    let prev_block_header = BlockHeader::new_testnet_genesis().unwrap();
+
+    let network_type = NetworkType::TestNet;
+
+    let in_amount_a = Amount::from_f64(10.0).unwrap();
+    let in_instance_a = Scalar::random();
+    let in_witness_a = ZKPWitness::new(in_instance_a).unwrap();
+    let in_output_a = Output::new(&in_amount_a, in_witness_a).unwrap();
+    let in_coin_a = Coin::new(&in_output_a, in_instance_a).unwrap();
+
+    let out_amount_a = Amount::from_f64(8.0).unwrap();
+    let out_instance_a = Scalar::random();
+    let out_witness_a = ZKPWitness::new(out_instance_a).unwrap();
+    let out_output_a = Output::new(&out_amount_a, out_witness_a).unwrap();
+    
+    let fee_a = Amount::from_f64(2.0).unwrap();
+    
+    let coins_a = vec![in_coin_a];
+    let outputs_a = vec![out_output_a];
+    let ds_a = vec![];
+
+    let tx_a = Transaction::new(network_type, &coins_a, &outputs_a, &ds_a, &fee_a).unwrap();
+
+    let in_amount_b = Amount::from_f64(10.0).unwrap();
+    let in_instance_b = Scalar::random();
+    let in_witness_b = ZKPWitness::new(in_instance_b).unwrap();
+    let in_output_b = Output::new(&in_amount_b, in_witness_b).unwrap();
+    let in_coin_b = Coin::new(&in_output_b, in_instance_b).unwrap();
+
+    let out_amount_b = Amount::from_f64(8.0).unwrap();
+    let out_instance_b = Scalar::random();
+    let out_witness_b = ZKPWitness::new(out_instance_b).unwrap();
+    let out_output_b = Output::new(&out_amount_b, out_witness_b).unwrap();
+    
+    let fee_b = Amount::from_f64(2.0).unwrap();
+    
+    let coins_b = vec![in_coin_b];
+    let outputs_b = vec![out_output_b];
+    let ds_b = vec![];
+
+    let tx_b = Transaction::new(network_type, &coins_b, &outputs_b, &ds_b, &fee_b).unwrap();
+
+    let txs_a = vec![tx_a.clone(), tx_b.clone()];
+    let txs_b = vec![tx_b.clone(), tx_a];
+
+    let block_a = Block::new(network_type, &txs_a).unwrap();
+    let block_b = Block::new(network_type, &txs_b).unwrap();
+    let instance = Scalar::random();
+    let witness = ZKPWitness::new(instance).unwrap();
+
+    let block_header = BlockHeader::new(&block_a, &txs_a, &prev_block_header, witness).unwrap();
+
+    let proof = block_b.transaction_proof(tx_b.id).unwrap();
+
+    let verified = block_header.verify_transaction(&proof).unwrap();
+    assert!(!verified)
+}
+
+fn add_seconds(timestamp: Timestamp, seconds: i64) -> Timestamp {
+    let secs: i64 = timestamp.to_string().parse().unwrap();
+
+    Timestamp::from_string(&format!("{}", secs + seconds)).unwrap()
+}
+
+fn retarget_header(difficulty: u32, memory: u32, duration: i64) -> BlockHeader {
+    let mut block_header = BlockHeader::default();
+    block_header.height = RETARGET_TIME - 1;
+    block_header.retarget_timestamp = Timestamp::min_value();
+    block_header.timestamp = add_seconds(Timestamp::min_value(), duration);
+    block_header.pow_difficulty = difficulty;
+    block_header.pow_memory = Memory::from(memory);
+    block_header
+}
+
+// mines the headers following a given one with a constant hashrate, in units of
+// work per second, returning the intervals between them
+fn simulate_mining(prev_block_header: &BlockHeader, hashrate: u32, length: u32) -> Vec<i64> {
+    let mut prev_block_header = prev_block_header.clone();
+    let mut intervals = Vec::new();
+
+    for _ in 0..length {
+        let mut block_header = BlockHeader::default();
+        block_header.height = prev_block_header.height + 1;
+        block_header.retarget_timestamp = get_retarget_timestamp(&prev_block_header);
+        block_header.pow_difficulty = get_difficulty(&prev_block_header).unwrap();
+        block_header.pow_memory = get_memory(&prev_block_header).unwrap();
+
+        let work = get_pow_work(block_header.pow_difficulty, &block_header.pow_memory);
+        let work: i64 = work.to_string().parse().unwrap();
+        let hashrate = i64::from(hashrate);
+        let interval = (work + hashrate / 2) / hashrate;
+
+        block_header.timestamp = add_seconds(prev_block_header.timestamp, interval);
+
+        intervals.push(interval);
+        prev_block_header = block_header;
+    }
+
+    intervals
+}
+
+#[test]
+fn block_header_retarget_succ() {
+    let expected_duration = i64::from(RETARGET_TIME * CONFIRMATION_TIME);
+
+    let prev_block_header = retarget_header(10, 64, expected_duration);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 10);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(64u32));
+    assert_eq!(get_retarget_timestamp(&prev_block_header), prev_block_header.timestamp);
+
+    let prev_block_header = retarget_header(10, 64, expected_duration / 2);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 11);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(64u32));
+
+    let prev_block_header = retarget_header(10, 64, expected_duration * 2);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 9);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(64u32));
+
+    // the adjustment is bounded
+    let prev_block_header = retarget_header(10, 64, 0);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 12);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(64u32));
+
+    let prev_block_header = retarget_header(10, 64, expected_duration * 100);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 8);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(64u32));
+
+    // the memory makes up for the work the difficulty cannot reach
+    let prev_block_header = retarget_header(10, 100, expected_duration / 3);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 12);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(75u32));
+
+    // the work does not go below the minimum
+    let prev_block_header = retarget_header(GENESIS_DIFFICULTY, GENESIS_MEMORY, expected_duration * 4);
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), GENESIS_DIFFICULTY);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(GENESIS_MEMORY));
+
+    // out of the retarget heights nothing changes
+    let mut prev_block_header = retarget_header(10, 64, 0);
+    prev_block_header.height = RETARGET_TIME;
+    assert_eq!(get_difficulty(&prev_block_header).unwrap(), 10);
+    assert_eq!(get_memory(&prev_block_header).unwrap(), Memory::from(64u32));
+    assert_eq!(get_retarget_timestamp(&prev_block_header), prev_block_header.retarget_timestamp);
+}
+
+#[test]
+fn block_header_retarget_fail() {
+    let mut prev_block_header = retarget_header(10, 64, 0);
+    prev_block_header.retarget_timestamp = add_seconds(prev_block_header.timestamp, 1);
+
+    let res = get_difficulty(&prev_block_header);
+    assert!(res.is_err());
+
+    let res = get_memory(&prev_block_header);
+    assert!(res.is_err());
+}
+
+#[test]
+fn block_header_retarget_convergence_succ() {
+    let mut genesis = BlockHeader::default();
+    genesis.timestamp = Timestamp::min_value();
+    genesis.retarget_timestamp = Timestamp::min_value();
+    genesis.pow_difficulty = GENESIS_DIFFICULTY;
+    genesis.pow_memory = Memory::from(GENESIS_MEMORY);
+
+    let periods = 20;
+    let confirmation_time = i64::from(CONFIRMATION_TIME);
+
+    for hashrate in &[1_000, 50_000, 3_000_000] {
+        let intervals = simulate_mining(&genesis, *hashrate, RETARGET_TIME * periods);
+
+        let last_period = &intervals[intervals.len() - RETARGET_TIME as usize..];
+        let average = last_period.iter().sum::<i64>() / i64::from(RETARGET_TIME);
+
+        assert!(average >= confirmation_time - 1);
+        assert!(average <= confirmation_time + 1);
+    }
+}
+
+#[test]
+fn block_header_retarget_convergence_fail() {
+    let mut genesis = BlockHeader::default();
+    genesis.timestamp = Timestamp::min_value();
+    genesis.retarget_timestamp = Timestamp::min_value();
+    genesis.pow_difficulty = GENESIS_DIFFICULTY;
+    genesis.pow_memory = Memory::from(GENESIS_MEMORY);
+
+    // a retarget cannot adjust more than the maximum factor, so a hashrate far
+    // from the genesis work takes more than one period to be matched
+    let hashrate = 3_000_000;
+    let intervals = simulate_mining(&genesis, hashrate, RETARGET_TIME * 2);
+
+    let second_period = &intervals[RETARGET_TIME as usize..];
+    let average = second_period.iter().sum::<i64>() / i64::from(RETARGET_TIME);
+
+    assert!(average < i64::from(CONFIRMATION_TIME) - 1);
+}
+
+// returns a chain of block headers whose timestamps are spaced by the given intervals
+fn timestamps_chain(intervals: &[i64]) -> Vec<BlockHeader> {
+    let mut chain: Vec<BlockHeader> = Vec::new();
+    let mut timestamp = Timestamp::min_value();
+
+    for interval in intervals {
+        timestamp = add_seconds(timestamp, *interval);
+
+        let mut block_header = BlockHeader::default();
+        block_header.timestamp = timestamp;
+
+        if let Some(prev_block_header) = chain.last() {
+            block_header.height = prev_block_header.height + 1;
+            block_header.prev_id = prev_block_header.id;
+        }
+
+        block_header.id = block_header.id().unwrap();
+        chain.push(block_header);
+    }
+
+    chain
+}
+
+fn next_header(prev_block_header: &BlockHeader, timestamp: Timestamp) -> BlockHeader {
+    let mut block_header = BlockHeader::default();
+    block_header.height = prev_block_header.height + 1;
+    block_header.prev_id = prev_block_header.id;
+    block_header.timestamp = timestamp;
+    block_header.id = block_header.id().unwrap();
+    block_header
+}
+
+#[test]
+fn block_header_median_time_past_succ() {
+    let chain = timestamps_chain(&[0, 10, 10, 10, 10]);
+    let median = get_median_time_past(&chain).unwrap();
+    assert_eq!(median, chain[2].timestamp);
+
+    // the timestamps are sorted before taking the median
+    let chain = timestamps_chain(&[0, 100, -90, 10, -30]);
+    let median = get_median_time_past(&chain).unwrap();
+    assert_eq!(median, chain[2].timestamp);
+}
+
+#[test]
+fn block_header_median_time_past_fail() {
+    let res = get_median_time_past(&[]);
+    assert!(res.is_err());
+}
+
+#[test]
+fn block_header_verify_timestamp_succ() {
+    let now = Timestamp::now();
+
+    let genesis = timestamps_chain(&[0]).pop().unwrap();
+    let verified = genesis.verify_timestamp(&[], now).unwrap();
+    assert!(verified);
+
+    let chain = timestamps_chain(&[10; 20]);
+    let ancestors = &chain[chain.len() - MEDIAN_TIME_SPAN as usize..];
+    let median = get_median_time_past(ancestors).unwrap();
+    let prev_block_header = chain.last().unwrap();
+
+    // older than the previous block header, but above the median
+    let block_header = next_header(prev_block_header, add_seconds(median, 1));
+    let verified = block_header.verify_timestamp(ancestors, now).unwrap();
+    assert!(verified);
+
+    let block_header = next_header(prev_block_header, add_seconds(now, MAX_TIMENOISE));
+    let verified = block_header.verify_timestamp(ancestors, now).unwrap();
+    assert!(verified);
+
+    // shorter chains use all of their block headers
+    let chain = timestamps_chain(&[10; 3]);
+    let block_header = next_header(chain.last().unwrap(), add_seconds(chain[1].timestamp, 1));
+    let verified = block_header.verify_timestamp(&chain, now).unwrap();
+    assert!(verified);
+}
+
+#[test]
+fn block_header_verify_timestamp_fail() {
+    let now = Timestamp::now();
+
+    let chain = timestamps_chain(&[10; 20]);
+    let ancestors = &chain[chain.len() - MEDIAN_TIME_SPAN as usize..];
+    let median = get_median_time_past(ancestors).unwrap();
+    let prev_block_header = chain.last().unwrap();
+
+    let block_header = next_header(prev_block_header, median);
+    let verified = block_header.verify_timestamp(ancestors, now).unwrap();
+    assert!(!verified);
+
+    let block_header = next_header(prev_block_header, add_seconds(now, MAX_TIMENOISE + 1));
+    let verified = block_header.verify_timestamp(ancestors, now).unwrap();
+    assert!(!verified);
+
+    let block_header = next_header(prev_block_header, now);
+
+    let res = block_header.verify_timestamp(&ancestors[1..], now);
+    assert!(res.is_err());
+
+    let res = block_header.verify_timestamp(&chain[..MEDIAN_TIME_SPAN as usize], now);
+    assert!(res.is_err());
+
+    let mut unlinked = ancestors.to_vec();
+    unlinked.swap(3, 4);
+
+    let res = block_header.verify_timestamp(&unlinked, now);
+    assert!(res.is_err());
+}
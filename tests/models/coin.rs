@@ -15,7 +15,7 @@ use libyobicash::models::coin::Coin;
 
 #[test]
 fn coin_new_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -26,7 +26,7 @@ fn coin_new_succ() {
 
 #[test]
 fn coin_new_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance_a = Scalar::random();
     let witness_a = ZKPWitness::new(instance_a).unwrap();
     let output = Output::new(&amount, witness_a).unwrap();
@@ -38,7 +38,7 @@ fn coin_new_fail() {
 
 #[test]
 fn coin_verify_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -54,7 +54,7 @@ fn coin_verify_succ() {
 
 #[test]
 fn coin_verify_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance_a = Scalar::random();
     let witness_a = ZKPWitness::new(instance_a).unwrap();
     let output = Output::new(&amount, witness_a).unwrap();
@@ -71,7 +71,7 @@ fn coin_verify_fail() {
 
 #[test]
 fn coin_validate_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -84,7 +84,7 @@ fn coin_validate_succ() {
 
 #[test]
 fn coin_validate_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -98,7 +98,7 @@ fn coin_validate_fail() {
 
 #[test]
 fn coin_to_json_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -112,7 +112,7 @@ fn coin_to_json_succ() {
 
 #[test]
 fn coin_to_json_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -127,7 +127,7 @@ fn coin_to_json_fail() {
 
 #[test]
 fn coin_to_bytes_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -141,7 +141,7 @@ fn coin_to_bytes_succ() {
 
 #[test]
 fn coin_to_bytes_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -156,7 +156,7 @@ fn coin_to_bytes_fail() {
 
 #[test]
 fn coin_to_hex_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -170,7 +170,7 @@ fn coin_to_hex_succ() {
 
 #[test]
 fn coin_to_hex_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -0,0 +1,121 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `compact_block` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::Validate;
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::{Random, Digest};
+use libyobicash::models::compact_block::CompactBlock;
+use fixtures::{new_transaction, new_block};
+
+#[test]
+fn compact_block_new_succ() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 3);
+    let salt = Random::u64();
+
+    let res = CompactBlock::new(&block, salt);
+    assert!(res.is_ok());
+
+    let compact_block = res.unwrap();
+    assert_eq!(compact_block.block_id, block.id);
+    assert_eq!(compact_block.short_ids.len(), transactions.len());
+
+    for (short_id, transaction) in compact_block.short_ids.iter().zip(transactions.iter()) {
+        assert_eq!(*short_id, compact_block.short_id(transaction.id).unwrap());
+    }
+
+    // a different salt gives different short ids
+    let other_compact_block = CompactBlock::new(&block, salt + 1).unwrap();
+    assert_ne!(other_compact_block.short_ids, compact_block.short_ids)
+}
+
+#[test]
+fn compact_block_new_fail() {
+    let (mut block, _) = new_block(NetworkType::TestNet, 2);
+    block.id = Digest::hash(b"block");
+
+    let res = CompactBlock::new(&block, Random::u64());
+    assert!(res.is_err());
+
+    let (block, _) = new_block(NetworkType::TestNet, 2);
+    let compact_block = CompactBlock::new(&block, Random::u64()).unwrap();
+
+    let mut duplicates = compact_block.clone();
+    duplicates.short_ids[1] = duplicates.short_ids[0];
+    let res = duplicates.validate();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::DuplicatesFound);
+
+    let mut shorter = compact_block.clone();
+    shorter.short_ids.pop();
+    let res = shorter.validate();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+    let mut longer_id = compact_block;
+    longer_id.short_ids[0] = 1 << 48;
+    let res = longer_id.validate();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound)
+}
+
+#[test]
+fn compact_block_reconstruct_succ() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 3);
+    let compact_block = CompactBlock::new(&block, Random::u64()).unwrap();
+
+    let pool = vec![new_transaction(NetworkType::TestNet, 2.0, &[]), transactions[2].clone(), transactions[0].clone()];
+
+    let mut partial_block = compact_block.reconstruct(&pool).unwrap();
+    assert!(!partial_block.is_complete());
+    assert_eq!(partial_block.missing_indexes(), vec![1]);
+
+    let res = partial_block.fill(&[transactions[1].clone()]);
+    assert!(res.is_ok());
+    assert!(partial_block.is_complete());
+
+    let (reconstructed, reconstructed_transactions) = partial_block.to_block().unwrap();
+    assert_eq!(reconstructed, block);
+    assert_eq!(reconstructed_transactions, transactions);
+
+    // a pool holding all the transactions
+    let partial_block = compact_block.reconstruct(&transactions).unwrap();
+    assert!(partial_block.missing_indexes().is_empty());
+
+    let (reconstructed, _) = partial_block.to_block().unwrap();
+    assert_eq!(reconstructed, block)
+}
+
+#[test]
+fn compact_block_reconstruct_fail() {
+    let (block, transactions) = new_block(NetworkType::TestNet, 3);
+    let compact_block = CompactBlock::new(&block, Random::u64()).unwrap();
+
+    let mut partial_block = compact_block.reconstruct(&[]).unwrap();
+    assert_eq!(partial_block.missing_indexes(), vec![0, 1, 2]);
+
+    let res = partial_block.to_block();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound);
+
+    let res = partial_block.fill(&transactions[..2]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+    let wrong_order = vec![transactions[1].clone(), transactions[0].clone(), transactions[2].clone()];
+    let res = partial_block.fill(&wrong_order);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidTransaction);
+    assert_eq!(partial_block.missing_indexes(), vec![0, 1, 2]);
+
+    let mut tampered = transactions.clone();
+    tampered[1].outputs_amount += Amount::from_f64(1.0).unwrap();
+    let res = partial_block.fill(&tampered);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidDigest);
+    assert_eq!(partial_block.missing_indexes(), vec![0, 1, 2]);
+
+    let mut invalid = compact_block;
+    invalid.short_ids.pop();
+    let res = invalid.reconstruct(&transactions);
+    assert!(res.is_err())
+}
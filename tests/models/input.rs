@@ -16,7 +16,7 @@ use libyobicash::models::coin::Coin;
 
 #[test]
 fn input_new_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -32,7 +32,7 @@ fn input_new_succ() {
 
 #[test]
 fn input_new_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance_a = Scalar::random();
     let witness = ZKPWitness::new(instance_a).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -50,7 +50,7 @@ fn input_new_fail() {
 
 #[test]
 fn input_verify_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -68,7 +68,7 @@ fn input_verify_succ() {
 
 #[test]
 fn input_verify_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance_a = Scalar::random();
     let witness_a = ZKPWitness::new(instance_a).unwrap();
     let mut output = Output::new(&amount, witness_a).unwrap();
@@ -89,7 +89,7 @@ fn input_verify_fail() {
 
 #[test]
 fn input_validate_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -107,7 +107,7 @@ fn input_validate_succ() {
 
 #[test]
 fn input_to_json_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -126,7 +126,7 @@ fn input_to_json_succ() {
 
 #[test]
 fn input_to_json_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -146,7 +146,7 @@ fn input_to_json_fail() {
 
 #[test]
 fn input_to_bytes_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -165,7 +165,7 @@ fn input_to_bytes_succ() {
 
 #[test]
 fn input_to_bytes_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -185,7 +185,7 @@ fn input_to_bytes_fail() {
 
 #[test]
 fn input_to_hex_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -204,7 +204,7 @@ fn input_to_hex_succ() {
 
 #[test]
 fn input_to_hex_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -7,6 +7,7 @@
 
 //! Libyobicash `models` module tests.
 
+mod report;
 mod data;
 mod output;
 mod coin;
@@ -14,3 +15,6 @@ mod input;
 mod transaction;
 mod block;
 mod block_header;
+mod monetary_policy;
+mod compact_block;
+mod block_filter;
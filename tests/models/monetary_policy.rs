@@ -0,0 +1,129 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `monetary_policy` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::{NetworkType, Amount, FixedAmount};
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::output::Output;
+use libyobicash::models::coin::Coin;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::models::monetary_policy::{get_subsidy, get_supply, get_max_supply_height, get_fees};
+use libyobicash::models::monetary_policy::{verify_coinbase_subsidy, verify_coinbase};
+
+// returns a regtest block header template with the block of a transaction paying 2.0
+// of fees, and the transaction
+fn new_block_header_template() -> (BlockHeader, Block, Vec<Transaction>) {
+    let genesis_witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let prev_block_header = BlockHeader::new_regtest_genesis(genesis_witness).unwrap();
+
+    let in_instance = Scalar::random();
+    let in_witness = ZKPWitness::new(in_instance).unwrap();
+    let in_output = Output::new(&Amount::from_f64(10.0).unwrap(), in_witness).unwrap();
+    let in_coin = Coin::new(&in_output, in_instance).unwrap();
+
+    let out_witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let out_output = Output::new(&Amount::from_f64(8.0).unwrap(), out_witness).unwrap();
+    let fee = Amount::from_f64(2.0).unwrap();
+
+    let transaction = Transaction::new(NetworkType::RegTest, &[in_coin], &[out_output], &[], &fee).unwrap();
+    let transactions = vec![transaction];
+    let block = Block::new(NetworkType::RegTest, &transactions).unwrap();
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let block_header = BlockHeader::new_template(&block, &transactions, &prev_block_header, witness).unwrap();
+
+    (block_header, block, transactions)
+}
+
+#[test]
+fn monetary_policy_schedule_succ() {
+    assert_eq!(get_subsidy(0).unwrap(), FixedAmount::from_coins(21).unwrap());
+    assert_eq!(get_subsidy(1).unwrap().to_decimal(), "1.4");
+    assert_eq!(get_subsidy(2).unwrap().to_decimal(), "1.96");
+    // 1.4^10 = 28.9254654976 is rounded down to a base unit
+    assert_eq!(get_subsidy(10).unwrap().to_decimal(), "28.92546549");
+
+    assert_eq!(get_supply(0).unwrap().to_decimal(), "21");
+    assert_eq!(get_supply(2).unwrap().to_decimal(), "24.36");
+    assert_eq!(get_supply(10).unwrap().to_decimal(), "118.73912923");
+
+    let height = get_max_supply_height().unwrap();
+    assert_eq!(height, 47);
+    assert_eq!(get_supply(height).unwrap(), FixedAmount::max_value());
+    assert!(get_supply(height - 1).unwrap() < FixedAmount::max_value());
+    assert_eq!(get_subsidy(height).unwrap().to_decimal(), "2551625.86756184");
+    assert!(get_subsidy(height + 1).unwrap().is_zero());
+    assert_eq!(get_supply(u32::max_value()).unwrap(), FixedAmount::max_value());
+
+    for height in 1..(height + 1) {
+        let supply = get_supply(height - 1).unwrap().checked_add(get_subsidy(height).unwrap()).unwrap();
+        assert_eq!(get_supply(height).unwrap(), supply);
+    }
+}
+
+#[test]
+fn monetary_policy_schedule_fail() {
+    assert_ne!(get_subsidy(1).unwrap(), FixedAmount::zero());
+    assert_ne!(Amount::from(get_subsidy(1).unwrap()), Amount::from_f32(1.4f32).unwrap())
+}
+
+#[test]
+fn monetary_policy_verify_coinbase_succ() {
+    let (block_header, block, transactions) = new_block_header_template();
+    let fees = get_fees(&block, &transactions).unwrap();
+    assert_eq!(fees, Amount::from_f64(2.0).unwrap());
+    assert_eq!(block_header.coinbase_amount, Amount::from(get_subsidy(1).unwrap()) + &fees);
+    assert_eq!(block_header.coinbase_output.amount, block_header.coinbase_amount);
+    assert!(verify_coinbase_subsidy(&block_header).is_ok());
+    assert!(verify_coinbase(&block_header, &fees).is_ok());
+
+    for block_header in &[BlockHeader::new_testnet_genesis().unwrap(), BlockHeader::new_mainnet_genesis().unwrap()] {
+        assert!(verify_coinbase(block_header, &Amount::zero()).is_ok());
+    }
+}
+
+#[test]
+fn monetary_policy_verify_coinbase_fail() {
+    let (mut block_header, block, transactions) = new_block_header_template();
+    let fees = get_fees(&block, &transactions).unwrap();
+
+    // the fees claimed are not the ones of the transactions
+    let err = verify_coinbase(&block_header, &Amount::zero()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::OutOfBound);
+    assert_eq!(err.details().path(), "coinbase_amount");
+
+    let err = get_fees(&block, &[]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    assert_eq!(err.details().path(), "transactions");
+
+    let (_, _, other_transactions) = new_block_header_template();
+    let err = get_fees(&block, &other_transactions).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidID);
+    assert_eq!(err.details().path(), "transactions[0]");
+
+    // the coinbase is below the subsidy
+    block_header.coinbase_amount = Amount::from_f64(1.0).unwrap();
+    let err = verify_coinbase_subsidy(&block_header).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::OutOfBound);
+    assert_eq!(err.details().path(), "coinbase_amount");
+
+    let (mut block_header, _, _) = new_block_header_template();
+    block_header.coinbase_output = Output::new(&Amount::from_f64(2.0).unwrap(), block_header.coinbase_output.witness).unwrap();
+    let err = verify_coinbase(&block_header, &fees).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::OutOfBound);
+    assert_eq!(err.details().path(), "coinbase_output");
+
+    let mut block_header = BlockHeader::new_testnet_genesis().unwrap();
+    block_header.coinbase_output = Output::new_mainnet_genesis().unwrap();
+    let err = verify_coinbase(&block_header, &Amount::zero()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidGenesis);
+    assert_eq!(err.details().path(), "coinbase_output")
+}
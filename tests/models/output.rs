@@ -8,6 +8,7 @@
 //! Libyobicash `output` module tests.
 
 use libyobicash::constants::{MAINWITNESS, TESTWITNESS};
+use libyobicash::error::ErrorKind;
 use libyobicash::traits::{Validate, Serialize};
 use libyobicash::utils::Amount;
 use libyobicash::crypto::{Random, Scalar, ZKPWitness, ZKPProof};
@@ -18,7 +19,7 @@ use libyobicash::models::input::Input;
 
 #[test]
 fn output_new_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
 
@@ -28,7 +29,7 @@ fn output_new_succ() {
 
 #[test]
 fn output_new_fail() {
-    let amount = Amount::from(1.0);
+    let amount = Amount::from_f64(1.0).unwrap();
     let witness = ZKPWitness::from_hex(MAINWITNESS).unwrap();
     
     let res = Output::new(&amount, witness);
@@ -92,7 +93,7 @@ fn output_new_mainnet_genesis_fail() {
 
 #[test]
 fn output_verify_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -109,7 +110,7 @@ fn output_verify_succ() {
 
 #[test]
 fn output_verify_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     let output = Output::new(&amount, witness).unwrap();
@@ -127,7 +128,7 @@ fn output_verify_fail() {
 
 #[test]
 fn output_validate_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -139,7 +140,7 @@ fn output_validate_succ() {
 
 #[test]
 fn output_validate_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -147,12 +148,20 @@ fn output_validate_fail() {
     output.witness = ZKPWitness::from_hex(TESTWITNESS).unwrap();
     
     let res = output.validate();
-    assert!(res.is_err())
+    assert!(res.is_err());
+
+    // the amount is not a whole number of base units
+    let third = Amount::from_string("1/3").unwrap();
+    let output = Output::new(&third, witness).unwrap();
+
+    let err = output.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+    assert_eq!(err.details().path(), "amount")
 }
 
 #[test]
 fn output_to_json_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -165,7 +174,7 @@ fn output_to_json_succ() {
 
 #[test]
 fn output_to_json_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -179,7 +188,7 @@ fn output_to_json_fail() {
 
 #[test]
 fn output_to_bytes_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -192,7 +201,7 @@ fn output_to_bytes_succ() {
 
 #[test]
 fn output_to_bytes_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -206,7 +215,7 @@ fn output_to_bytes_fail() {
 
 #[test]
 fn output_to_hex_succ() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
@@ -219,7 +228,7 @@ fn output_to_hex_succ() {
 
 #[test]
 fn output_to_hex_fail() {
-    let amount = Amount::from(10.0);
+    let amount = Amount::from_f64(10.0).unwrap();
     let instance = Scalar::random();
     let witness = ZKPWitness::new(instance).unwrap();
     
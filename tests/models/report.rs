@@ -0,0 +1,407 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `report` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::{Identify, Validate, ValidateReport};
+use libyobicash::utils::{Version, NetworkType, Timestamp, Amount};
+use libyobicash::crypto::{Digest, Scalar, ZKPWitness};
+use libyobicash::models::report::Rule;
+use libyobicash::models::output::Output;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use fixtures::{new_data, new_transaction, new_block, new_block_header};
+
+// asserts that a model violates a rule, and that its validation fails with the
+// first violation of its report
+fn assert_parity<T: Validate + ValidateReport>(model: &T, rule: Rule) {
+    let report = model.validate_report().unwrap();
+    assert!(report.contains(rule), "{} not in {:?}", rule, report.codes());
+
+    let violation = &report.violations[0];
+    let err = model.validate().unwrap_err();
+    assert_eq!(err.kind(), violation.rule.kind());
+    assert_eq!(err.details(), &violation.details)
+}
+
+fn future_timestamp() -> Timestamp {
+    Timestamp::from_unix(Timestamp::now().to_unix() + 1_000_000)
+}
+
+fn future_version() -> Version {
+    let mut version = Version::current().unwrap();
+    version.major += 1;
+    version
+}
+
+#[test]
+fn validation_report_succ() {
+    let data = new_data();
+    assert!(data.validate_report().unwrap().is_valid());
+
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    assert!(transaction.validate_report().unwrap().is_valid());
+    assert!(transaction.outputs[0].validate_report().unwrap().is_valid());
+    assert!(transaction.inputs[0].validate_report().unwrap().is_valid());
+
+    let block = Block::new(NetworkType::RegTest, &[transaction.clone()]).unwrap();
+    assert!(block.validate_report().unwrap().is_valid());
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let block_header = BlockHeader::new(&block, &[transaction], &genesis, witness).unwrap();
+
+    let report = block_header.validate_report().unwrap();
+    assert!(report.is_valid());
+    assert!(report.to_result().is_ok());
+    assert_eq!(report.to_string(), "")
+}
+
+#[test]
+fn validation_report_fail() {
+    let mut transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    let output = transaction.outputs[0].clone();
+    transaction.outputs.push(output);
+    transaction.fee += Amount::one();
+
+    // all the violations are reported, in order
+    let report = transaction.validate_report().unwrap();
+    assert_eq!(report.codes(), vec!["transaction-id",
+                                    "transaction-outputs-length",
+                                    "transaction-duplicate-outputs",
+                                    "transaction-outputs-amount"]);
+    assert!(transaction.validate().is_err());
+
+    let violation = &report.violations[1];
+    assert_eq!(violation.details.path(), "outputs");
+    assert_eq!(violation.details.expected, Some("1".to_string()));
+    assert_eq!(violation.details.actual, Some("2".to_string()));
+    assert_eq!(violation.to_string(), "transaction-outputs-length: Invalid length at outputs (expected 1, actual 2)");
+
+    let err = report.to_result().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidDigest);
+    assert_eq!(err.details().path(), "id");
+
+    // the amounts are not whole numbers of base units
+    let mut transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    transaction.fee = Amount::from_string("1/3").unwrap();
+    transaction.id = transaction.id().unwrap();
+
+    let report = transaction.validate_report().unwrap();
+    assert_eq!(report.codes(), vec!["transaction-fee-units"]);
+    assert_parity(&transaction, Rule::TransactionFeeUnits);
+
+    // nested violations are reported at the path of the nested model
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let mut genesis = BlockHeader::new_regtest_genesis(witness).unwrap();
+    genesis.coinbase_output.id = Digest::hash(b"output");
+    genesis.pow_nonce += 1;
+
+    let report = genesis.validate_report().unwrap();
+    assert!(report.contains(Rule::BlockHeaderId));
+    assert!(report.contains(Rule::OutputId));
+    assert!(report.contains(Rule::BlockHeaderPoW));
+    assert!(!report.contains(Rule::BlockHeaderCoinbaseAmount));
+
+    let violation = report.violations.iter().find(|v| v.rule == Rule::OutputId).unwrap();
+    assert_eq!(violation.details.path(), "coinbase_output.id")
+}
+
+#[test]
+fn validation_report_parity_output_fail() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[]);
+    let output = transaction.outputs[0].clone();
+
+    let mut invalid = output.clone();
+    invalid.id = Digest::hash(b"output");
+    assert_parity(&invalid, Rule::OutputId);
+
+    let genesis_output = Output::new_testnet_genesis().unwrap();
+    let mut invalid = genesis_output.clone();
+    invalid.amount = Amount::one();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::OutputGenesisWitness);
+
+    let mut invalid = output.clone();
+    invalid.amount = Amount::from_string("1/3").unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::OutputAmountUnits);
+
+    // an invalid proof cannot be built with the crypto constructors, which
+    // validate it
+    let input = transaction.inputs[0].clone();
+    assert!(input.validate_report().unwrap().is_valid());
+    assert!(!input.validate_report().unwrap().contains(Rule::InputProof));
+    assert!(input.validate().is_ok())
+}
+
+#[test]
+fn validation_report_parity_data_fail() {
+    let data = new_data();
+
+    let mut invalid = data.clone();
+    invalid.id = Digest::hash(b"data");
+    assert_parity(&invalid, Rule::DataId);
+
+    let mut invalid = data.clone();
+    invalid.to = invalid.from;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::DataKeys);
+
+    let mut invalid = data.clone();
+    invalid.plain_size = invalid.cyph_size + 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::DataPlainSize);
+
+    let mut invalid = data.clone();
+    invalid.cyph_size += 16;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::DataCyphSize);
+
+    let mut invalid = data.clone();
+    invalid.cyphertext.push(0);
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::DataCyphertext);
+
+    let mut invalid = data.clone();
+    invalid.duration = 0;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::DataDuration)
+}
+
+#[test]
+fn validation_report_parity_transaction_fail() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[new_data()]);
+
+    let mut invalid = transaction.clone();
+    invalid.version = future_version();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionVersion);
+
+    let mut invalid = transaction.clone();
+    invalid.timestamp = future_timestamp();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionTimestamp);
+
+    let mut invalid = transaction.clone();
+    invalid.id = Digest::hash(b"transaction");
+    assert_parity(&invalid, Rule::TransactionId);
+
+    let mut invalid = transaction.clone();
+    invalid.inputs_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionInputsLength);
+
+    let mut invalid = transaction.clone();
+    invalid.outputs_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionOutputsLength);
+
+    let mut invalid = transaction.clone();
+    invalid.data_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionDataLength);
+
+    let mut invalid = transaction.clone();
+    let input = invalid.inputs[0].clone();
+    invalid.inputs.push(input);
+    invalid.inputs_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionDuplicateInputs);
+
+    let mut invalid = transaction.clone();
+    let output = invalid.outputs[0].clone();
+    invalid.outputs_amount += &output.amount;
+    invalid.outputs.push(output);
+    invalid.outputs_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionDuplicateOutputs);
+
+    let mut invalid = transaction.clone();
+    let data_id = invalid.data_ids[0];
+    invalid.data_ids.push(data_id);
+    invalid.data_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionDuplicateData);
+
+    let mut invalid = transaction.clone();
+    invalid.outputs_amount += Amount::one();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionOutputsAmount);
+
+    let mut invalid = transaction.clone();
+    invalid.outputs_amount -= &invalid.outputs[0].amount.clone();
+    invalid.outputs[0].amount = Amount::zero();
+    invalid.outputs[0].id = invalid.outputs[0].id().unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionOutputAmount);
+
+    let mut invalid = transaction.clone();
+    let third = Amount::from_string("1/3").unwrap();
+    invalid.outputs_amount = &invalid.outputs_amount - &invalid.outputs[0].amount + &third;
+    invalid.outputs[0].amount = third;
+    invalid.outputs[0].id = invalid.outputs[0].id().unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionOutputUnits);
+
+    let mut invalid = transaction.clone();
+    invalid.fee = Amount::zero() - Amount::one();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionFee);
+
+    let mut invalid = transaction.clone();
+    invalid.fee = Amount::from_string("1/3").unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionFeeUnits);
+
+    let mut invalid = transaction.clone();
+    invalid.outputs_amount = &invalid.outputs_amount - &invalid.outputs[0].amount + Amount::genesis_value();
+    invalid.outputs[0].amount = Amount::genesis_value();
+    invalid.outputs[0].id = invalid.outputs[0].id().unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionGenesisOutput);
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let coinbase = Transaction::new_regtest_genesis(witness).unwrap();
+
+    let mut invalid = coinbase.clone();
+    invalid.data_ids.push(Digest::hash(b"data"));
+    invalid.data_length += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionCoinbase);
+
+    let mut invalid = coinbase.clone();
+    invalid.network_type = NetworkType::TestNet;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::TransactionGenesis)
+}
+
+#[test]
+fn validation_report_parity_block_fail() {
+    let (block, _) = new_block(NetworkType::RegTest, 2);
+
+    let mut invalid = block.clone();
+    invalid.version = future_version();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockVersion);
+
+    let mut invalid = block.clone();
+    invalid.timestamp = future_timestamp();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockTimestamp);
+
+    let mut invalid = block.clone();
+    invalid.id = Digest::hash(b"block");
+    assert_parity(&invalid, Rule::BlockId);
+
+    let mut invalid = block.clone();
+    invalid.transactions_size = 0;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockTransactionsSize);
+
+    let mut invalid = block.clone();
+    invalid.transactions_length = 0;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockTransactionsLength);
+
+    let mut invalid = block.clone();
+    invalid.transactions_ids.push(Digest::hash(b"transaction"));
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockTransactionsIds);
+
+    let mut invalid = block.clone();
+    invalid.transactions_ids[1] = invalid.transactions_ids[0];
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockDuplicateTransactions);
+
+    let (block, _) = new_block(NetworkType::RegTest, 1);
+    let mut invalid = block.clone();
+    invalid.network_type = NetworkType::TestNet;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockGenesis)
+}
+
+#[test]
+fn validation_report_parity_block_header_fail() {
+    let (block, transactions) = new_block(NetworkType::RegTest, 2);
+    let block_header = new_block_header(&block, &transactions);
+
+    let mut invalid = block_header.clone();
+    invalid.version = future_version();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderVersion);
+
+    let mut invalid = block_header.clone();
+    invalid.timestamp = future_timestamp();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderTimestamp);
+
+    let mut invalid = block_header.clone();
+    invalid.retarget_timestamp = Timestamp::from_unix(invalid.timestamp.to_unix() + 1);
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderRetargetTimestamp);
+
+    let mut invalid = block_header.clone();
+    invalid.id = Digest::hash(b"block_header");
+    assert_parity(&invalid, Rule::BlockHeaderId);
+
+    let mut invalid = block_header.clone();
+    invalid.prev_id = invalid.id;
+    assert_parity(&invalid, Rule::BlockHeaderPrevId);
+
+    let mut invalid = block_header.clone();
+    invalid.transactions_size = 0;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderTransactionsSize);
+
+    let mut invalid = block_header.clone();
+    invalid.transactions_length = 0;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderTransactionsLength);
+
+    let mut invalid = block_header.clone();
+    invalid.coinbase_amount = Amount::zero();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderCoinbaseAmount);
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let mut invalid = block_header.clone();
+    invalid.coinbase_output = Output::new(&(&invalid.coinbase_amount + &Amount::one()), witness).unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderCoinbaseOutput);
+
+    let mut invalid = block_header.clone();
+    invalid.pow_nonce += 1;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderPoW);
+
+    // a PoW which cannot be built is a PoW violation too
+    let mut invalid = block_header.clone();
+    invalid.pow_difficulty = 0;
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderPoW);
+
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+
+    let mut invalid = genesis.clone();
+    invalid.timestamp = Timestamp::now();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderGenesis);
+
+    let mut invalid = genesis.clone();
+    invalid.coinbase_output = Output::new(&invalid.coinbase_amount, witness).unwrap();
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderCoinbaseGenesis);
+
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let mut invalid = new_block_header(&block, &transactions);
+    invalid.prev_id = Digest::hash(b"prev_id");
+    invalid.id = invalid.id().unwrap();
+    assert_parity(&invalid, Rule::BlockHeaderNetwork)
+}
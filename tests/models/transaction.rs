@@ -7,7 +7,8 @@
 
 //! Libyobicash `transaction` module tests.
 
-use libyobicash::traits::{Validate, Serialize};
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::{Identify, Validate, Serialize};
 use libyobicash::utils::{NetworkType, Amount};
 use libyobicash::crypto::{Random, Scalar, ZKPWitness, SecretKey};
 use libyobicash::models::output::Output;
@@ -17,13 +18,13 @@ use libyobicash::models::transaction::Transaction;
 
 #[test]
 fn transaction_new_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -36,7 +37,7 @@ fn transaction_new_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -50,7 +51,7 @@ fn transaction_new_succ() {
 
 #[test]
 fn transaction_new_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
@@ -65,7 +66,7 @@ fn transaction_new_fail() {
     let mut data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     data.duration = 0;
 
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![in_output];
@@ -94,7 +95,7 @@ fn transaction_new_regtest_genesis_fail() {
 
     let mut transaction = Transaction::new_regtest_genesis(regtest_witness).unwrap();
     transaction.outputs_length += 1;
-    let amount: Amount = 10f32.into();
+    let amount = Amount::from_f32(10f32).unwrap();
     transaction.outputs_amount += amount;
 
     let res = transaction.is_genesis();
@@ -113,7 +114,7 @@ fn transaction_new_testnet_genesis_succ() {
 fn transaction_new_testnet_genesis_fail() {
     let mut transaction = Transaction::new_testnet_genesis().unwrap();
     transaction.outputs_length += 1;
-    let amount: Amount = 10f32.into();
+    let amount = Amount::from_f32(10f32).unwrap();
     transaction.outputs_amount += amount;
 
     let res = transaction.is_genesis();
@@ -132,7 +133,7 @@ fn transaction_new_mainnet_genesis_new_succ() {
 fn transaction_new_mainnet_genesis_fail() {
     let mut transaction = Transaction::new_mainnet_genesis().unwrap();
     transaction.outputs_length += 1;
-    let amount: Amount = 10f32.into();
+    let amount = Amount::from_f32(10f32).unwrap();
     transaction.outputs_amount += amount;
 
     let res = transaction.is_genesis();
@@ -141,13 +142,13 @@ fn transaction_new_mainnet_genesis_fail() {
 
 #[test]
 fn transaction_validate_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -160,7 +161,7 @@ fn transaction_validate_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -176,13 +177,13 @@ fn transaction_validate_succ() {
 
 #[test]
 fn transaction_validate_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -195,7 +196,7 @@ fn transaction_validate_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -205,21 +206,71 @@ fn transaction_validate_fail() {
     
     let mut transaction = Transaction::new(network_type, &coins, &outputs, &ds, &fee).unwrap();
 
+    let mut longer = transaction.clone();
+    longer.outputs.push(longer.outputs[0].clone());
+    longer.id = longer.id().unwrap();
+
+    let err = longer.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    assert_eq!(err.details().path(), "outputs");
+    assert_eq!(err.details().expected, Some("1".to_string()));
+    assert_eq!(err.details().actual, Some("2".to_string()));
+
+    // the declared outputs amount is not the sum of the outputs
+    let mut inflated = transaction.clone();
+    inflated.outputs_amount = Amount::zero();
+    inflated.fee = Amount::from_f64(10.0).unwrap();
+    inflated.id = inflated.id().unwrap();
+
+    let err = inflated.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBalance);
+    assert_eq!(err.details().path(), "outputs_amount");
+    assert_eq!(err.details().expected, Some("8".to_string()));
+
+    let mut negative_fee = transaction.clone();
+    negative_fee.fee = Amount::zero() - Amount::one();
+    negative_fee.id = negative_fee.id().unwrap();
+
+    let err = negative_fee.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::OutOfBound);
+    assert_eq!(err.details().path(), "fee");
+
+    // the amounts are not whole numbers of base units
+    let third = Amount::from_string("1/3").unwrap();
+
+    let mut fractional_output = transaction.clone();
+    fractional_output.outputs[0] = Output::new(&third, out_witness).unwrap();
+    fractional_output.outputs_amount = third.clone();
+    fractional_output.id = fractional_output.id().unwrap();
+
+    let err = fractional_output.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+    assert_eq!(err.details().path(), "outputs[0]");
+
+    let mut fractional_fee = transaction.clone();
+    fractional_fee.fee = third;
+    fractional_fee.id = fractional_fee.id().unwrap();
+
+    let err = fractional_fee.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+    assert_eq!(err.details().path(), "fee");
+
     transaction.fee += Amount::one();
 
-    let res = transaction.validate();
-    assert!(res.is_err())
+    let err = transaction.validate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidDigest);
+    assert_eq!(err.details().path(), "id")
 }
 
 #[test]
 fn transaction_to_json_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -232,7 +283,7 @@ fn transaction_to_json_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -250,13 +301,13 @@ fn transaction_to_json_succ() {
 
 #[test]
 fn transaction_to_json_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -269,7 +320,7 @@ fn transaction_to_json_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -288,13 +339,13 @@ fn transaction_to_json_fail() {
 
 #[test]
 fn transaction_to_bytes_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -307,7 +358,7 @@ fn transaction_to_bytes_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -324,13 +375,13 @@ fn transaction_to_bytes_succ() {
 
 #[test]
 fn transaction_to_bytes_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -343,7 +394,7 @@ fn transaction_to_bytes_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -362,13 +413,13 @@ fn transaction_to_bytes_fail() {
 
 #[test]
 fn transaction_to_hex_succ() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -381,7 +432,7 @@ fn transaction_to_hex_succ() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
@@ -398,13 +449,13 @@ fn transaction_to_hex_succ() {
 
 #[test]
 fn transaction_to_hex_fail() {
-    let in_amount = Amount::from(10.0);
+    let in_amount = Amount::from_f64(10.0).unwrap();
     let in_instance = Scalar::random();
     let in_witness = ZKPWitness::new(in_instance).unwrap();
     let in_output = Output::new(&in_amount, in_witness).unwrap();
     let in_coin = Coin::new(&in_output, in_instance).unwrap();
 
-    let out_amount = Amount::from(8.0);
+    let out_amount = Amount::from_f64(8.0).unwrap();
     let out_instance = Scalar::random();
     let out_witness = ZKPWitness::new(out_instance).unwrap();
     let out_output = Output::new(&out_amount, out_witness).unwrap();
@@ -417,7 +468,7 @@ fn transaction_to_hex_fail() {
     let plain = Random::bytes(len);
     let data = Data::new(sk_a, pk_b, dur, &plain).unwrap();
     
-    let fee = Amount::from(2.0);
+    let fee = Amount::from_f64(2.0).unwrap();
     
     let coins = vec![in_coin];
     let outputs = vec![out_output];
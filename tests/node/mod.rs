@@ -0,0 +1,454 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `node` module tests.
+
+mod peer;
+mod sync;
+
+use futures::Future;
+
+use libyobicash::utils::{NetworkType, Version};
+use libyobicash::crypto::{Random, Scalar, ZKPWitness};
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::models::compact_block::CompactBlock;
+use libyobicash::models::block_filter::BlockFilter;
+use libyobicash::mining::miner::{Miner, MiningJob};
+use libyobicash::store::MemoryStore;
+use libyobicash::protocol::{Message, Handshake, InventoryKind, InventoryItem};
+use libyobicash::protocol::{GetBlockTransactions, BlockTransactions};
+use libyobicash::node::{Node, NodeHandle, PeerConnection};
+use fixtures::{new_transaction, new_block, new_block_header};
+
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+fn spawn_node() -> NodeHandle {
+    let node = Node::new(NetworkType::TestNet, MemoryStore::new()).unwrap();
+    let handle = node.handle();
+
+    thread::spawn(move || node.wait());
+
+    handle
+}
+
+// completes the handshake of a peer already added to a node
+fn handshake(mut peer: PeerConnection, handshake: Handshake) -> PeerConnection {
+    match peer.recv() {
+        Some(Message::Handshake(node_handshake)) => {
+            assert_eq!(node_handshake.network_type, NetworkType::TestNet);
+        },
+        _ => panic!("expected a handshake"),
+    }
+
+    peer.send(Message::Handshake(handshake)).unwrap();
+    peer
+}
+
+fn connect_peer(handle: &NodeHandle) -> PeerConnection {
+    let (peer, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+
+    handshake(peer, Handshake::new(NetworkType::TestNet, 0).unwrap())
+}
+
+#[test]
+fn node_handshake_succ() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    peer.send(Message::Ping(7)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::Pong(7)));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_handshake_fail() {
+    let handle = spawn_node();
+
+    let (peer, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+    let mut peer = handshake(peer, Handshake::new(NetworkType::MainNet, 0).unwrap());
+    assert_eq!(peer.recv(), None);
+
+    let mut incompatible = Handshake::new(NetworkType::TestNet, 0).unwrap();
+    incompatible.version = Version::min_value().unwrap();
+
+    let (peer, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+    let mut peer = handshake(peer, incompatible);
+    assert_eq!(peer.recv(), None);
+
+    // messages before the handshake are not accepted
+    let (mut peer, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+    peer.send(Message::Ping(7)).unwrap();
+    assert!(peer.recv().is_some());
+    assert_eq!(peer.recv(), None);
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_relay_transaction_succ() {
+    let handle = spawn_node();
+    let peer_a = connect_peer(&handle);
+    let mut peer_b = connect_peer(&handle);
+
+    // the pong assures that the node has completed the handshake with peer_b
+    peer_b.send(Message::Ping(1)).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Pong(1)));
+
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+    peer_a.send(Message::Transaction(transaction.clone())).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Transaction(transaction.clone())));
+
+    peer_b.send(Message::GetTransactions(vec![transaction.id])).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Transaction(transaction.clone())));
+
+    let broadcasted = new_transaction(NetworkType::TestNet, 2.0, &[]);
+    handle.broadcast(Message::Transaction(broadcasted.clone())).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Transaction(broadcasted)));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_relay_transaction_fail() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    let transaction = new_transaction(NetworkType::MainNet, 2.0, &[]);
+    peer.send(Message::Transaction(transaction)).unwrap();
+    assert_eq!(peer.recv(), None);
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_relay_between_nodes_succ() {
+    let handle_a = spawn_node();
+    let handle_b = spawn_node();
+
+    let (link_a, link_b) = PeerConnection::loopback();
+    handle_a.add_peer(link_a).unwrap();
+    handle_b.add_peer(link_b).unwrap();
+
+    // the nodes send their handshakes to each other before the ones to the peers,
+    // so they are handled before the messages of the peers
+    let peer_a = connect_peer(&handle_a);
+    let mut peer_b = connect_peer(&handle_b);
+
+    // the pong assures that node b has completed the handshake with peer_b
+    peer_b.send(Message::Ping(1)).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Pong(1)));
+
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+    peer_a.send(Message::Transaction(transaction.clone())).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Transaction(transaction)));
+
+    handle_a.shutdown().unwrap();
+    handle_b.shutdown().unwrap()
+}
+
+#[test]
+fn node_inventory_succ() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+    let item = InventoryItem::new(InventoryKind::Transaction, transaction.id);
+
+    peer.send(Message::Inventory(vec![item])).unwrap();
+    assert_eq!(peer.recv(), Some(Message::GetTransactions(vec![transaction.id])));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_compact_block_succ() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let block_header = new_block_header(&block, &transactions);
+    let transaction = transactions[1].clone();
+
+    // an unknown compact block is requested in full
+    let compact_block = CompactBlock::new(&block, Random::u64()).unwrap();
+    peer.send(Message::CompactBlock(compact_block)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::GetBlock(block.id)));
+
+    peer.send(Message::Headers(vec![genesis, block_header])).unwrap();
+
+    for transaction in &transactions {
+        peer.send(Message::Transaction(transaction.clone())).unwrap();
+    }
+
+    peer.send(Message::Block(block.clone())).unwrap();
+
+    let get_block_transactions = GetBlockTransactions {
+        block_id: block.id,
+        indexes: vec![1],
+    };
+
+    let block_transactions = BlockTransactions {
+        block_id: block.id,
+        transactions: vec![transaction],
+    };
+
+    peer.send(Message::GetBlockTransactions(get_block_transactions)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::BlockTransactions(block_transactions)));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_compact_block_fail() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let block_header = new_block_header(&block, &transactions);
+
+    let mut get_block_transactions = GetBlockTransactions {
+        block_id: block.id,
+        indexes: vec![1],
+    };
+
+    let item = InventoryItem::new(InventoryKind::Block, block.id);
+
+    peer.send(Message::GetBlockTransactions(get_block_transactions.clone())).unwrap();
+    assert_eq!(peer.recv(), Some(Message::NotFound(vec![item])));
+
+    peer.send(Message::Headers(vec![genesis, block_header])).unwrap();
+
+    for transaction in transactions {
+        peer.send(Message::Transaction(transaction)).unwrap();
+    }
+
+    peer.send(Message::Block(block)).unwrap();
+
+    get_block_transactions.indexes = vec![2];
+    peer.send(Message::GetBlockTransactions(get_block_transactions)).unwrap();
+    assert_eq!(peer.recv(), None);
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_block_succ() {
+    let handle = spawn_node();
+    let peer_a = connect_peer(&handle);
+    let mut peer_b = connect_peer(&handle);
+
+    peer_b.send(Message::Ping(1)).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Pong(1)));
+
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let block_headers = vec![genesis, new_block_header(&block, &transactions)];
+
+    peer_a.send(Message::Headers(block_headers.clone())).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Headers(block_headers)));
+
+    for transaction in transactions {
+        peer_a.send(Message::Transaction(transaction.clone())).unwrap();
+        assert_eq!(peer_b.recv(), Some(Message::Transaction(transaction)));
+    }
+
+    peer_a.send(Message::Block(block.clone())).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Block(block)));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_block_fail() {
+    let handle = spawn_node();
+
+    let (block, transactions) = new_block(NetworkType::TestNet, 2);
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let block_header = new_block_header(&block, &transactions);
+
+    // a block without a stored block header is not accepted
+    let mut peer = connect_peer(&handle);
+    peer.send(Message::Block(block.clone())).unwrap();
+    assert_eq!(peer.recv(), None);
+
+    // the missing transactions of a block are requested
+    let mut peer = connect_peer(&handle);
+    peer.send(Message::Headers(vec![genesis, block_header])).unwrap();
+    peer.send(Message::Transaction(transactions[0].clone())).unwrap();
+    peer.send(Message::Block(block.clone())).unwrap();
+    assert_eq!(peer.recv(), Some(Message::GetTransactions(vec![transactions[1].id])));
+
+    peer.send(Message::GetBlock(block.id)).unwrap();
+    let item = InventoryItem::new(InventoryKind::Block, block.id);
+    assert_eq!(peer.recv(), Some(Message::NotFound(vec![item])));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_block_filter_succ() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    let transactions = vec![new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])];
+    let block = Block::new(NetworkType::TestNet, &transactions).unwrap();
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let block_header = new_block_header(&block, &transactions);
+
+    peer.send(Message::Headers(vec![genesis, block_header.clone()])).unwrap();
+
+    for transaction in &transactions {
+        peer.send(Message::Transaction(transaction.clone())).unwrap();
+    }
+
+    peer.send(Message::Block(block.clone())).unwrap();
+    peer.send(Message::GetBlockFilter(block_header.id)).unwrap();
+
+    let block_filter = BlockFilter::new(&block_header, &block, &transactions).unwrap();
+    assert_eq!(peer.recv(), Some(Message::BlockFilter(block_filter)));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_block_filter_fail() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    let transactions = vec![new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])];
+    let block = Block::new(NetworkType::TestNet, &transactions).unwrap();
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let block_header = new_block_header(&block, &transactions);
+    let item = InventoryItem::new(InventoryKind::BlockHeader, block_header.id);
+
+    peer.send(Message::GetBlockFilter(block_header.id)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::NotFound(vec![item])));
+
+    // the block is not stored
+    peer.send(Message::Headers(vec![genesis, block_header.clone()])).unwrap();
+    peer.send(Message::GetBlockFilter(block_header.id)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::NotFound(vec![item])));
+
+    // the block is not stored without its transactions
+    peer.send(Message::Block(block.clone())).unwrap();
+    assert_eq!(peer.recv(), Some(Message::GetTransactions(block.transactions_ids.clone())));
+
+    peer.send(Message::GetBlockFilter(block_header.id)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::NotFound(vec![item])));
+
+    handle.shutdown().unwrap()
+}
+
+// returns the testnet genesis block header and a block header following it
+fn new_block_headers() -> (BlockHeader, BlockHeader) {
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+
+    let transactions = vec![new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])];
+    let block = Block::new(NetworkType::TestNet, &transactions).unwrap();
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let block_header = BlockHeader::new(&block, &transactions, &genesis, witness).unwrap();
+
+    (genesis, block_header)
+}
+
+// returns the height in the handshake sent by a node to a new peer
+fn handshake_height(handle: &NodeHandle) -> u32 {
+    let (mut peer, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+
+    match peer.recv() {
+        Some(Message::Handshake(handshake)) => handshake.height,
+        _ => panic!("expected a handshake"),
+    }
+}
+
+#[test]
+fn node_headers_succ() {
+    let handle = spawn_node();
+    let peer_a = connect_peer(&handle);
+    let mut peer_b = connect_peer(&handle);
+
+    peer_b.send(Message::Ping(1)).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Pong(1)));
+
+    let (genesis, block_header) = new_block_headers();
+    let block_headers = vec![genesis, block_header];
+
+    peer_a.send(Message::Headers(block_headers.clone())).unwrap();
+    assert_eq!(peer_b.recv(), Some(Message::Headers(block_headers)));
+    assert_eq!(handshake_height(&handle), 1);
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_headers_fail() {
+    let handle = spawn_node();
+    let mut peer = connect_peer(&handle);
+
+    // a block header without a stored parent is not stored nor counted in the height
+    let (_, block_header) = new_block_headers();
+    peer.send(Message::Headers(vec![block_header])).unwrap();
+    assert_eq!(peer.recv(), None);
+    assert_eq!(handshake_height(&handle), 0);
+
+    // a block header declaring more difficulty than its retarget
+    let genesis = BlockHeader::new_testnet_genesis().unwrap();
+    let transactions = vec![new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])];
+    let block = Block::new(NetworkType::TestNet, &transactions).unwrap();
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let mut job = MiningJob::new(&block, &transactions, &genesis, witness).unwrap();
+    job.block_header.pow_difficulty += 1;
+    let block_header = Miner::new(2).unwrap().mine(&mut job, |_| {}).unwrap().unwrap();
+
+    let mut peer = connect_peer(&handle);
+    peer.send(Message::Headers(vec![genesis, block_header])).unwrap();
+    assert_eq!(peer.recv(), None);
+    assert_eq!(handshake_height(&handle), 0);
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_listen_succ() {
+    let handle = spawn_node();
+    let address = handle.listen_on(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+
+    let stream = TcpStream::connect(address).unwrap();
+    let peer = PeerConnection::from_tcp(stream).unwrap();
+    let mut peer = handshake(peer, Handshake::new(NetworkType::TestNet, 0).unwrap());
+
+    peer.send(Message::Ping(7)).unwrap();
+    assert_eq!(peer.recv(), Some(Message::Pong(7)));
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn node_listen_fail() {
+    let handle = spawn_node();
+    let address = handle.listen_on(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+
+    let res = handle.listen_on(address);
+    assert!(res.is_err());
+
+    handle.shutdown().unwrap();
+
+    // the node stops once it handles the shutdown
+    while handle.add_peer(PeerConnection::loopback().0).is_ok() {
+        thread::yield_now();
+    }
+}
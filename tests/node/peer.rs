@@ -0,0 +1,69 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `peer` module tests.
+
+use libyobicash::node::PeerConnection;
+use libyobicash::protocol::Message;
+
+use std::net::{TcpListener, TcpStream};
+
+#[test]
+fn peer_connection_loopback_succ() {
+    let (a, mut b) = PeerConnection::loopback();
+
+    a.send(Message::Ping(1)).unwrap();
+    a.send(Message::Pong(2)).unwrap();
+
+    assert_eq!(b.recv(), Some(Message::Ping(1)));
+    assert_eq!(b.recv(), Some(Message::Pong(2)))
+}
+
+#[test]
+fn peer_connection_loopback_fail() {
+    let (a, mut b) = PeerConnection::loopback();
+    drop(a);
+
+    assert_eq!(b.recv(), None);
+
+    let res = b.send(Message::Ping(1));
+    assert!(res.is_err())
+}
+
+#[test]
+fn peer_connection_from_tcp_succ() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let stream_a = TcpStream::connect(address).unwrap();
+    let (stream_b, _) = listener.accept().unwrap();
+
+    let a = PeerConnection::from_tcp(stream_a).unwrap();
+    let mut b = PeerConnection::from_tcp(stream_b).unwrap();
+
+    a.send(Message::Ping(1)).unwrap();
+    a.send(Message::GetTransactions(vec![])).unwrap();
+
+    assert_eq!(b.recv(), Some(Message::Ping(1)));
+    assert_eq!(b.recv(), Some(Message::GetTransactions(vec![])))
+}
+
+#[test]
+fn peer_connection_from_tcp_fail() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let stream_a = TcpStream::connect(address).unwrap();
+    let (stream_b, _) = listener.accept().unwrap();
+
+    let a = PeerConnection::from_tcp(stream_a).unwrap();
+    let mut b = PeerConnection::from_tcp(stream_b).unwrap();
+
+    drop(a);
+
+    assert_eq!(b.recv(), None)
+}
@@ -0,0 +1,321 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `sync` module tests.
+
+use futures::Future;
+
+use libyobicash::error::ErrorKind;
+use libyobicash::result::Result;
+use libyobicash::traits::Identify;
+use libyobicash::constants::MAX_IGNORED_MESSAGES;
+use libyobicash::utils::{Version, NetworkType, Timestamp};
+use libyobicash::crypto::{Digest, Scalar, ZKPWitness};
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::mining::miner::{Miner, MiningJob};
+use libyobicash::store::{Store, MemoryStore};
+use libyobicash::protocol::{Message, Handshake};
+use libyobicash::node::{Node, PeerConnection, SyncEngine, SyncState, SyncTransport, PeerTransport};
+use fixtures::{new_block, regtest_genesis};
+
+use std::thread;
+
+// mines a block header with a timestamp above the previous one, and so above the
+// median of the previous ones
+fn mine_block_header(block: &Block,
+                     transactions: &[Transaction],
+                     prev_block_header: &BlockHeader,
+                     witness: ZKPWitness) -> BlockHeader {
+    let mut job = MiningJob::new(block, transactions, prev_block_header, witness).unwrap();
+
+    let timestamp = Timestamp::from_unix(prev_block_header.timestamp.to_unix() + 1);
+    job.block_header.timestamp = timestamp.max(block.timestamp);
+
+    Miner::new(1).unwrap().mine(&mut job, |_| {}).unwrap().unwrap()
+}
+
+// returns a store with a regtest chain of a given length, and its genesis
+fn new_chain(length: u32) -> (MemoryStore, BlockHeader) {
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let (genesis, genesis_block, genesis_transaction) = regtest_genesis(witness);
+
+    let mut store = MemoryStore::new();
+    store.put_block_header(&genesis).unwrap();
+    store.put_block(&genesis_block).unwrap();
+    store.put_transaction(&genesis_transaction).unwrap();
+
+    let mut prev_block_header = genesis.clone();
+
+    for _ in 1..length {
+        let (block, transactions) = new_block(NetworkType::RegTest, 1);
+        let block_header = mine_block_header(&block, &transactions, &prev_block_header, witness);
+
+        store.put_block_header(&block_header).unwrap();
+        store.put_block(&block).unwrap();
+        store.put_transaction(&transactions[0]).unwrap();
+
+        prev_block_header = block_header;
+    }
+
+    (store, genesis)
+}
+
+// a peer serving the items of a store, failing after a number of requests
+struct MockPeer {
+    store: MemoryStore,
+    requests: u32,
+    max_requests: u32,
+}
+
+impl MockPeer {
+    fn new(store: &MemoryStore) -> MockPeer {
+        MockPeer {
+            store: store.clone(),
+            requests: 0,
+            max_requests: u32::MAX,
+        }
+    }
+
+    fn request(&mut self) -> Result<()> {
+        if self.requests == self.max_requests {
+            return Err(ErrorKind::Disconnected.into());
+        }
+
+        self.requests += 1;
+
+        Ok(())
+    }
+}
+
+impl SyncTransport for MockPeer {
+    fn get_headers(&mut self, locator: &[Digest]) -> Result<Vec<BlockHeader>> {
+        self.request()?;
+
+        self.store.get_block_headers_by_prev_id(locator[0])
+    }
+
+    fn get_block(&mut self, id: Digest) -> Result<Block> {
+        self.request()?;
+
+        self.store.get_block(id)
+    }
+
+    fn get_transactions(&mut self, ids: &[Digest]) -> Result<Vec<Transaction>> {
+        self.request()?;
+
+        ids.iter().map(|id| self.store.get_transaction(*id)).collect()
+    }
+}
+
+fn assert_synced(store: &MemoryStore, chain_store: &MemoryStore, genesis: &BlockHeader, length: u32) {
+    let mut prev_id = genesis.id;
+
+    for height in 0..length {
+        let block_header = chain_store.get_block_headers_by_height(height).unwrap().pop().unwrap();
+        assert_eq!(store.get_block_header(block_header.id).unwrap(), block_header);
+
+        let block = store.get_block(block_header.block_id).unwrap();
+
+        for id in &block.transactions_ids {
+            assert!(store.has_transaction(*id).unwrap());
+        }
+
+        if height > 0 {
+            assert_eq!(block_header.prev_id, prev_id);
+        }
+
+        prev_id = block_header.id;
+    }
+}
+
+#[test]
+fn sync_engine_run_succ() {
+    let length = 3;
+    let (chain_store, genesis) = new_chain(length);
+
+    let mut engine = SyncEngine::new(&genesis).unwrap();
+    assert_eq!(engine.state(), SyncState::Headers);
+
+    let mut peer = MockPeer::new(&chain_store);
+    let mut store = MemoryStore::new();
+
+    let res = engine.run(&mut peer, &mut store);
+    assert!(res.is_ok());
+
+    assert_eq!(engine.state(), SyncState::Done);
+    assert_eq!(engine.height(), length - 1);
+    assert_eq!(engine.progress().blocks_length, length);
+
+    assert_synced(&store, &chain_store, &genesis, length)
+}
+
+#[test]
+fn sync_engine_run_fail() {
+    let length = 2;
+    let (chain_store, genesis) = new_chain(length);
+
+    let block_header = chain_store.get_block_headers_by_height(1).unwrap().pop().unwrap();
+
+    // a block header tampered after being mined
+    let (other_block, _) = new_block(NetworkType::RegTest, 1);
+    let mut tampered_header = block_header.clone();
+    tampered_header.block_id = other_block.id;
+    tampered_header.id = tampered_header.id().unwrap();
+
+    let mut tampered_store = MemoryStore::new();
+    tampered_store.put_block_header(&genesis).unwrap();
+    tampered_store.put_block_header(&tampered_header).unwrap();
+
+    // the tampered block header does not verify against the previous one
+    let mut engine = SyncEngine::new(&genesis).unwrap();
+    let mut peer = MockPeer::new(&tampered_store);
+    let res = engine.run(&mut peer, &mut MemoryStore::new());
+    assert!(res.is_err());
+    assert_eq!(engine.height(), 0);
+
+    // a block missing from the peer
+    let mut missing_store = MemoryStore::new();
+    missing_store.put_block_header(&genesis).unwrap();
+    missing_store.put_block_header(&block_header).unwrap();
+
+    let mut engine = SyncEngine::new(&genesis).unwrap();
+    let mut peer = MockPeer::new(&missing_store);
+    let res = engine.run(&mut peer, &mut MemoryStore::new());
+    assert!(res.is_err());
+    assert_eq!(engine.state(), SyncState::Blocks);
+
+    let res = SyncEngine::new(&block_header);
+    assert!(res.is_err())
+}
+
+#[test]
+fn sync_engine_resume_succ() {
+    let length = 3;
+    let (chain_store, genesis) = new_chain(length);
+
+    let mut engine = SyncEngine::new(&genesis).unwrap();
+    let mut store = MemoryStore::new();
+
+    // headers: 3 requests, blocks: 2 requests each
+    let mut peer = MockPeer::new(&chain_store);
+    peer.max_requests = 5;
+
+    let res = engine.run(&mut peer, &mut store);
+    assert!(res.is_err());
+    assert_eq!(engine.state(), SyncState::Blocks);
+    assert_eq!(engine.progress().blocks_length, 1);
+
+    let progress = engine.progress().clone();
+
+    let mut engine = SyncEngine::resume(progress).unwrap();
+    let mut peer = MockPeer::new(&chain_store);
+
+    let res = engine.run(&mut peer, &mut store);
+    assert!(res.is_ok());
+    assert_eq!(peer.requests, 2 * (length - 1));
+
+    assert_synced(&store, &chain_store, &genesis, length)
+}
+
+#[test]
+fn sync_engine_resume_fail() {
+    let (_, genesis) = new_chain(1);
+
+    let mut progress = SyncEngine::new(&genesis).unwrap().progress().clone();
+    progress.blocks_length = 2;
+
+    let res = SyncEngine::resume(progress.clone());
+    assert!(res.is_err());
+
+    progress.block_headers.clear();
+    progress.blocks_length = 0;
+
+    let res = SyncEngine::resume(progress);
+    assert!(res.is_err())
+}
+
+#[test]
+fn peer_transport_succ() {
+    let length = 2;
+    let (chain_store, genesis) = new_chain(length);
+
+    let node = Node::new(NetworkType::RegTest, chain_store.clone()).unwrap();
+    let handle = node.handle();
+    thread::spawn(move || node.wait());
+
+    let (connection, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+
+    let mut transport = PeerTransport::new(connection, NetworkType::RegTest, 0).unwrap();
+
+    let mut engine = SyncEngine::new(&genesis).unwrap();
+    let mut store = MemoryStore::new();
+
+    let res = engine.run(&mut transport, &mut store);
+    assert!(res.is_ok());
+
+    assert_synced(&store, &chain_store, &genesis, length);
+
+    handle.shutdown().unwrap()
+}
+
+#[test]
+fn peer_transport_fail() {
+    let (chain_store, genesis) = new_chain(2);
+
+    let mut headers_store = MemoryStore::new();
+
+    for height in 0..2 {
+        let block_header = chain_store.get_block_headers_by_height(height).unwrap().pop().unwrap();
+        headers_store.put_block_header(&block_header).unwrap();
+    }
+
+    let node = Node::new(NetworkType::RegTest, headers_store).unwrap();
+    let handle = node.handle();
+    thread::spawn(move || node.wait());
+
+    let (connection, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+    let res = PeerTransport::new(connection, NetworkType::TestNet, 0);
+    assert!(res.is_err());
+
+    // a peer with an incompatible version
+    let (connection, remote) = PeerConnection::loopback();
+    let mut incompatible = Handshake::new(NetworkType::RegTest, 0).unwrap();
+    incompatible.version = Version::min_value().unwrap();
+    remote.send(Message::Handshake(incompatible)).unwrap();
+
+    let res = PeerTransport::new(connection, NetworkType::RegTest, 0);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidVersion);
+
+    // a peer sending only unrelated messages
+    let (connection, remote) = PeerConnection::loopback();
+    remote.send(Message::Handshake(Handshake::new(NetworkType::RegTest, 0).unwrap())).unwrap();
+
+    for nonce in 0..MAX_IGNORED_MESSAGES as u64 + 1 {
+        remote.send(Message::Pong(nonce)).unwrap();
+    }
+
+    let mut transport = PeerTransport::new(connection, NetworkType::RegTest, 0).unwrap();
+    let res = transport.get_headers(&[genesis.id]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    let (connection, remote) = PeerConnection::loopback();
+    handle.add_peer(remote).unwrap();
+    let mut transport = PeerTransport::new(connection, NetworkType::RegTest, 0).unwrap();
+
+    // the node has the block headers, but not the blocks
+    let mut engine = SyncEngine::new(&genesis).unwrap();
+    let res = engine.run(&mut transport, &mut MemoryStore::new());
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(engine.state(), SyncState::Blocks);
+    assert_eq!(engine.height(), 1);
+
+    handle.shutdown().unwrap()
+}
@@ -0,0 +1,268 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `message` module tests.
+
+use libyobicash::constants::{PROTOCOL_VERSION, MAX_MESSAGE_SIZE, ENCODING_VERSION};
+use libyobicash::constants::{MAX_HEADERS_LENGTH, MAX_INVENTORY_LENGTH, MAX_LOCATOR_LENGTH};
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::{BinarySerialize, Serialize};
+use libyobicash::utils::{NetworkType, Version};
+use libyobicash::crypto::{Random, Digest};
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::models::compact_block::CompactBlock;
+use libyobicash::models::block_filter::BlockFilter;
+use libyobicash::protocol::{Message, MessageKind, MessageHeader, MESSAGE_HEADER_LENGTH};
+use libyobicash::protocol::{Handshake, GetHeaders, GetBlockTransactions, BlockTransactions};
+use libyobicash::protocol::{InventoryKind, InventoryItem};
+use fixtures::{new_data, new_transaction};
+
+use std::io::Cursor;
+
+fn new_messages() -> Vec<Message> {
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    let block = Block::new_testnet_genesis().unwrap();
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+    let data = new_data();
+
+    let get_headers = GetHeaders {
+        locator: vec![block_header.id],
+        stop_id: Digest::hash(b"stop"),
+    };
+
+    let compact_block = CompactBlock::new(&block, Random::u64()).unwrap();
+    let genesis_transaction = Transaction::new_testnet_genesis().unwrap();
+    let block_filter = BlockFilter::new(&block_header, &block, &[genesis_transaction]).unwrap();
+
+    let get_block_transactions = GetBlockTransactions {
+        block_id: block.id,
+        indexes: vec![0],
+    };
+
+    let block_transactions = BlockTransactions {
+        block_id: block.id,
+        transactions: vec![transaction.clone()],
+    };
+
+    let items = vec![
+        InventoryItem::new(InventoryKind::BlockHeader, block_header.id),
+        InventoryItem::new(InventoryKind::Transaction, transaction.id),
+    ];
+
+    vec![
+        Message::Handshake(Handshake::new(NetworkType::TestNet, 10).unwrap()),
+        Message::Ping(Random::u64()),
+        Message::Pong(Random::u64()),
+        Message::GetHeaders(get_headers),
+        Message::Headers(vec![block_header.clone()]),
+        Message::GetBlock(block.id),
+        Message::Block(block),
+        Message::GetTransactions(vec![transaction.id]),
+        Message::Transaction(transaction),
+        Message::GetData(vec![data.id]),
+        Message::Data(data),
+        Message::Inventory(items.clone()),
+        Message::NotFound(items),
+        Message::CompactBlock(compact_block),
+        Message::GetBlockTransactions(get_block_transactions),
+        Message::BlockTransactions(block_transactions),
+        Message::GetBlockFilter(block_header.id),
+        Message::BlockFilter(block_filter),
+    ]
+}
+
+#[test]
+fn message_header_from_bytes_succ() {
+    let header = MessageHeader::new(MessageKind::Headers, 100).unwrap();
+    let buf = header.to_bytes().unwrap();
+    assert_eq!(buf.len(), MESSAGE_HEADER_LENGTH);
+
+    let res = MessageHeader::from_bytes(&buf);
+    assert!(res.is_ok());
+    assert_eq!(res.unwrap(), header)
+}
+
+#[test]
+fn message_header_from_bytes_fail() {
+    let res = MessageHeader::new(MessageKind::Block, MAX_MESSAGE_SIZE + 1);
+    assert!(res.is_err());
+
+    let header = MessageHeader::new(MessageKind::Block, 100).unwrap();
+    let buf = header.to_bytes().unwrap();
+
+    let res = MessageHeader::from_bytes(&buf[1..]);
+    assert!(res.is_err());
+
+    let mut wrong_version = buf.clone();
+    wrong_version[3] = (PROTOCOL_VERSION + 1) as u8;
+    let res = MessageHeader::from_bytes(&wrong_version);
+    assert!(res.is_err());
+
+    let mut wrong_kind = buf.clone();
+    wrong_kind[4] = 255;
+    let res = MessageHeader::from_bytes(&wrong_kind);
+    assert!(res.is_err());
+
+    let mut wrong_length = buf.clone();
+    wrong_length[5] = 255;
+    let res = MessageHeader::from_bytes(&wrong_length);
+    assert!(res.is_err())
+}
+
+#[test]
+fn message_to_bytes_succ() {
+    for message in new_messages() {
+        let buf = message.to_bytes().unwrap();
+        assert_eq!(MessageKind::from_u8(buf[4]).unwrap(), message.kind());
+
+        let res = Message::from_bytes(&buf);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), message)
+    }
+
+    // the payloads are the canonical encoding hashed in the ids
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+    let buf = Message::Transaction(transaction.clone()).to_bytes().unwrap();
+    assert_eq!(&buf[MESSAGE_HEADER_LENGTH..], &transaction.to_bytes().unwrap()[..]);
+
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    let buf = Message::Headers(vec![block_header.clone()]).to_bytes().unwrap();
+    assert_eq!(buf[MESSAGE_HEADER_LENGTH], ENCODING_VERSION);
+    assert_eq!(&buf[MESSAGE_HEADER_LENGTH + 5..], &block_header.to_bytes().unwrap()[1..])
+}
+
+#[test]
+fn message_to_bytes_fail() {
+    let headers = vec![BlockHeader::default(); MAX_HEADERS_LENGTH as usize + 1];
+    let res = Message::Headers(headers).to_bytes();
+    assert!(res.is_err());
+
+    let ids = vec![Digest::default(); MAX_INVENTORY_LENGTH as usize + 1];
+    let res = Message::GetTransactions(ids).to_bytes();
+    assert!(res.is_err());
+
+    let get_headers = GetHeaders {
+        locator: vec![Digest::default(); MAX_LOCATOR_LENGTH as usize + 1],
+        stop_id: Digest::default(),
+    };
+    let res = Message::GetHeaders(get_headers).to_bytes();
+    assert!(res.is_err());
+
+    let mut handshake = Handshake::new(NetworkType::TestNet, 0).unwrap();
+    handshake.version = Version::from_parts(1000, 0, 0, "", "").unwrap();
+    let res = Message::Handshake(handshake).to_bytes();
+    assert!(res.is_err())
+}
+
+#[test]
+fn message_from_bytes_fail() {
+    let message = Message::Transaction(new_transaction(NetworkType::TestNet, 2.0, &[]));
+    let buf = message.to_bytes().unwrap();
+
+    let res = Message::from_bytes(&buf[..buf.len() - 1]);
+    assert!(res.is_err());
+
+    let mut longer = buf.clone();
+    longer.push(0);
+    let res = Message::from_bytes(&longer);
+    assert!(res.is_err());
+
+    // a payload of a different kind
+    let mut wrong_kind = buf.clone();
+    wrong_kind[4] = MessageKind::Block as u8;
+    let res = Message::from_bytes(&wrong_kind);
+    assert!(res.is_err());
+
+    let res = Message::from_bytes(&buf[..MESSAGE_HEADER_LENGTH - 1]);
+    assert!(res.is_err())
+}
+
+#[test]
+fn message_read_from_succ() {
+    let messages = new_messages();
+    let mut buf = Vec::new();
+
+    for message in &messages {
+        message.write_to(&mut buf).unwrap();
+    }
+
+    let mut reader = Cursor::new(buf);
+
+    for message in messages {
+        let res = Message::read_from(&mut reader);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), message)
+    }
+}
+
+#[test]
+fn message_read_from_fail() {
+    let message = Message::Ping(Random::u64());
+    let buf = message.to_bytes().unwrap();
+
+    let mut reader = Cursor::new(buf[..buf.len() - 1].to_vec());
+    let res = Message::read_from(&mut reader);
+    assert!(res.is_err());
+
+    // a list length prefix above the maximum length of the message lists
+    for &(kind, length) in &[(MessageKind::Headers, MAX_HEADERS_LENGTH + 1),
+                             (MessageKind::Inventory, MAX_INVENTORY_LENGTH + 1)] {
+        let mut payload = vec![ENCODING_VERSION];
+        payload.extend_from_slice(&[(length >> 24) as u8, (length >> 16) as u8, (length >> 8) as u8, length as u8]);
+
+        let mut buf = MessageHeader::new(kind, payload.len() as u32).unwrap().to_bytes().unwrap();
+        buf.extend_from_slice(&payload);
+
+        let res = Message::read_from(&mut Cursor::new(buf.clone()));
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+        let res = Message::from_bytes(&buf);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+    }
+
+    // trailing bytes after the payload
+    let mut buf = message.to_bytes().unwrap();
+    buf.push(0);
+    buf[8] += 1;
+    let res = Message::read_from(&mut Cursor::new(buf));
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength)
+}
+
+#[test]
+fn handshake_verify_succ() {
+    let handshake = Handshake::new(NetworkType::RegTest, 0).unwrap();
+    let version = Version::current().unwrap();
+    let res = handshake.verify(NetworkType::RegTest, &version);
+    assert!(res.is_ok());
+
+    let mut patched = version.clone();
+    patched.patch += 1;
+    let res = handshake.verify(NetworkType::RegTest, &patched);
+    assert!(res.is_ok());
+}
+
+#[test]
+fn handshake_verify_fail() {
+    let handshake = Handshake::new(NetworkType::RegTest, 0).unwrap();
+    let version = Version::current().unwrap();
+    let res = handshake.verify(NetworkType::TestNet, &version);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidNetwork);
+
+    let mut other_major = version.clone();
+    other_major.major += 1;
+    let res = handshake.verify(NetworkType::RegTest, &other_major);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidVersion);
+
+    if version.major == 0 {
+        let mut other_minor = version.clone();
+        other_minor.minor += 1;
+        let res = handshake.verify(NetworkType::RegTest, &other_minor);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidVersion);
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `file` module tests.
+
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::{Random, Digest};
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::store::{Store, FileStore};
+use fixtures::{new_output, new_data, new_transaction};
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+fn store_path() -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("yobicash-store-{}", Random::u64()));
+    path
+}
+
+#[test]
+fn file_store_put_block_header_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+
+    let res = store.put_block_header(&block_header);
+    assert!(res.is_ok());
+
+    let stored = store.get_block_header(block_header.id).unwrap();
+    assert_eq!(stored, block_header);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_block_header_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let res = store.put_block_header(&block_header);
+    assert!(res.is_err());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_get_block_headers_by_height_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_height(0).unwrap();
+    assert_eq!(block_headers, vec![block_header]);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_get_block_headers_by_height_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_height(1).unwrap();
+    assert!(block_headers.is_empty());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_get_block_headers_by_prev_id_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_prev_id(Digest::default()).unwrap();
+    assert_eq!(block_headers, vec![block_header]);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_get_block_headers_by_prev_id_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_prev_id(block_header.id).unwrap();
+    assert!(block_headers.is_empty());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_get_block_headers_by_block_id_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_block_id(block_header.block_id).unwrap();
+    assert_eq!(block_headers, vec![block_header]);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_get_block_headers_by_block_id_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_block_id(block_header.id).unwrap();
+    assert!(block_headers.is_empty());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_block_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let block = Block::new(NetworkType::TestNet, &[new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])]).unwrap();
+
+    store.put_block(&block).unwrap();
+
+    assert!(store.has_block(block.id).unwrap());
+    assert_eq!(store.get_block(block.id).unwrap(), block);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_block_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let mut block = Block::new(NetworkType::TestNet, &[new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])]).unwrap();
+    block.transactions_size += 1;
+
+    let res = store.put_block(&block);
+    assert!(res.is_err());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_transaction_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+
+    store.put_transaction(&transaction).unwrap();
+
+    assert!(store.has_transaction(transaction.id).unwrap());
+    assert_eq!(store.get_transaction(transaction.id).unwrap(), transaction);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_transaction_fail() {
+    let path = store_path();
+    let store = FileStore::open(&path).unwrap();
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+
+    let res = store.get_transaction(transaction.id);
+    assert!(res.is_err());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_output_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let output = new_output(10.0);
+
+    store.put_output(&output).unwrap();
+
+    assert!(store.has_output(output.id).unwrap());
+    assert_eq!(store.get_output(output.id).unwrap(), output);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_output_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let mut output = new_output(10.0);
+    output.amount = Amount::from_f64(11.0).unwrap();
+
+    let res = store.put_output(&output);
+    assert!(res.is_err());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_data_succ() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let data = new_data();
+
+    store.put_data(&data).unwrap();
+
+    assert!(store.has_data(data.id).unwrap());
+    assert_eq!(store.get_data(data.id).unwrap(), data);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_put_data_fail() {
+    let path = store_path();
+    let mut store = FileStore::open(&path).unwrap();
+    let data = new_data();
+    store.put_data(&data).unwrap();
+
+    let res = store.put_data(&data);
+    assert!(res.is_err());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_open_succ() {
+    let path = store_path();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+
+    {
+        let mut store = FileStore::open(&path).unwrap();
+        store.put_block_header(&block_header).unwrap();
+        store.put_transaction(&transaction).unwrap();
+    }
+
+    let store = FileStore::open(&path).unwrap();
+    assert_eq!(store.get_block_header(block_header.id).unwrap(), block_header);
+    assert_eq!(store.get_transaction(transaction.id).unwrap(), transaction);
+    assert_eq!(store.get_block_headers_by_height(0).unwrap(), vec![block_header]);
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_open_fail() {
+    let path = store_path();
+    fs::write(&path, &[255u8; 100]).unwrap();
+
+    let res = FileStore::open(&path);
+    assert!(res.is_err());
+
+    fs::remove_file(&path).unwrap()
+}
+
+#[test]
+fn file_store_open_truncated_succ() {
+    let path = store_path();
+    let output_a = new_output(10.0);
+    let output_b = new_output(20.0);
+
+    {
+        let mut store = FileStore::open(&path).unwrap();
+        store.put_output(&output_a).unwrap();
+        store.put_output(&output_b).unwrap();
+    }
+
+    let length = fs::metadata(&path).unwrap().len();
+    let file = OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(length - 10).unwrap();
+
+    let mut store = FileStore::open(&path).unwrap();
+    assert!(store.has_output(output_a.id).unwrap());
+    assert!(!store.has_output(output_b.id).unwrap());
+
+    store.put_output(&output_b).unwrap();
+    assert_eq!(store.get_output(output_b.id).unwrap(), output_b);
+
+    // the record appended after the truncation is read back
+    let store = FileStore::open(&path).unwrap();
+    assert_eq!(store.get_output(output_a.id).unwrap(), output_a);
+    assert_eq!(store.get_output(output_b.id).unwrap(), output_b);
+
+    fs::remove_file(&path).unwrap()
+}
@@ -0,0 +1,180 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `memory` module tests.
+
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::Digest;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use libyobicash::store::{Store, MemoryStore};
+use fixtures::{new_output, new_data, new_transaction};
+
+#[test]
+fn memory_store_put_block_header_succ() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+
+    let res = store.put_block_header(&block_header);
+    assert!(res.is_ok());
+
+    let stored = store.get_block_header(block_header.id).unwrap();
+    assert_eq!(stored, block_header)
+}
+
+#[test]
+fn memory_store_put_block_header_fail() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let res = store.put_block_header(&block_header);
+    assert!(res.is_err())
+}
+
+#[test]
+fn memory_store_get_block_headers_by_height_succ() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_height(0).unwrap();
+    assert_eq!(block_headers, vec![block_header])
+}
+
+#[test]
+fn memory_store_get_block_headers_by_height_fail() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_height(1).unwrap();
+    assert!(block_headers.is_empty())
+}
+
+#[test]
+fn memory_store_get_block_headers_by_prev_id_succ() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_prev_id(Digest::default()).unwrap();
+    assert_eq!(block_headers, vec![block_header])
+}
+
+#[test]
+fn memory_store_get_block_headers_by_prev_id_fail() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_prev_id(block_header.id).unwrap();
+    assert!(block_headers.is_empty())
+}
+
+#[test]
+fn memory_store_get_block_headers_by_block_id_succ() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_block_id(block_header.block_id).unwrap();
+    assert_eq!(block_headers, vec![block_header])
+}
+
+#[test]
+fn memory_store_get_block_headers_by_block_id_fail() {
+    let mut store = MemoryStore::new();
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    store.put_block_header(&block_header).unwrap();
+
+    let block_headers = store.get_block_headers_by_block_id(block_header.id).unwrap();
+    assert!(block_headers.is_empty())
+}
+
+#[test]
+fn memory_store_put_block_succ() {
+    let mut store = MemoryStore::new();
+    let block = Block::new(NetworkType::TestNet, &[new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])]).unwrap();
+
+    store.put_block(&block).unwrap();
+
+    assert!(store.has_block(block.id).unwrap());
+    assert_eq!(store.get_block(block.id).unwrap(), block)
+}
+
+#[test]
+fn memory_store_put_block_fail() {
+    let mut store = MemoryStore::new();
+    let mut block = Block::new(NetworkType::TestNet, &[new_transaction(NetworkType::TestNet, 2.0, &[]), new_transaction(NetworkType::TestNet, 2.0, &[])]).unwrap();
+    block.transactions_size += 1;
+
+    let res = store.put_block(&block);
+    assert!(res.is_err())
+}
+
+#[test]
+fn memory_store_put_transaction_succ() {
+    let mut store = MemoryStore::new();
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+
+    store.put_transaction(&transaction).unwrap();
+
+    assert!(store.has_transaction(transaction.id).unwrap());
+    assert_eq!(store.get_transaction(transaction.id).unwrap(), transaction)
+}
+
+#[test]
+fn memory_store_put_transaction_fail() {
+    let store = MemoryStore::new();
+    let transaction = new_transaction(NetworkType::TestNet, 2.0, &[]);
+
+    let res = store.get_transaction(transaction.id);
+    assert!(res.is_err())
+}
+
+#[test]
+fn memory_store_put_output_succ() {
+    let mut store = MemoryStore::new();
+    let output = new_output(10.0);
+
+    store.put_output(&output).unwrap();
+
+    assert!(store.has_output(output.id).unwrap());
+    assert_eq!(store.get_output(output.id).unwrap(), output)
+}
+
+#[test]
+fn memory_store_put_output_fail() {
+    let mut store = MemoryStore::new();
+    let mut output = new_output(10.0);
+    output.amount = Amount::from_f64(11.0).unwrap();
+
+    let res = store.put_output(&output);
+    assert!(res.is_err())
+}
+
+#[test]
+fn memory_store_put_data_succ() {
+    let mut store = MemoryStore::new();
+    let data = new_data();
+
+    store.put_data(&data).unwrap();
+
+    assert!(store.has_data(data.id).unwrap());
+    assert_eq!(store.get_data(data.id).unwrap(), data)
+}
+
+#[test]
+fn memory_store_put_data_fail() {
+    let mut store = MemoryStore::new();
+    let data = new_data();
+    store.put_data(&data).unwrap();
+
+    let res = store.put_data(&data);
+    assert!(res.is_err())
+}
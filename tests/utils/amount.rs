@@ -25,11 +25,26 @@ fn amount_from_string_fail() {
     assert!(res.is_err())
 }
 
+#[test]
+fn amount_from_f64_succ() {
+    let res = Amount::from_f64(10.5);
+    assert_eq!(res.unwrap(), Amount::from_string("21/2").unwrap())
+}
+
+#[test]
+fn amount_from_f64_fail() {
+    let res = Amount::from_f64(::std::f64::NAN);
+    assert!(res.is_err());
+
+    let res = Amount::from_f32(::std::f32::INFINITY);
+    assert!(res.is_err())
+}
+
 #[test]
 fn amount_to_string_succ() {
     let n = 10.0;
 
-    let amount_a: Amount = n.into();
+    let amount_a = Amount::from_f64(n).unwrap();
     let s = amount_a.to_string();
     let amount_b = Amount::from_string(&s).unwrap();
     
@@ -40,7 +55,7 @@ fn amount_to_string_succ() {
 fn amount_to_string_fail() {
     let n = 10.0;
     
-    let amount_a: Amount = n.into();
+    let amount_a = Amount::from_f64(n).unwrap();
     let mut s = amount_a.to_string();
     s.pop();
     let amount_b = Amount::from_string(&s).unwrap();
@@ -139,3 +154,25 @@ fn amount_div_assign_succ() {
     
     assert_eq!(div, c)
 }
+
+#[test]
+fn amount_to_decimal_succ() {
+    assert_eq!(Amount::from(21u32).to_decimal().unwrap(), "21");
+    assert_eq!(Amount::from_f64(1.5).unwrap().to_decimal().unwrap(), "1.5");
+    assert_eq!(Amount::from_string("-1/8").unwrap().to_decimal().unwrap(), "-0.125");
+    assert_eq!(Amount::from_string("1/20").unwrap().to_decimal().unwrap(), "0.05");
+
+    let amount = Amount::from_decimal("12.050").unwrap();
+    assert_eq!(amount, Amount::from_string("241/20").unwrap());
+    assert_eq!(amount.to_decimal().unwrap(), "12.05")
+}
+
+#[test]
+fn amount_to_decimal_fail() {
+    assert!(Amount::from_string("1/3").unwrap().to_decimal().is_none());
+
+    for s in &["", "-", "1.", ".5", "1e3", "1.2.3", "+1", "0x10"] {
+        let res = Amount::from_decimal(s);
+        assert!(res.is_err())
+    }
+}
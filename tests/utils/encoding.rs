@@ -0,0 +1,169 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `encoding` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::{HexSerialize, Serialize, Encode};
+use libyobicash::utils::{NetworkType, Amount, Timestamp, Version};
+use libyobicash::utils::encoding::{Encoder, Decoder, DecodeLimits};
+use libyobicash::crypto::{Random, Scalar, SecretKey, ZKPWitness};
+use libyobicash::crypto::HexSerialize as CryptoHexSerialize;
+use libyobicash::models::data::Data;
+use libyobicash::models::output::Output;
+use libyobicash::models::input::Input;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use fixtures::{new_data, new_transaction};
+
+#[test]
+fn encoding_vectors_succ() {
+    let amount = Amount::genesis_value();
+    assert_eq!(amount.to_hex().unwrap(), "01000000023231");
+
+    let amount = Amount::from_f64(1.5).unwrap();
+    assert_eq!(amount.to_hex().unwrap(), "0100000003332f32");
+
+    let timestamp = Timestamp::min_value();
+    assert_eq!(timestamp.to_hex().unwrap(), "01000000005a5fe380");
+
+    let version = Version::parse("1.2.3-alpha.beta").unwrap();
+    assert_eq!(version.to_hex().unwrap(), "0100000001000000020000000300000005616c7068610000000462657461");
+
+    let output = Output::new_testnet_genesis().unwrap();
+    assert_eq!(output.to_hex().unwrap(),
+               "0127ae086ad98d452979b7c2f636668bae4d5cd1f5c65f71cb475516d95eff305416b15fe2801b0e9ecc256eec43d5\
+                d13d205fc72174add9aaa1d4b60662491c07000000023231893bca8b0c490032e53c0349ca922418b2349af16e57323\
+                0680a36e38ea08a47");
+
+    let transaction = Transaction::new_testnet_genesis().unwrap();
+    assert_eq!(transaction.id.to_hex().unwrap(),
+               "be2771d9adc27daf51be5be2d7f7d64356b96f7212f89b4c3a8abaf2a4cd96dfaf505bbc75ad37cc9ab5e17621be22\
+                1432e11208e5b6cc59727f22a45e4fa6a0");
+
+    let block = Block::new_testnet_genesis().unwrap();
+    assert_eq!(block.id.to_hex().unwrap(),
+               "66af168536b85038ac925b04a18959484bc88e21ee22c164aa1dd1f861456b242a17ceb82a13796ace5cd0cca91f59\
+                13c818646b632adc28df51411fa359f16d");
+
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    assert_eq!(block_header.id.to_hex().unwrap(),
+               "34ea23015f160adddc8fde7441cf7eb6d388b84ac7d279c5131b60078a5c36e4bbdb71f8cde038ca9b676128a64eea\
+                7e139c5f0f5130017d707dd27758d71cc3")
+}
+
+#[test]
+fn encoding_round_trip_succ() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[new_data()]);
+    let res = Transaction::from_bytes(&transaction.to_bytes().unwrap());
+    assert_eq!(res.unwrap(), transaction);
+
+    let input = transaction.inputs[0];
+    let res = Input::from_bytes(&input.to_bytes().unwrap());
+    assert_eq!(res.unwrap(), input);
+
+    let data = Data::new(SecretKey::random(), SecretKey::random().to_public(), 10, &Random::bytes(100)).unwrap();
+    let res = Data::from_bytes(&data.to_bytes().unwrap());
+    assert_eq!(res.unwrap(), data);
+
+    let block = Block::new(NetworkType::RegTest, &[transaction]).unwrap();
+    let res = Block::from_bytes(&block.to_bytes().unwrap());
+    assert_eq!(res.unwrap(), block);
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let block_header = BlockHeader::new_regtest_genesis(witness).unwrap();
+    let res = BlockHeader::from_bytes(&block_header.to_bytes().unwrap());
+    assert_eq!(res.unwrap(), block_header);
+
+    // the declared lengths are encoded apart from the lists
+    let mut invalid = new_transaction(NetworkType::RegTest, 1.0, &[new_data()]);
+    invalid.outputs.clear();
+    let res = Transaction::from_bytes(&invalid.to_bytes().unwrap());
+    assert_eq!(res.unwrap(), invalid)
+}
+
+#[test]
+fn encoding_round_trip_fail() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.0, &[new_data()]);
+    let buf = transaction.to_bytes().unwrap();
+
+    let mut unknown_version = buf.clone();
+    unknown_version[0] = 2;
+    let res = Transaction::from_bytes(&unknown_version);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotSupported);
+
+    let mut trailing = buf.clone();
+    trailing.push(0);
+    let res = Transaction::from_bytes(&trailing);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+    let res = Transaction::from_bytes(&buf[..buf.len() - 1]);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+    // a non reduced amount
+    let res = Amount::from_hex("0100000003322f34");
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidFormat);
+
+    // a memory with leading zeros
+    let mut encoder = Encoder::new(Vec::new());
+    encoder.write_str("064").unwrap();
+    let buf = encoder.into_inner();
+    let res = Decoder::new(&buf[..]).read_memory();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidFormat)
+}
+
+#[test]
+fn encoding_stream_succ() {
+    let transactions = vec![new_transaction(NetworkType::RegTest, 1.0, &[new_data()]), new_transaction(NetworkType::RegTest, 1.0, &[new_data()]), new_transaction(NetworkType::RegTest, 1.0, &[new_data()])];
+    let block = Block::new(NetworkType::RegTest, &transactions[..1]).unwrap();
+
+    let mut buf = Vec::new();
+    block.write_to(&mut buf).unwrap();
+    for transaction in &transactions {
+        transaction.write_to(&mut buf).unwrap();
+    }
+
+    let mut reader = &buf[..];
+    let res = Block::read_from(&mut reader, DecodeLimits::default());
+    assert_eq!(res.unwrap(), block);
+    for transaction in &transactions {
+        let res = Transaction::read_from(&mut reader, DecodeLimits::default());
+        assert_eq!(&res.unwrap(), transaction);
+    }
+    assert!(reader.is_empty());
+
+    let data = Data::new(SecretKey::random(), SecretKey::random().to_public(), 10, &Random::bytes(1000)).unwrap();
+    let buf = data.to_bytes().unwrap();
+    let limits = DecodeLimits::new(buf.len() as u64, 0);
+    let res = Data::read_from(&mut &buf[..], limits);
+    assert_eq!(res.unwrap(), data)
+}
+
+#[test]
+fn encoding_stream_fail() {
+    let data = Data::new(SecretKey::random(), SecretKey::random().to_public(), 10, &Random::bytes(1000)).unwrap();
+    let buf = data.to_bytes().unwrap();
+    let limits = DecodeLimits::new(buf.len() as u64 - 1, 0);
+    let res = Data::read_from(&mut &buf[..], limits);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    let block = Block::new(NetworkType::RegTest, &[new_transaction(NetworkType::RegTest, 1.0, &[new_data()])]).unwrap();
+    let buf = block.to_bytes().unwrap();
+    let limits = DecodeLimits::new(buf.len() as u64, 0);
+    let res = Block::read_from(&mut &buf[..], limits);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    // a forged length prefix is not allocated before reading
+    let buf = [0xff, 0xff, 0xff, 0xff, 0];
+    let limits = DecodeLimits::new(u64::max_value(), 0);
+    let res = Decoder::with_limits(&buf[..], limits).read_bytes();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+    let res = Decoder::new(&buf[..]).read_bytes();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound)
+}
@@ -0,0 +1,130 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `fixed_amount` module tests.
+
+use libyobicash::constants::{AMOUNT_UNIT, MAX_SUPPLY};
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::{BinarySerialize, HexSerialize};
+use libyobicash::utils::{Amount, FixedAmount};
+
+#[test]
+fn fixed_amount_parse_succ() {
+    let amount = FixedAmount::parse("1.25 YBC").unwrap();
+    assert_eq!(amount.units(), 125_000_000);
+    assert_eq!(amount.to_string(), "1.25 YBC");
+    assert_eq!(FixedAmount::parse("1.25").unwrap(), amount);
+    assert_eq!(FixedAmount::parse("1.250").unwrap(), amount);
+
+    let amount = FixedAmount::parse("0.00000001").unwrap();
+    assert_eq!(amount.units(), 1);
+    assert_eq!(amount.to_decimal(), "0.00000001");
+
+    let amount = FixedAmount::parse("21000000 YBC").unwrap();
+    assert_eq!(amount, FixedAmount::max_value());
+    assert_eq!(amount.to_string(), "21000000 YBC");
+
+    assert_eq!(FixedAmount::zero().to_string(), "0 YBC")
+}
+
+#[test]
+fn fixed_amount_parse_fail() {
+    for s in &["", "YBC", "1.25 BTC", "1.25  YBC", "1.25 YBC x", "-1", "+1", ".5", "1.", "1e3", "1,5"] {
+        let res = FixedAmount::parse(s);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidFormat);
+    }
+
+    let res = FixedAmount::parse("0.000000001");
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidFormat);
+
+    for s in &["21000000.00000001", "99999999999999999999"] {
+        let res = FixedAmount::parse(s);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+    }
+}
+
+#[test]
+fn fixed_amount_arithmetic_succ() {
+    let a = FixedAmount::from_coins(3).unwrap();
+    let b = FixedAmount::parse("0.5").unwrap();
+
+    assert_eq!(a.checked_add(b).unwrap().to_decimal(), "3.5");
+    assert_eq!(a.checked_sub(b).unwrap().to_decimal(), "2.5");
+    assert_eq!(b.checked_mul(3).unwrap().to_decimal(), "1.5");
+    assert_eq!(a.checked_div(2).unwrap().to_decimal(), "1.5");
+    assert_eq!(FixedAmount::from_units(10).unwrap().checked_div(3).unwrap().units(), 3);
+    assert_eq!(FixedAmount::checked_sum(&[a, b, b]).unwrap(), FixedAmount::from_coins(4).unwrap());
+
+    let max = FixedAmount::max_value();
+    assert_eq!(max.checked_sub(max).unwrap(), FixedAmount::zero())
+}
+
+#[test]
+fn fixed_amount_arithmetic_fail() {
+    let max = FixedAmount::max_value();
+    let one = FixedAmount::from_units(1).unwrap();
+
+    assert_eq!(max.checked_add(one).unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert_eq!(one.checked_sub(max).unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert_eq!(max.checked_mul(2).unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert_eq!(max.checked_mul(u64::max_value()).unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert_eq!(max.checked_div(0).unwrap_err().kind(), ErrorKind::OutOfBound);
+    assert_eq!(FixedAmount::checked_sum(&[max, one]).unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    let res = FixedAmount::from_units(MAX_SUPPLY + 1);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    let res = FixedAmount::from_coins(u64::max_value());
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound)
+}
+
+#[test]
+fn fixed_amount_from_amount_succ() {
+    let amount = Amount::from_f64(1.25).unwrap();
+    let fixed = amount.to_fixed().unwrap();
+    assert_eq!(fixed.units(), 125_000_000);
+    assert_eq!(Amount::from(fixed), amount);
+
+    let amount = Amount::from_string("1/3").unwrap();
+    let fixed = amount.to_fixed_floor().unwrap();
+    assert_eq!(fixed.to_decimal(), "0.33333333");
+
+    let amount = Amount::genesis_value();
+    assert_eq!(amount.to_fixed().unwrap(), FixedAmount::from_coins(21).unwrap())
+}
+
+#[test]
+fn fixed_amount_from_amount_fail() {
+    let res = Amount::from_string("1/3").unwrap().to_fixed();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidFormat);
+
+    let res = Amount::from_string("-1").unwrap().to_fixed_floor();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    let res = Amount::from((MAX_SUPPLY + 1, AMOUNT_UNIT)).to_fixed();
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound)
+}
+
+#[test]
+fn fixed_amount_to_hex_succ() {
+    let amount = FixedAmount::parse("1.25").unwrap();
+    let s = amount.to_hex().unwrap();
+    assert_eq!(s, "010000000007735940");
+    assert_eq!(FixedAmount::from_hex(&s).unwrap(), amount)
+}
+
+#[test]
+fn fixed_amount_to_hex_fail() {
+    let res = FixedAmount::from_hex("01ffffffffffffffff");
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfBound);
+
+    let amount = FixedAmount::parse("1.25").unwrap();
+    let mut b = amount.to_bytes().unwrap();
+    b.pop();
+    let res = FixedAmount::from_bytes(&b);
+    assert!(res.is_err())
+}
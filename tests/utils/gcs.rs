@@ -0,0 +1,65 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `gcs` module tests.
+
+use libyobicash::crypto::{Random, Digest};
+use libyobicash::utils::gcs::GolombCodedSet;
+
+fn random_items(len: usize) -> Vec<Vec<u8>> {
+    let mut items = Vec::new();
+
+    for _ in 0..len {
+        items.push(Random::bytes(32));
+    }
+
+    items
+}
+
+#[test]
+fn gcs_new_succ() {
+    let key = Digest::hash(b"key");
+    let mut items = random_items(100);
+    items.push(items[0].clone());
+
+    let gcs = GolombCodedSet::new(key, &items).unwrap();
+    assert_eq!(gcs.length, 100);
+
+    // about GCS_P + 2 bits for each item
+    assert!(gcs.data.len() < 100 * 3);
+
+    for item in &items {
+        assert!(gcs.contains(key, item).unwrap());
+    }
+
+    let res = gcs.contains_any(key, &[Random::bytes(32), items[50].clone()]);
+    assert!(res.unwrap())
+}
+
+#[test]
+fn gcs_new_fail() {
+    let key = Digest::hash(b"key");
+    let items = random_items(100);
+
+    let gcs = GolombCodedSet::new(key, &items).unwrap();
+
+    // a false positive happens about once every GCS_M queries
+    let res = gcs.contains_any(key, &random_items(100));
+    assert!(!res.unwrap());
+
+    let res = gcs.contains(Digest::hash(b"other key"), &items[0]);
+    assert!(!res.unwrap());
+
+    let empty = GolombCodedSet::new(key, &[]).unwrap();
+    let res = empty.contains(key, &items[0]);
+    assert!(!res.unwrap());
+
+    let mut truncated = gcs.clone();
+    truncated.data.truncate(10);
+    let res = truncated.contains(key, &items[99]);
+    assert!(res.is_err())
+}
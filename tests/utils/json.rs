@@ -0,0 +1,155 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `json` module tests.
+
+use serde_json::Value;
+
+use libyobicash::error::ErrorKind;
+use libyobicash::traits::Serialize;
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::utils::json;
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::schema::json_schema;
+use libyobicash::models::output::Output;
+use libyobicash::models::transaction::Transaction;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_header::BlockHeader;
+use fixtures::{new_data, new_transaction};
+
+#[test]
+fn json_representation_succ() {
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    let value = json::to_value(&block_header).unwrap();
+
+    assert_eq!(value["json_version"], 1);
+    assert_eq!(value["network_type"], "testnet");
+    assert_eq!(value["timestamp"]["rfc3339"], "2018-01-18T00:00:00Z");
+    assert_eq!(value["timestamp"]["unix"], 1516233600);
+    assert_eq!(value["coinbase_amount"], "21");
+    assert_eq!(value["coinbase_output"]["json_version"], 1);
+    assert_eq!(value["coinbase_output"]["amount"], "21");
+    assert_eq!(value["pow_nonce"], Value::from(block_header.pow_nonce.to_string()));
+    assert_eq!(value["id"].as_str().unwrap().len(), 128);
+
+    let transaction = new_transaction(NetworkType::RegTest, 1.25, &[new_data()]);
+    let value = json::to_value(&transaction).unwrap();
+
+    assert_eq!(value["fee"], "1.25");
+    assert_eq!(value["outputs"][0]["amount"], "8.75");
+    assert_eq!(value["inputs"][0]["json_version"], 1);
+    assert_eq!(value["data_ids"].as_array().unwrap().len(), 1);
+
+    let res = Transaction::from_json(&transaction.to_json().unwrap());
+    assert_eq!(res.unwrap(), transaction);
+
+    let block = Block::new(NetworkType::RegTest, &[transaction]).unwrap();
+    let res = Block::from_json(&block.to_json().unwrap());
+    assert_eq!(res.unwrap(), block);
+
+    let res = BlockHeader::from_json(&block_header.to_json().unwrap());
+    assert_eq!(res.unwrap(), block_header);
+
+    // the amounts without a finite decimal representation are fractions
+    let output = Output::new(&Amount::from_string("1/3").unwrap(), ZKPWitness::new(Scalar::random()).unwrap()).unwrap();
+    let value = json::to_value(&output).unwrap();
+    assert_eq!(value["amount"], "1/3");
+    let res: Output = json::from_value(&value).unwrap();
+    assert_eq!(res, output)
+}
+
+#[test]
+fn json_representation_fail() {
+    let transaction = new_transaction(NetworkType::RegTest, 1.25, &[new_data()]);
+    let value = json::to_value(&transaction).unwrap();
+
+    let mut unknown_version = value.clone();
+    unknown_version["json_version"] = Value::from(2);
+    let err = json::from_value::<Transaction>(&unknown_version).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+    assert_eq!(err.details().path(), "json_version");
+
+    let mut unknown_field = value.clone();
+    unknown_field["size"] = Value::from(0);
+    let err = json::from_value::<Transaction>(&unknown_field).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+
+    let mut missing_field = value.clone();
+    missing_field.as_object_mut().unwrap().remove("fee");
+    let err = json::from_value::<Transaction>(&missing_field).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.details().path(), "fee");
+
+    let mut uppercase_id = value.clone();
+    let id = value["inputs"][0]["id"].as_str().unwrap().to_uppercase();
+    uppercase_id["inputs"][0]["id"] = Value::from(id);
+    let err = json::from_value::<Transaction>(&uppercase_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+    assert_eq!(err.details().path(), "inputs[0].id");
+
+    let mut numeric_amount = value.clone();
+    numeric_amount["fee"] = Value::from(1.25);
+    let err = json::from_value::<Transaction>(&numeric_amount).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+    assert_eq!(err.details().path(), "fee");
+
+    // the amounts are accepted only in their canonical representation
+    for amount in &["001.25", "1.250", "+1.25", "5/4", "10/8"] {
+        let mut non_canonical_amount = value.clone();
+        non_canonical_amount["fee"] = Value::from(*amount);
+        let err = json::from_value::<Transaction>(&non_canonical_amount).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidFormat);
+        assert_eq!(err.details().path(), "fee");
+    }
+
+    let mut inconsistent_timestamp = value.clone();
+    inconsistent_timestamp["timestamp"]["unix"] = Value::from(0);
+    let err = json::from_value::<Transaction>(&inconsistent_timestamp).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidTime);
+    assert_eq!(err.details().path(), "timestamp")
+}
+
+#[test]
+fn json_schema_succ() {
+    let schema = json_schema();
+    assert_eq!(schema["json_version"], 1);
+
+    let definitions = schema["definitions"].as_object().unwrap();
+    for name in &["Data", "Output", "Coin", "Input", "Transaction", "Block", "BlockHeader"] {
+        assert!(definitions.contains_key(*name));
+    }
+
+    let transaction_schema = &definitions["Transaction"];
+    assert_eq!(transaction_schema["additionalProperties"], false);
+    assert_eq!(transaction_schema["properties"]["inputs"]["items"]["$ref"], "#/definitions/Input");
+
+    // the schemas describe all the fields of the representations
+    let block_header = BlockHeader::new_testnet_genesis().unwrap();
+    let representations = vec![
+        ("Transaction", json::to_value(&new_transaction(NetworkType::RegTest, 1.25, &[new_data()])).unwrap()),
+        ("BlockHeader", json::to_value(&block_header).unwrap()),
+        ("Output", json::to_value(&block_header.coinbase_output).unwrap()),
+    ];
+
+    for (name, value) in representations {
+        let properties = definitions[name]["properties"].as_object().unwrap();
+        let required = definitions[name]["required"].as_array().unwrap();
+        let fields = value.as_object().unwrap();
+
+        assert_eq!(properties.len(), fields.len());
+        assert_eq!(required.len(), fields.len());
+
+        for field in fields.keys() {
+            assert!(properties.contains_key(field));
+
+            if let Some(reference) = properties[field]["$ref"].as_str() {
+                let definition = reference.trim_start_matches("#/definitions/");
+                assert!(definitions.contains_key(definition));
+            }
+        }
+    }
+}
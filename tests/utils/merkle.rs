@@ -0,0 +1,173 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `merkle` module tests.
+
+use libyobicash::traits::{Validate, BinarySerialize, HexSerialize};
+use libyobicash::crypto::{Random, Digest};
+use libyobicash::utils::merkle::{MerkleTree, MerkleProof, merkle_root};
+
+fn random_leaves(len: usize) -> Vec<Digest> {
+    let mut leaves = Vec::new();
+
+    for _ in 0..len {
+        leaves.push(Digest::hash(&Random::bytes(32)));
+    }
+
+    leaves
+}
+
+#[test]
+fn merkle_tree_new_succ() {
+    let leaves = random_leaves(7);
+
+    let res = MerkleTree::new(&leaves);
+    assert!(res.is_ok())
+}
+
+#[test]
+fn merkle_tree_new_fail() {
+    let leaves = Vec::new();
+
+    let res = MerkleTree::new(&leaves);
+    assert!(res.is_err())
+}
+
+#[test]
+fn merkle_root_succ() {
+    let leaves = random_leaves(5);
+
+    let root_a = merkle_root(&leaves).unwrap();
+    let root_b = merkle_root(&leaves).unwrap();
+    assert_eq!(root_a, root_b)
+}
+
+#[test]
+fn merkle_root_fail() {
+    let leaves_a = random_leaves(5);
+    let mut leaves_b = leaves_a.clone();
+    leaves_b.swap(0, 1);
+
+    let root_a = merkle_root(&leaves_a).unwrap();
+    let root_b = merkle_root(&leaves_b).unwrap();
+    assert_ne!(root_a, root_b)
+}
+
+#[test]
+fn merkle_tree_proof_succ() {
+    for len in 1..18 {
+        let leaves = random_leaves(len);
+        let tree = MerkleTree::new(&leaves).unwrap();
+        let root = tree.root();
+
+        for leaf in leaves {
+            let proof = tree.proof(leaf).unwrap();
+            let verified = proof.verify(root).unwrap();
+            assert!(verified)
+        }
+    }
+}
+
+#[test]
+fn merkle_tree_proof_fail() {
+    let leaves = random_leaves(9);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let leaf = Digest::hash(&Random::bytes(32));
+
+    let res = tree.proof(leaf);
+    assert!(res.is_err())
+}
+
+#[test]
+fn merkle_proof_verify_succ() {
+    let leaves = random_leaves(6);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let proof = tree.proof(leaves[5]).unwrap();
+
+    let verified = proof.verify(tree.root()).unwrap();
+    assert!(verified)
+}
+
+#[test]
+fn merkle_proof_verify_fail() {
+    let leaves = random_leaves(6);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let mut proof = tree.proof(leaves[2]).unwrap();
+    proof.leaf = leaves[3];
+
+    let verified = proof.verify(tree.root()).unwrap();
+    assert!(!verified)
+}
+
+#[test]
+fn merkle_proof_validate_succ() {
+    let leaves = random_leaves(11);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let proof = tree.proof(leaves[10]).unwrap();
+
+    let res = proof.validate();
+    assert!(res.is_ok())
+}
+
+#[test]
+fn merkle_proof_validate_fail() {
+    let leaves = random_leaves(11);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let mut proof = tree.proof(leaves[10]).unwrap();
+    proof.path.push(leaves[0]);
+
+    let res = proof.validate();
+    assert!(res.is_err())
+}
+
+#[test]
+fn merkle_proof_to_bytes_succ() {
+    let leaves = random_leaves(4);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let proof_a = tree.proof(leaves[1]).unwrap();
+
+    let proof_buf = proof_a.to_bytes().unwrap();
+    let proof_b = MerkleProof::from_bytes(&proof_buf).unwrap();
+    assert_eq!(proof_a, proof_b)
+}
+
+#[test]
+fn merkle_proof_to_bytes_fail() {
+    let leaves = random_leaves(4);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let proof = tree.proof(leaves[1]).unwrap();
+
+    let mut proof_buf = proof.to_bytes().unwrap();
+    proof_buf.pop();
+
+    let res = MerkleProof::from_bytes(&proof_buf);
+    assert!(res.is_err())
+}
+
+#[test]
+fn merkle_proof_to_hex_succ() {
+    let leaves = random_leaves(3);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let proof_a = tree.proof(leaves[2]).unwrap();
+
+    let proof_hex = proof_a.to_hex().unwrap();
+    let proof_b = MerkleProof::from_hex(&proof_hex).unwrap();
+    assert_eq!(proof_a, proof_b)
+}
+
+#[test]
+fn merkle_proof_to_hex_fail() {
+    let leaves = random_leaves(3);
+    let tree = MerkleTree::new(&leaves).unwrap();
+    let proof = tree.proof(leaves[2]).unwrap();
+
+    let mut proof_hex = proof.to_hex().unwrap();
+    proof_hex.pop();
+
+    let res = MerkleProof::from_hex(&proof_hex);
+    assert!(res.is_err())
+}
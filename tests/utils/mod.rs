@@ -11,3 +11,8 @@ mod version;
 mod network_type;
 mod timestamp;
 mod amount;
+mod fixed_amount;
+mod merkle;
+mod gcs;
+mod encoding;
+mod json;
@@ -48,3 +48,17 @@ fn network_type_to_hex_fail() {
     let res = NetworkType::from_hex(&net_type_str);
     assert!(res.is_err())
 }
+
+#[test]
+fn network_type_parse_succ() {
+    for network_type in &[NetworkType::MainNet, NetworkType::TestNet, NetworkType::RegTest] {
+        let res = NetworkType::parse(&network_type.to_string());
+        assert_eq!(res.unwrap(), *network_type)
+    }
+}
+
+#[test]
+fn network_type_parse_fail() {
+    let res = NetworkType::parse("TestNet");
+    assert!(res.is_err())
+}
@@ -94,7 +94,7 @@ fn timestamp_to_bytes_fail() {
     
     let timestamp_a = Timestamp::parse(date).unwrap();
     let mut timestamp_a_buf = timestamp_a.to_bytes().unwrap();
-    timestamp_a_buf[0] ^= timestamp_a_buf[0];
+    timestamp_a_buf[1] ^= timestamp_a_buf[1];
     
     let res = Timestamp::from_bytes(&timestamp_a_buf);
     assert!(res.is_ok())
@@ -116,3 +116,19 @@ fn timestamp_validate_fail() {
     let res = timestamp.validate();
     assert!(res.is_err())
 }
+
+#[test]
+fn timestamp_to_rfc3339_succ() {
+    let timestamp = Timestamp::min_value();
+    let s = timestamp.to_rfc3339().unwrap();
+    assert_eq!(s, "2018-01-18T00:00:00Z");
+    assert_eq!(Timestamp::parse(&s).unwrap(), timestamp);
+    assert_eq!(Timestamp::from_unix(timestamp.to_unix()), timestamp)
+}
+
+#[test]
+fn timestamp_to_rfc3339_fail() {
+    let timestamp = Timestamp::from_unix(i64::max_value());
+    let res = timestamp.to_rfc3339();
+    assert!(res.is_err())
+}
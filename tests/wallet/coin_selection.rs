@@ -0,0 +1,172 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `coin_selection` module tests.
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::output::Output;
+use libyobicash::models::coin::Coin;
+use libyobicash::wallet::coin_selection::*;
+
+fn new_coins(amounts: &[f64]) -> Vec<Coin> {
+    let mut coins = Vec::new();
+
+    for amount in amounts {
+        let instance = Scalar::random();
+        let witness = ZKPWitness::new(instance).unwrap();
+        let output = Output::new(&Amount::from_f64(*amount).unwrap(), witness).unwrap();
+        coins.push(Coin::new(&output, instance).unwrap());
+    }
+
+    coins
+}
+
+fn new_outputs(amounts: &[f64]) -> Vec<Output> {
+    let mut outputs = Vec::new();
+
+    for amount in amounts {
+        let witness = ZKPWitness::new(Scalar::random()).unwrap();
+        outputs.push(Output::new(&Amount::from_f64(*amount).unwrap(), witness).unwrap());
+    }
+
+    outputs
+}
+
+fn change_witness() -> ZKPWitness {
+    ZKPWitness::new(Scalar::random()).unwrap()
+}
+
+#[test]
+fn fee_policy_fee_succ() {
+    let policy = FeePolicy::new(&Amount::from(1u32), &Amount::from(2u32), &Amount::from(3u32));
+
+    assert_eq!(policy.fee(0, 0), Amount::from(1u32));
+    assert_eq!(policy.fee(2, 1), Amount::from(8u32))
+}
+
+#[test]
+fn select_coins_largest_first_succ() {
+    let coins = new_coins(&[1.0, 10.0, 5.0]);
+    let outputs = new_outputs(&[12.0]);
+    let policy = FeePolicy::fixed(&Amount::from(1u32));
+
+    let selection = select_coins(SelectionStrategy::LargestFirst,
+                                 &coins, &outputs, &policy).unwrap();
+
+    assert_eq!(selection.coins, vec![coins[1].clone(), coins[2].clone()]);
+    assert_eq!(selection.fee, Amount::from(1u32));
+    assert_eq!(selection.change.clone().unwrap(), Amount::from(2u32));
+
+    let res = selection.build_transaction(NetworkType::RegTest, &outputs, || Ok(change_witness()));
+    assert!(res.is_ok())
+}
+
+#[test]
+fn select_coins_largest_first_fail() {
+    let coins = new_coins(&[1.0, 10.0, 5.0]);
+    let outputs = new_outputs(&[16.0]);
+    let policy = FeePolicy::fixed(&Amount::from(1u32));
+
+    let res = select_coins(SelectionStrategy::LargestFirst,
+                           &coins, &outputs, &policy);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InsufficientFunds)
+}
+
+#[test]
+fn select_coins_branch_and_bound_succ() {
+    let coins = new_coins(&[10.0, 7.0, 4.0, 3.0]);
+    let outputs = new_outputs(&[6.0]);
+    let policy = FeePolicy::new(&Amount::from_f64(0.5).unwrap(), &Amount::from_f64(0.25).unwrap(), &Amount::from_f64(0.0).unwrap());
+
+    let selection = select_coins(SelectionStrategy::BranchAndBound,
+                                 &coins, &outputs, &policy).unwrap();
+
+    assert_eq!(selection.coins, vec![coins[2].clone(), coins[3].clone()]);
+    assert!(selection.change.is_none());
+    assert_eq!(selection.fee, Amount::from_f64(1.0).unwrap());
+
+    let res = selection.build_transaction(NetworkType::RegTest, &outputs, || Ok(change_witness()));
+    assert!(res.is_ok())
+}
+
+#[test]
+fn select_coins_branch_and_bound_deep_succ() {
+    // the search depth grows with the number of coins
+    let coins = vec![new_coins(&[1.0])[0].clone(); 50_000];
+    let outputs = new_outputs(&[40_000.0]);
+    let policy = FeePolicy::fixed(&Amount::zero());
+
+    let selection = select_coins(SelectionStrategy::BranchAndBound,
+                                 &coins, &outputs, &policy).unwrap();
+
+    assert_eq!(selection.coins.len(), 40_000);
+    assert!(selection.change.is_none())
+}
+
+#[test]
+fn select_coins_branch_and_bound_fail() {
+    let coins = new_coins(&[10.0, 7.0]);
+    let outputs = new_outputs(&[5.0]);
+    let policy = FeePolicy::fixed(&Amount::from(1u32));
+
+    let selection = select_coins(SelectionStrategy::BranchAndBound,
+                                 &coins, &outputs, &policy).unwrap();
+
+    assert_eq!(selection.coins, vec![coins[0].clone()]);
+    assert_eq!(selection.change.unwrap(), Amount::from(4u32));
+
+    let res = select_coins(SelectionStrategy::BranchAndBound,
+                           &coins, &new_outputs(&[17.0]), &policy);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InsufficientFunds)
+}
+
+#[test]
+fn select_coins_random_succ() {
+    let coins = new_coins(&[1.0, 2.0, 3.0, 4.0]);
+    let outputs = new_outputs(&[5.0]);
+    let policy = FeePolicy::fixed(&Amount::from(1u32));
+
+    let selection = select_coins(SelectionStrategy::Random,
+                                 &coins, &outputs, &policy).unwrap();
+
+    let mut spent = selection.fee.clone() + &Amount::from(5u32);
+    if let Some(ref change) = selection.change {
+        spent += change;
+    }
+
+    assert_eq!(selection.coins_amount(), spent);
+
+    let res = selection.build_transaction(NetworkType::RegTest, &outputs, || Ok(change_witness()));
+    assert!(res.is_ok())
+}
+
+#[test]
+fn select_coins_random_fail() {
+    let coins = new_coins(&[1.0, 2.0]);
+    let outputs = new_outputs(&[5.0]);
+    let policy = FeePolicy::fixed(&Amount::from(1u32));
+
+    let res = select_coins(SelectionStrategy::Random,
+                           &coins, &outputs, &policy);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InsufficientFunds)
+}
+
+#[test]
+fn select_coins_change_fee_succ() {
+    let coins = new_coins(&[10.0]);
+    let outputs = new_outputs(&[7.5]);
+    let policy = FeePolicy::new(&Amount::from(1u32), &Amount::zero(), &Amount::from(1u32));
+
+    let selection = select_coins(SelectionStrategy::LargestFirst,
+                                 &coins, &outputs, &policy).unwrap();
+
+    // the surplus does not pay for the change output
+    assert!(selection.change.is_none());
+    assert_eq!(selection.fee, Amount::from_f64(2.5).unwrap())
+}
@@ -0,0 +1,72 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `keychain` module tests.
+
+use libyobicash::crypto::{Random, Scalar, ZKPWitness};
+use libyobicash::wallet::keychain::Keychain;
+
+#[test]
+fn keychain_new_succ() {
+    let seed = Random::bytes(32);
+    let res = Keychain::new(&seed);
+    assert!(res.is_ok());
+
+    let keychain = res.unwrap();
+    assert!(keychain.is_empty());
+    assert_eq!(keychain.seed(), seed.as_slice())
+}
+
+#[test]
+fn keychain_new_fail() {
+    let res = Keychain::new(&[]);
+    assert!(res.is_err())
+}
+
+#[test]
+fn keychain_derive_succ() {
+    let mut keychain = Keychain::random();
+
+    let key_a = keychain.derive().unwrap();
+    let key_b = keychain.derive().unwrap();
+
+    assert_eq!(key_a.index, 0);
+    assert_eq!(key_b.index, 1);
+    assert_ne!(key_a.instance, key_b.instance);
+    assert_eq!(ZKPWitness::new(key_a.instance).unwrap(), key_a.witness);
+    assert_eq!(keychain.len(), 2)
+}
+
+#[test]
+fn keychain_restore_succ() {
+    let mut keychain = Keychain::random();
+    keychain.derive().unwrap();
+    keychain.derive().unwrap();
+
+    let restored = Keychain::restore(keychain.seed(), 2).unwrap();
+    assert_eq!(restored, keychain)
+}
+
+#[test]
+fn keychain_get_succ() {
+    let mut keychain = Keychain::random();
+    let key = keychain.derive().unwrap();
+
+    assert!(keychain.contains(key.witness).unwrap());
+    assert_eq!(keychain.get(key.witness).unwrap(), Some(key))
+}
+
+#[test]
+fn keychain_get_fail() {
+    let mut keychain = Keychain::random();
+    keychain.derive().unwrap();
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+
+    assert!(!keychain.contains(witness).unwrap());
+    assert!(keychain.get(witness).unwrap().is_none())
+}
@@ -0,0 +1,175 @@
+// Copyright 2018 Yobicash Ltd.
+//
+// Licensed under the MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+// and the Apache 2.0 license <LICENSE-APACHE or https://opensource.org/licenses/Apache-2.0>.
+// This file may not be copied, modified, or distributed except according to those
+// terms.
+
+//! Libyobicash `wallet` module tests.
+
+mod keychain;
+mod coin_selection;
+
+use libyobicash::error::ErrorKind;
+use libyobicash::utils::{NetworkType, Amount};
+use libyobicash::crypto::{Scalar, ZKPWitness};
+use libyobicash::models::output::Output;
+use libyobicash::models::block::Block;
+use libyobicash::models::block_filter::BlockFilter;
+use libyobicash::wallet::{Wallet, FeePolicy};
+use fixtures::new_block_header;
+
+fn funded_wallet(amounts: &[f64]) -> (Wallet, Vec<Output>) {
+    let mut wallet = Wallet::random(NetworkType::RegTest);
+    let mut outputs = Vec::new();
+
+    for amount in amounts {
+        let witness = wallet.new_witness().unwrap();
+        let output = Output::new(&Amount::from_f64(*amount).unwrap(), witness).unwrap();
+        wallet.scan_output(&output).unwrap();
+        outputs.push(output);
+    }
+
+    (wallet, outputs)
+}
+
+#[test]
+fn wallet_scan_output_succ() {
+    let (mut wallet, outputs) = funded_wallet(&[10.0]);
+
+    assert!(wallet.contains_coin(outputs[0].id));
+    assert_eq!(wallet.balance(), Amount::from_f64(10.0).unwrap());
+
+    let res = wallet.scan_output(&outputs[0]);
+    assert!(!res.unwrap())
+}
+
+#[test]
+fn wallet_scan_output_fail() {
+    let (mut wallet, _) = funded_wallet(&[]);
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let output = Output::new(&Amount::from_f64(10.0).unwrap(), witness).unwrap();
+
+    assert!(!wallet.scan_output(&output).unwrap());
+    assert_eq!(wallet.balance(), Amount::zero())
+}
+
+#[test]
+fn wallet_build_transaction_succ() {
+    let (mut wallet, outputs) = funded_wallet(&[10.0, 5.0, 3.0]);
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let targets = vec![(witness, Amount::from_f64(12.0).unwrap())];
+    let fee = FeePolicy::fixed(&Amount::from_f64(1.0).unwrap());
+
+    let tx = wallet.build_transaction(&targets, &fee).unwrap();
+    assert_eq!(tx.inputs_length, 2);
+    assert_eq!(tx.outputs_length, 2);
+    assert_eq!(tx.outputs[0].witness, witness);
+    assert_eq!(tx.outputs[1].amount, Amount::from_f64(2.0).unwrap());
+    assert!(wallet.keychain().contains(tx.outputs[1].witness).unwrap());
+    assert_eq!(wallet.keychain().len(), 4);
+
+    let count = wallet.scan_transaction(&tx).unwrap();
+    assert_eq!(count, 1);
+    assert!(!wallet.contains_coin(outputs[0].id));
+    assert!(!wallet.contains_coin(outputs[1].id));
+    assert!(wallet.contains_coin(outputs[2].id));
+    assert_eq!(wallet.balance(), Amount::from_f64(5.0).unwrap());
+
+    // without change no witness is derived
+    let targets = vec![(witness, Amount::from_f64(4.0).unwrap())];
+
+    let tx = wallet.build_transaction(&targets, &fee).unwrap();
+    assert_eq!(tx.outputs_length, 1);
+    assert_eq!(wallet.keychain().len(), 4)
+}
+
+#[test]
+fn wallet_build_transaction_fail() {
+    let (mut wallet, _) = funded_wallet(&[10.0, 5.0]);
+
+    let witness = ZKPWitness::new(Scalar::random()).unwrap();
+    let targets = vec![(witness, Amount::from_f64(15.0).unwrap())];
+    let fee = FeePolicy::fixed(&Amount::from_f64(1.0).unwrap());
+
+    let res = wallet.build_transaction(&targets, &fee);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InsufficientFunds);
+
+    let res = wallet.build_transaction(&[], &fee);
+    assert!(res.is_err())
+}
+
+#[test]
+fn wallet_scan_transaction_succ() {
+    let (mut sender, _) = funded_wallet(&[10.0]);
+    let mut receiver = Wallet::random(NetworkType::RegTest);
+
+    let witness = receiver.new_witness().unwrap();
+    let targets = vec![(witness, Amount::from_f64(7.0).unwrap())];
+    let fee = FeePolicy::fixed(&Amount::from_f64(1.0).unwrap());
+
+    let tx = sender.build_transaction(&targets, &fee).unwrap();
+
+    let count = receiver.scan_transaction(&tx).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(receiver.balance(), Amount::from_f64(7.0).unwrap());
+
+    sender.scan_transaction(&tx).unwrap();
+    assert_eq!(sender.balance(), Amount::from_f64(2.0).unwrap())
+}
+
+#[test]
+fn wallet_scan_transaction_fail() {
+    let (mut sender, _) = funded_wallet(&[10.0]);
+    let mut receiver = Wallet::random(NetworkType::TestNet);
+
+    let witness = receiver.new_witness().unwrap();
+    let targets = vec![(witness, Amount::from_f64(7.0).unwrap())];
+    let fee = FeePolicy::fixed(&Amount::from_f64(1.0).unwrap());
+
+    let tx = sender.build_transaction(&targets, &fee).unwrap();
+
+    let res = receiver.scan_transaction(&tx);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidNetwork)
+}
+
+#[test]
+fn wallet_match_filter_succ() {
+    let (mut sender, _) = funded_wallet(&[10.0]);
+    let mut receiver = Wallet::random(NetworkType::RegTest);
+
+    let witness = receiver.new_witness().unwrap();
+    let targets = vec![(witness, Amount::from_f64(7.0).unwrap())];
+    let fee = FeePolicy::fixed(&Amount::from_f64(1.0).unwrap());
+
+    let tx = sender.build_transaction(&targets, &fee).unwrap();
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = new_block_header(&block, &[tx.clone()]);
+    let block_filter = BlockFilter::new(&block_header, &block, &[tx]).unwrap();
+
+    assert!(receiver.match_filter(&block_filter).unwrap());
+    assert!(sender.match_filter(&block_filter).unwrap())
+}
+
+#[test]
+fn wallet_match_filter_fail() {
+    let (mut sender, _) = funded_wallet(&[10.0]);
+    let mut receiver = Wallet::random(NetworkType::RegTest);
+
+    let witness = receiver.new_witness().unwrap();
+    let targets = vec![(witness, Amount::from_f64(7.0).unwrap())];
+    let fee = FeePolicy::fixed(&Amount::from_f64(1.0).unwrap());
+
+    let tx = sender.build_transaction(&targets, &fee).unwrap();
+    let block = Block::new(NetworkType::RegTest, &[tx.clone()]).unwrap();
+    let block_header = new_block_header(&block, &[tx.clone()]);
+    let block_filter = BlockFilter::new(&block_header, &block, &[tx]).unwrap();
+
+    let (other, _) = funded_wallet(&[3.0]);
+    assert!(!other.match_filter(&block_filter).unwrap());
+
+    let empty = Wallet::random(NetworkType::RegTest);
+    assert!(!empty.match_filter(&block_filter).unwrap())
+}